@@ -86,7 +86,7 @@ impl<T: Message> StdioSystem for Const<T> {
     fn build_system(config: StdioConfig) -> Result<System, StdioError> {
         use crate::{CoreBlocks, IoBlocks, SysBlocks, SystemBuilding};
 
-        let Some(value) = config.params.get("value").map(String::clone) else {
+        let Some(value) = config.params.get("value").cloned() else {
             return Err(StdioError::MissingParameter("value"))?;
         };
 
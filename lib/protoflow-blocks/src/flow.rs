@@ -1,3 +1,41 @@
 // This is free and unencumbered software released into the public domain.
 
-pub trait FlowBlocks {}
+use crate::prelude::{String, Vec};
+use protoflow_core::Message;
+
+pub trait FlowBlocks {
+    fn broadcast<T: Message + 'static, const N: usize>(&self) -> Broadcast<T, N>;
+
+    fn merge<T: Message + 'static, const N: usize>(&self) -> Merge<T, N>;
+
+    fn round_robin<T: Message + 'static, const N: usize>(&self) -> RoundRobin<T, N>;
+
+    fn route<T: Message + 'static>(&self, routes: Vec<(String, RoutePredicate)>) -> Route<T>;
+
+    fn select<T: Message + 'static, const N: usize>(&self) -> Select<T, N>;
+
+    fn switch<T: Message + 'static, const N: usize>(&self) -> Switch<T, N>;
+
+    fn zip<A: Message + 'static, B: Message + 'static>(&self) -> Zip<A, B>;
+}
+
+mod broadcast;
+pub use broadcast::*;
+
+mod merge;
+pub use merge::*;
+
+mod round_robin;
+pub use round_robin::*;
+
+mod route;
+pub use route::*;
+
+mod select;
+pub use select::*;
+
+mod switch;
+pub use switch::*;
+
+mod zip;
+pub use zip::*;
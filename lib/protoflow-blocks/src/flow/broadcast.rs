@@ -0,0 +1,116 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{
+    Block, BlockResult, BlockRuntime, InputPort, Message, MessageSender, OutputPorts,
+};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that sends a copy of each message it receives to all of its
+/// connected outputs.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/broadcast.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/broadcast.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::{prelude::Bytes, *};
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let broadcaster = s.broadcast::<Bytes, 2>();
+///     let stdout = s.write_stdout();
+///     let stderr = s.write_stderr();
+///     s.connect(&stdin.output, &broadcaster.input);
+///     s.connect(&broadcaster.outputs[0], &stdout.input);
+///     s.connect(&broadcaster.outputs[1], &stderr.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Broadcast
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Broadcast<T: Message, const N: usize> {
+    /// The input message stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output message streams.
+    #[output]
+    pub outputs: OutputPorts<T, N>,
+}
+
+impl<T: Message, const N: usize> Broadcast<T, N> {
+    pub fn new(input: InputPort<T>, outputs: OutputPorts<T, N>) -> Self {
+        Self { input, outputs }
+    }
+}
+
+impl<T: Message, const N: usize> Block for Broadcast<T, N> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.input)?;
+
+        while let Some(message) = self.input.recv()? {
+            self.outputs.send(&message)?;
+        }
+
+        self.input.close()?;
+        self.outputs.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static, const N: usize>
+    StdioSystem for Broadcast<T, N>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let broadcaster = s.broadcast::<T, N>();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &broadcaster.input);
+            // Send the first copy to stdout, and any others to stderr:
+            for (index, output) in broadcaster.outputs.iter().enumerate() {
+                let message_encoder = s.encode_with::<T>(config.encoding);
+                s.connect(output, &message_encoder.input);
+                if index == 0 {
+                    let stdout = s.write_stdout();
+                    s.connect(&message_encoder.output, &stdout.input);
+                } else {
+                    let stderr = s.write_stderr();
+                    s.connect(&message_encoder.output, &stderr.input);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Broadcast;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Broadcast::<i32, 2>::new(s.input(), s.outputs()));
+        });
+    }
+}
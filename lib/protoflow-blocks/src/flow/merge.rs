@@ -0,0 +1,116 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPorts, Message, OutputPort};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that merges the messages from several input streams into one
+/// output stream.
+///
+/// Messages are passed through in the order they arrive, taking turns
+/// between the inputs so that a busy input can't starve the others. The
+/// output stream ends once all the input streams have ended.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/merge.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/merge.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let hello = s.const_string("Hello");
+///     let world = s.const_string("World");
+///     let merger = s.merge::<String, 2>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&hello.output, &merger.inputs[0]);
+///     s.connect(&world.output, &merger.inputs[1]);
+///     s.connect(&merger.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Merge
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Merge<T: Message, const N: usize> {
+    /// The input message streams.
+    #[input]
+    pub inputs: InputPorts<T, N>,
+
+    /// The output message stream.
+    #[output]
+    pub output: OutputPort<T>,
+}
+
+impl<T: Message, const N: usize> Merge<T, N> {
+    pub fn new(inputs: InputPorts<T, N>, output: OutputPort<T>) -> Self {
+        Self { inputs, output }
+    }
+}
+
+impl<T: Message, const N: usize> Block for Merge<T, N> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.output)?;
+
+        let mut next = 0;
+        while let Some((index, message)) = self.inputs.recv_from(next)? {
+            self.output.send(&message)?;
+            next = index + 1;
+        }
+
+        self.inputs.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static, const N: usize>
+    StdioSystem for Merge<T, N>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let merger = s.merge::<T, N>();
+            let message_encoder = s.encode_with::<T>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            if let Some(input) = merger.inputs.first() {
+                s.connect(&message_decoder.output, input);
+            }
+            s.connect(&merger.output, &message_encoder.input);
+            s.connect(&message_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Merge;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Merge::<i32, 2>::new(s.inputs(), s.output()));
+        });
+    }
+}
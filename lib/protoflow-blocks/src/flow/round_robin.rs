@@ -0,0 +1,131 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{
+    Block, BlockResult, BlockRuntime, InputPort, Message, OutputPorts, Port, PortError,
+};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that distributes the messages it receives over its connected
+/// outputs, sending each message to the next output in turn.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/round_robin.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/round_robin.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines();
+///     let distributor = s.round_robin::<String, 2>();
+///     let stdout_encoder = s.encode_lines();
+///     let stderr_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     let stderr = s.write_stderr();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &distributor.input);
+///     s.connect(&distributor.outputs[0], &stdout_encoder.input);
+///     s.connect(&distributor.outputs[1], &stderr_encoder.input);
+///     s.connect(&stdout_encoder.output, &stdout.input);
+///     s.connect(&stderr_encoder.output, &stderr.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute RoundRobin
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct RoundRobin<T: Message, const N: usize> {
+    /// The input message stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output message streams.
+    #[output]
+    pub outputs: OutputPorts<T, N>,
+}
+
+impl<T: Message, const N: usize> RoundRobin<T, N> {
+    pub fn new(input: InputPort<T>, outputs: OutputPorts<T, N>) -> Self {
+        Self { input, outputs }
+    }
+}
+
+impl<T: Message, const N: usize> Block for RoundRobin<T, N> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.input)?;
+
+        let mut next = 0;
+        while let Some(message) = self.input.recv()? {
+            let Some(index) = (0..N)
+                .map(|offset| (next + offset) % N)
+                .find(|&index| self.outputs[index].is_connected())
+            else {
+                return Err(PortError::Disconnected)?;
+            };
+            self.outputs[index].send(&message)?;
+            next = index + 1;
+        }
+
+        self.input.close()?;
+        self.outputs.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static, const N: usize>
+    StdioSystem for RoundRobin<T, N>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let distributor = s.round_robin::<T, N>();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &distributor.input);
+            // Send the messages for the first output to stdout, and any
+            // others to stderr:
+            for (index, output) in distributor.outputs.iter().enumerate() {
+                let message_encoder = s.encode_with::<T>(config.encoding);
+                s.connect(output, &message_encoder.input);
+                if index == 0 {
+                    let stdout = s.write_stdout();
+                    s.connect(&message_encoder.output, &stdout.input);
+                } else {
+                    let stderr = s.write_stderr();
+                    s.connect(&message_encoder.output, &stderr.input);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoundRobin;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(RoundRobin::<i32, 2>::new(s.input(), s.outputs()));
+        });
+    }
+}
@@ -0,0 +1,194 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{String, Vec},
+    StdioConfig, StdioError, StdioSystem, System,
+};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPort, Message, OutputPort, Port};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+mod predicate;
+pub use predicate::*;
+
+/// A block that routes each message it receives to the output of the first
+/// route whose predicate it satisfies, or else to the default output.
+///
+/// Each route has a name and a [`RoutePredicate`] on the fields of the
+/// message, such as `value matches "^ERROR"` or `$2 in 10..20`. Messages
+/// routed to an output that isn't connected are dropped.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/route.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/route.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines();
+///     let router = s.route::<String>(vec![
+///         ("errors".into(), "value matches \"^ERROR\"".parse().unwrap()),
+///     ]);
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &router.input);
+///     s.connect(router.output("errors").unwrap(), &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// Each parameter is a route, with the parameter name as the route name,
+/// and the routes are tried in the order of their names. The messages of
+/// all routes are written to stdout, and the other messages to stderr.
+///
+/// ```console
+/// $ protoflow execute Route errors='value matches "^ERROR"'
+/// ```
+///
+/// ## Configuring the block via SysML
+///
+/// Each attribute of the block usage is a route, named after the attribute
+/// and typed by the route's predicate, quoted as a name:
+///
+/// ```sysml
+/// block router : Protoflow::Route {
+///     attribute errors : 'value matches "^ERROR"';
+/// }
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Route<T: Message> {
+    /// The input message stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output message streams, one for each route.
+    #[output]
+    pub outputs: Vec<OutputPort<T>>,
+
+    /// The output stream of the messages that match no route.
+    #[output]
+    pub default: OutputPort<T>,
+
+    /// A parameter for the routes, as names and predicates.
+    #[parameter]
+    pub routes: Vec<(String, RoutePredicate)>,
+}
+
+impl<T: Message> Route<T> {
+    pub fn new(input: InputPort<T>, default: OutputPort<T>) -> Self {
+        Self::with_params(input, Vec::new(), default, Vec::new())
+    }
+
+    /// Instantiates the block with an output for each route.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there aren't as many outputs as routes.
+    pub fn with_params(
+        input: InputPort<T>,
+        outputs: Vec<OutputPort<T>>,
+        default: OutputPort<T>,
+        routes: Vec<(String, RoutePredicate)>,
+    ) -> Self {
+        assert_eq!(outputs.len(), routes.len(), "one output per route");
+        Self {
+            input,
+            outputs,
+            default,
+            routes,
+        }
+    }
+
+    /// Returns the output port of the route with the given name.
+    pub fn output(&self, name: &str) -> Option<&OutputPort<T>> {
+        let index = self.routes.iter().position(|(route, _)| route == name)?;
+        self.outputs.get(index)
+    }
+}
+
+impl<T: Message> Block for Route<T> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.input)?;
+
+        while let Some(message) = self.input.recv()? {
+            let encoded = message.encode_to_vec();
+            let output = self
+                .routes
+                .iter()
+                .position(|(_, predicate)| predicate.matches_encoded(&encoded))
+                .map_or(&self.default, |index| &self.outputs[index]);
+            if output.is_connected() {
+                output.send(&message)?;
+            }
+        }
+
+        self.input.close()?;
+        for output in self.outputs.iter_mut() {
+            output.close()?;
+        }
+        self.default.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static> StdioSystem
+    for Route<T>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        let mut routes = Vec::new();
+        for (name, predicate) in config.params.iter() {
+            let Ok(predicate) = predicate.parse::<RoutePredicate>() else {
+                return Err(StdioError::InvalidParameter("route"))?;
+            };
+            routes.push((name.clone(), predicate));
+        }
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let router = s.route::<T>(routes);
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &router.input);
+            for output in router.outputs.iter() {
+                let message_encoder = s.encode_with::<T>(config.encoding);
+                let stdout = s.write_stdout();
+                s.connect(output, &message_encoder.input);
+                s.connect(&message_encoder.output, &stdout.input);
+            }
+            let message_encoder = s.encode_with::<T>(config.encoding);
+            let stderr = s.write_stderr();
+            s.connect(&router.default, &message_encoder.input);
+            s.connect(&message_encoder.output, &stderr.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Route;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Route::<i32>::new(s.input(), s.output()));
+        });
+    }
+}
@@ -0,0 +1,714 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::prelude::{bytes::Buf, fmt, format, prost, vec, Box, FromStr, String, ToString, Vec};
+use core::cmp::Ordering;
+use prost::encoding::{decode_key, decode_varint, WireType};
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// A predicate on the fields of a message, as used by [`Route`](crate::Route).
+///
+/// Predicates are parsed from expressions such as:
+///
+/// ```text
+/// value == "hello"
+/// value matches "^(ERROR|WARN):"
+/// $2 in 10..20 and not $3.1 == "test"
+/// ($1 > 0.5 or $4 != 0) && !($5:sint64 <= -1)
+/// ```
+///
+/// Fields are referred to by their numbers, as in `$2`, with `$2.1` referring
+/// to field 1 of the message in field 2. `value` is short for `$1`, which is
+/// where the wrapper messages for scalars (such as `String` and `u64`) hold
+/// their value.
+///
+/// Fields are compared with:
+///
+/// - `==`, `!=`, `<`, `<=`, `>`, `>=` and a number or a double-quoted string;
+/// - `in` and a range of numbers, such as `1..10` or `1..=9`;
+/// - `matches` and a double-quoted regular expression (requires the `std`
+///   feature).
+///
+/// Comparisons are combined with `and` (or `&&`), `or` (or `||`), `not` (or
+/// `!`) and parentheses, and `true` and `false` are predicates too.
+///
+/// As there are no message descriptors to go by, fields are interpreted from
+/// their wire types, unless their Protobuf type is stated after a colon, as
+/// in `$2:uint64` or `value:fixed32`. By default, varints are read as
+/// `int64`, fixed-width numbers as `double` or `float`, and length-delimited
+/// fields as UTF-8 strings or nested messages. So the type must be stated
+/// for `uint64` values over `i64::MAX`, for the zigzag-encoded `sint32` and
+/// `sint64`, and for `fixed32`, `fixed64`, `sfixed32` and `sfixed64`, which
+/// would otherwise be misread. The types are `int32`, `int64`, `uint32`,
+/// `uint64`, `sint32`, `sint64`, `bool`, `fixed32`, `fixed64`, `sfixed32`,
+/// `sfixed64`, `float`, `double` and `string`.
+///
+/// Missing fields have the default value of `0` or `""`, and a comparison of
+/// mismatched types is false, as with a field whose wire type doesn't match
+/// its stated type.
+#[derive(Clone)]
+pub struct RoutePredicate {
+    source: String,
+    expr: Expr,
+}
+
+/// An error in the syntax of a [`RoutePredicate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoutePredicateError(pub String);
+
+impl RoutePredicate {
+    /// Checks whether a message satisfies the predicate.
+    pub fn matches<M: prost::Message>(&self, message: &M) -> bool {
+        self.matches_encoded(&message.encode_to_vec())
+    }
+
+    /// Checks whether an encoded message satisfies the predicate.
+    pub fn matches_encoded(&self, message: &[u8]) -> bool {
+        self.expr.eval(message)
+    }
+}
+
+impl FromStr for RoutePredicate {
+    type Err = RoutePredicateError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(RoutePredicateError(format!("unexpected {}", token)));
+        }
+        Ok(Self {
+            source: input.to_string(),
+            expr,
+        })
+    }
+}
+
+impl fmt::Debug for RoutePredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RoutePredicate").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for RoutePredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl fmt::Display for RoutePredicateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid predicate: {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RoutePredicateError {}
+
+#[derive(Clone)]
+enum Expr {
+    Bool(bool),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(FieldRef, CompareOp, Literal),
+    InRange(FieldRef, Number, Number, bool),
+    #[cfg(feature = "std")]
+    Matches(FieldRef, regex::Regex),
+}
+
+/// A field referred to by its path, and by its type if stated.
+#[derive(Clone, Debug, PartialEq)]
+struct FieldRef {
+    path: Vec<u32>,
+    r#type: Option<FieldType>,
+}
+
+/// The Protobuf type of a field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FieldType {
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Bool,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Float,
+    Double,
+    String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Literal {
+    Number(Number),
+    String(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Number {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+impl Expr {
+    fn eval(&self, message: &[u8]) -> bool {
+        match self {
+            Self::Bool(value) => *value,
+            Self::Not(expr) => !expr.eval(message),
+            Self::And(lhs, rhs) => lhs.eval(message) && rhs.eval(message),
+            Self::Or(lhs, rhs) => lhs.eval(message) || rhs.eval(message),
+            Self::Compare(field, op, Literal::Number(number)) => {
+                let Some(value) = field.number(message) else {
+                    return false;
+                };
+                value
+                    .compare(number)
+                    .is_some_and(|ordering| op.holds(ordering))
+            }
+            Self::Compare(field, op, Literal::String(string)) => {
+                let Some(value) = field.string(message) else {
+                    return false;
+                };
+                op.holds(value.cmp(string.as_str()))
+            }
+            Self::InRange(field, start, end, inclusive) => {
+                let Some(value) = field.number(message) else {
+                    return false;
+                };
+                let above_start = value.compare(start).is_some_and(Ordering::is_ge);
+                let below_end = match inclusive {
+                    true => value.compare(end).is_some_and(Ordering::is_le),
+                    false => value.compare(end).is_some_and(Ordering::is_lt),
+                };
+                above_start && below_end
+            }
+            #[cfg(feature = "std")]
+            Self::Matches(field, regex) => {
+                let Some(value) = field.string(message) else {
+                    return false;
+                };
+                regex.is_match(value)
+            }
+        }
+    }
+}
+
+impl CompareOp {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering.is_eq(),
+            Self::Ne => ordering.is_ne(),
+            Self::Lt => ordering.is_lt(),
+            Self::Le => ordering.is_le(),
+            Self::Gt => ordering.is_gt(),
+            Self::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+impl FieldRef {
+    /// Reads the field of an encoded message as a number, if it is one.
+    fn number(&self, message: &[u8]) -> Option<Number> {
+        lookup(message, &self.path)?.number(self.r#type)
+    }
+
+    /// Reads the field of an encoded message as a string, if it is one.
+    fn string<'a>(&self, message: &'a [u8]) -> Option<&'a str> {
+        match self.r#type {
+            None | Some(FieldType::String) => lookup(message, &self.path)?.string(),
+            Some(_) => None,
+        }
+    }
+}
+
+impl FieldType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "int32" => Self::Int32,
+            "int64" => Self::Int64,
+            "uint32" => Self::Uint32,
+            "uint64" => Self::Uint64,
+            "sint32" => Self::Sint32,
+            "sint64" => Self::Sint64,
+            "bool" => Self::Bool,
+            "fixed32" => Self::Fixed32,
+            "fixed64" => Self::Fixed64,
+            "sfixed32" => Self::Sfixed32,
+            "sfixed64" => Self::Sfixed64,
+            "float" => Self::Float,
+            "double" => Self::Double,
+            "string" => Self::String,
+            _ => return None,
+        })
+    }
+
+    fn is_string(self) -> bool {
+        self == Self::String
+    }
+}
+
+impl Number {
+    fn compare(&self, other: &Number) -> Option<Ordering> {
+        match (self.to_i128(), other.to_i128()) {
+            (Some(lhs), Some(rhs)) => Some(lhs.cmp(&rhs)),
+            _ => self.to_f64().partial_cmp(&other.to_f64()),
+        }
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        match self {
+            Self::Int(value) => Some(value.into()),
+            Self::UInt(value) => Some(value.into()),
+            Self::Float(_) => None,
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        match self {
+            Self::Int(value) => value as f64,
+            Self::UInt(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+}
+
+/// The raw value of a field, as found on the wire.
+#[derive(Clone, Copy, Debug)]
+enum Field<'a> {
+    Missing,
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32(u32),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Field<'a> {
+    /// Reads the field as a number of the given type, or else as per its
+    /// wire type.
+    fn number(self, r#type: Option<FieldType>) -> Option<Number> {
+        use FieldType::*;
+        Some(match (self, r#type) {
+            (_, Some(String)) => return None,
+            (Self::Missing, Some(Uint32 | Uint64 | Fixed32 | Fixed64)) => Number::UInt(0),
+            (Self::Missing, Some(Float | Double)) => Number::Float(0.0),
+            (Self::Missing, _) => Number::Int(0),
+            (Self::Varint(value), None | Some(Int64)) => Number::Int(value as i64),
+            (Self::Varint(value), Some(Int32)) => Number::Int((value as i32).into()),
+            (Self::Varint(value), Some(Uint32)) => Number::UInt((value as u32).into()),
+            (Self::Varint(value), Some(Uint64)) => Number::UInt(value),
+            (Self::Varint(value), Some(Sint32 | Sint64)) => {
+                Number::Int((value >> 1) as i64 ^ -((value & 1) as i64)) // zigzag
+            }
+            (Self::Varint(value), Some(Bool)) => Number::Int((value != 0).into()),
+            (Self::Fixed64(value), None | Some(Double)) => Number::Float(f64::from_bits(value)),
+            (Self::Fixed64(value), Some(Fixed64)) => Number::UInt(value),
+            (Self::Fixed64(value), Some(Sfixed64)) => Number::Int(value as i64),
+            (Self::Fixed32(value), None | Some(Float)) => {
+                Number::Float(f32::from_bits(value).into())
+            }
+            (Self::Fixed32(value), Some(Fixed32)) => Number::UInt(value.into()),
+            (Self::Fixed32(value), Some(Sfixed32)) => Number::Int((value as i32).into()),
+            _ => return None, // a mismatched wire type
+        })
+    }
+
+    fn string(self) -> Option<&'a str> {
+        match self {
+            Self::Missing => Some(""),
+            Self::Bytes(bytes) => core::str::from_utf8(bytes).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Looks up a field by its path in an encoded message.
+///
+/// Returns `None` if the message is malformed.
+fn lookup<'a>(message: &'a [u8], path: &[u32]) -> Option<Field<'a>> {
+    let (&number, path) = path.split_first()?;
+    let field = find_field(message, number)?;
+    match (field, path.is_empty()) {
+        (field, true) => Some(field),
+        (Field::Bytes(message), false) => lookup(message, path),
+        (Field::Missing, false) => Some(Field::Missing),
+        (_, false) => None,
+    }
+}
+
+/// Finds the last occurrence of a field in an encoded message, as that is
+/// the one that counts for scalar fields.
+fn find_field(mut message: &[u8], number: u32) -> Option<Field<'_>> {
+    let mut field = Field::Missing;
+    while message.has_remaining() {
+        let (tag, wire_type) = decode_key(&mut message).ok()?;
+        let value = match wire_type {
+            WireType::Varint => Field::Varint(decode_varint(&mut message).ok()?),
+            WireType::SixtyFourBit if message.remaining() >= 8 => {
+                Field::Fixed64(message.get_u64_le())
+            }
+            WireType::ThirtyTwoBit if message.remaining() >= 4 => {
+                Field::Fixed32(message.get_u32_le())
+            }
+            WireType::LengthDelimited => {
+                let length = usize::try_from(decode_varint(&mut message).ok()?).ok()?;
+                if message.remaining() < length {
+                    return None;
+                }
+                let (bytes, rest) = message.split_at(length);
+                message = rest;
+                Field::Bytes(bytes)
+            }
+            _ => return None, // truncated, or a deprecated group
+        };
+        if tag == number {
+            field = value;
+        }
+    }
+    Some(field)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Field(Vec<u32>),
+    Type(FieldType),
+    Number(Number),
+    String(String),
+    Op(CompareOp),
+    Range(bool),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Word(word) => write!(f, "`{}`", word),
+            Self::Field(path) => {
+                let path: Vec<String> = path.iter().map(ToString::to_string).collect();
+                write!(f, "`${}`", path.join("."))
+            }
+            Self::Type(_) => write!(f, "field type"),
+            Self::Number(_) => write!(f, "number"),
+            Self::String(_) => write!(f, "string"),
+            Self::Op(_) => write!(f, "comparison"),
+            Self::Range(_) => write!(f, "range"),
+            Self::And => write!(f, "`and`"),
+            Self::Or => write!(f, "`or`"),
+            Self::Not => write!(f, "`not`"),
+            Self::Open => write!(f, "`(`"),
+            Self::Close => write!(f, "`)`"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, RoutePredicateError> {
+    let error = |message: &str| Err(RoutePredicateError(message.to_string()));
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+        let token = match (c, next) {
+            (c, _) if c.is_whitespace() => continue,
+            ('(', _) => Token::Open,
+            (')', _) => Token::Close,
+            ('=', Some('=')) => {
+                chars.next();
+                Token::Op(CompareOp::Eq)
+            }
+            ('!', Some('=')) => {
+                chars.next();
+                Token::Op(CompareOp::Ne)
+            }
+            ('<', Some('=')) => {
+                chars.next();
+                Token::Op(CompareOp::Le)
+            }
+            ('>', Some('=')) => {
+                chars.next();
+                Token::Op(CompareOp::Ge)
+            }
+            ('<', _) => Token::Op(CompareOp::Lt),
+            ('>', _) => Token::Op(CompareOp::Gt),
+            ('!', _) => Token::Not,
+            ('&', Some('&')) => {
+                chars.next();
+                Token::And
+            }
+            ('|', Some('|')) => {
+                chars.next();
+                Token::Or
+            }
+            ('.', Some('.')) => {
+                chars.next();
+                let inclusive = chars.next_if(|(_, c)| *c == '=').is_some();
+                Token::Range(inclusive)
+            }
+            (':', Some(c)) if c.is_alphabetic() => {
+                let mut name = String::new();
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric()) {
+                    name.push(c);
+                }
+                match FieldType::parse(&name) {
+                    Some(r#type) => Token::Type(r#type),
+                    None => return Err(RoutePredicateError(format!("unknown type `{}`", name))),
+                }
+            }
+            ('"', _) => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        None => return error("unterminated string"),
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c @ ('"' | '\\'))) => string.push(c),
+                            Some((_, 'n')) => string.push('\n'),
+                            Some((_, 't')) => string.push('\t'),
+                            // Keep other escapes, as for regular expressions:
+                            Some((_, c)) => {
+                                string.push('\\');
+                                string.push(c);
+                            }
+                            None => return error("unterminated string"),
+                        },
+                        Some((_, c)) => string.push(c),
+                    }
+                }
+                Token::String(string)
+            }
+            ('$', _) => {
+                let mut path = vec![];
+                loop {
+                    let mut digits = String::new();
+                    while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                        digits.push(c);
+                    }
+                    match digits.parse::<u32>() {
+                        Ok(number) if number > 0 => path.push(number),
+                        _ => return error("invalid field number"),
+                    }
+                    // Continue with a nested field, but not with a range:
+                    let mut lookahead = chars.clone();
+                    match (lookahead.next(), lookahead.next()) {
+                        (Some((_, '.')), Some((_, c))) if c.is_ascii_digit() => {
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                Token::Field(path)
+            }
+            (c, _)
+                if c.is_ascii_digit() || (c == '-' && next.is_some_and(|c| c.is_ascii_digit())) =>
+            {
+                let mut end = start + c.len_utf8();
+                let mut is_float = false;
+                loop {
+                    let mut lookahead = chars.clone();
+                    match (lookahead.next(), lookahead.next()) {
+                        (Some((i, c)), _) if c.is_ascii_digit() => end = i + 1,
+                        // A decimal point, but not a range:
+                        (Some((i, '.')), Some((_, c))) if c.is_ascii_digit() && !is_float => {
+                            is_float = true;
+                            end = i + 1;
+                        }
+                        _ => break,
+                    }
+                    chars.next();
+                }
+                let literal = &input[start..end];
+                let number = match is_float {
+                    false => (literal.parse().map(Number::Int))
+                        .or_else(|_| literal.parse().map(Number::UInt))
+                        .ok(),
+                    true => literal.parse().map(Number::Float).ok(),
+                };
+                match number {
+                    Some(number) => Token::Number(number),
+                    None => return error("invalid number"),
+                }
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some((_, c)) = chars.next_if(|(_, c)| c.is_alphanumeric() || *c == '_') {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "value" => Token::Field(vec![1]),
+                    _ => Token::Word(word),
+                }
+            }
+            (c, _) => return Err(RoutePredicateError(format!("unexpected `{}`", c))),
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, RoutePredicateError> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token.ok_or_else(|| RoutePredicateError("unexpected end".to_string()))
+    }
+
+    fn accept(&mut self, token: &Token) -> bool {
+        let accepted = self.peek() == Some(token);
+        if accepted {
+            self.position += 1;
+        }
+        accepted
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, RoutePredicateError> {
+        let mut expr = self.parse_and()?;
+        while self.accept(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, RoutePredicateError> {
+        let mut expr = self.parse_not()?;
+        while self.accept(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, RoutePredicateError> {
+        if self.accept(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, RoutePredicateError> {
+        match self.next()? {
+            Token::Open => {
+                let expr = self.parse_or()?;
+                match self.next()? {
+                    Token::Close => Ok(expr),
+                    token => Err(RoutePredicateError(format!(
+                        "expected `)`, found {}",
+                        token
+                    ))),
+                }
+            }
+            Token::Word(word) if word == "true" => Ok(Expr::Bool(true)),
+            Token::Word(word) if word == "false" => Ok(Expr::Bool(false)),
+            Token::Field(path) => {
+                let r#type = match self.peek() {
+                    Some(Token::Type(r#type)) => Some(*r#type),
+                    _ => None,
+                };
+                if r#type.is_some() {
+                    self.position += 1;
+                }
+                self.parse_test(FieldRef { path, r#type })
+            }
+            token => Err(RoutePredicateError(format!("unexpected {}", token))),
+        }
+    }
+
+    fn parse_test(&mut self, field: FieldRef) -> Result<Expr, RoutePredicateError> {
+        let is_string = field.r#type.map(FieldType::is_string);
+        let mismatch = |message: &str| Err(RoutePredicateError(message.to_string()));
+        match self.next()? {
+            Token::Op(op) => match self.next()? {
+                Token::Number(_) if is_string == Some(true) => {
+                    mismatch("expected string for a string field")
+                }
+                Token::String(_) if is_string == Some(false) => {
+                    mismatch("expected number for a numeric field")
+                }
+                Token::Number(number) => Ok(Expr::Compare(field, op, Literal::Number(number))),
+                Token::String(string) => Ok(Expr::Compare(field, op, Literal::String(string))),
+                token => Err(RoutePredicateError(format!(
+                    "expected number or string, found {}",
+                    token
+                ))),
+            },
+            Token::Word(word) if word == "in" => {
+                if is_string == Some(true) {
+                    return mismatch("expected numeric field for `in`");
+                }
+                let start = self.parse_number()?;
+                let Token::Range(inclusive) = self.next()? else {
+                    return Err(RoutePredicateError("expected range".to_string()));
+                };
+                let end = self.parse_number()?;
+                Ok(Expr::InRange(field, start, end, inclusive))
+            }
+            Token::Word(word) if word == "matches" => {
+                if is_string == Some(false) {
+                    return mismatch("expected string field for `matches`");
+                }
+                let Token::String(pattern) = self.next()? else {
+                    return Err(RoutePredicateError("expected string".to_string()));
+                };
+                #[cfg(feature = "std")]
+                match regex::Regex::new(&pattern) {
+                    Ok(regex) => Ok(Expr::Matches(field, regex)),
+                    Err(error) => Err(RoutePredicateError(error.to_string())),
+                }
+                #[cfg(not(feature = "std"))]
+                {
+                    let _ = pattern;
+                    Err(RoutePredicateError(
+                        "`matches` requires the `std` feature".to_string(),
+                    ))
+                }
+            }
+            token => Err(RoutePredicateError(format!(
+                "expected comparison, `in` or `matches`, found {}",
+                token
+            ))),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Number, RoutePredicateError> {
+        match self.next()? {
+            Token::Number(number) => Ok(number),
+            token => Err(RoutePredicateError(format!(
+                "expected number, found {}",
+                token
+            ))),
+        }
+    }
+}
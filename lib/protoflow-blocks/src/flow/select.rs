@@ -0,0 +1,166 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{
+    prelude::format, Block, BlockError, BlockResult, BlockRuntime, InputPort, InputPorts, Message,
+    OutputPort,
+};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that passes through messages from the input selected by an
+/// index.
+///
+/// For each index received, one message is received from the selected
+/// input and passed through, and once the index stream ends, the last
+/// index received keeps applying until the selected input ends. An index
+/// past the last input fails the block.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/select.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/select.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let hello = s.const_string("Hello");
+///     let world = s.const_string("World");
+///     let index = s.block(Const::<u64>::with_params(s.output(), 1));
+///     let selector = s.select::<String, 2>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&hello.output, &selector.inputs[0]);
+///     s.connect(&world.output, &selector.inputs[1]);
+///     s.connect(&index.output, &selector.index);
+///     s.connect(&selector.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Select index=0
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Select<T: Message, const N: usize> {
+    /// The index of the input to receive each message from.
+    #[input]
+    pub index: InputPort<u64>,
+
+    /// The input message streams.
+    #[input]
+    pub inputs: InputPorts<T, N>,
+
+    /// The output message stream.
+    #[output]
+    pub output: OutputPort<T>,
+}
+
+impl<T: Message, const N: usize> Select<T, N> {
+    pub fn new(index: InputPort<u64>, inputs: InputPorts<T, N>, output: OutputPort<T>) -> Self {
+        Self {
+            index,
+            inputs,
+            output,
+        }
+    }
+}
+
+impl<T: Message, const N: usize> Block for Select<T, N> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.index)?;
+
+        let mut index = None;
+        let mut indexing = true;
+        loop {
+            if indexing {
+                match self.index.recv()? {
+                    Some(next) => index = Some(next),
+                    None => indexing = false,
+                }
+            }
+            let Some(index) = index else {
+                break; // the index stream ended without any index
+            };
+            let Some(input) = usize::try_from(index)
+                .ok()
+                .and_then(|index| self.inputs.as_slice().get(index))
+            else {
+                return Err(BlockError::Other(format!("invalid index: {}", index)));
+            };
+            match input.recv()? {
+                Some(message) => self.output.send(&message)?,
+                None if indexing => continue, // the selected input has ended
+                None => break,
+            }
+        }
+
+        self.index.close()?;
+        self.inputs.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static, const N: usize>
+    StdioSystem for Select<T, N>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{Const, FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        let index = config
+            .params
+            .get("index")
+            .map(|v| v.as_str().parse::<u64>());
+        let index = match index {
+            None => return Err(StdioError::MissingParameter("index"))?,
+            Some(Err(_)) => return Err(StdioError::InvalidParameter("index"))?,
+            Some(Ok(index)) => index,
+        };
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let index_param = s.block(Const::<u64>::with_params(s.output(), index));
+            let selector = s.select::<T, N>();
+            let message_encoder = s.encode_with::<T>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            // Only the selected input is fed from stdin:
+            if let Some(input) = usize::try_from(index)
+                .ok()
+                .and_then(|index| selector.inputs.as_slice().get(index))
+            {
+                s.connect(&message_decoder.output, input);
+            }
+            s.connect(&index_param.output, &selector.index);
+            s.connect(&selector.output, &message_encoder.input);
+            s.connect(&message_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Select;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Select::<i32, 2>::new(s.input(), s.inputs(), s.output()));
+        });
+    }
+}
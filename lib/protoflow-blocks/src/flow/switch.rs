@@ -0,0 +1,164 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{
+    prelude::format, Block, BlockError, BlockResult, BlockRuntime, InputPort, Message, OutputPorts,
+};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that routes each message it receives to the output selected by
+/// an index.
+///
+/// An index is received for each message, and once the index stream ends,
+/// the last index received keeps applying to all further messages. An
+/// index past the last output fails the block.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/switch.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/switch.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::{prelude::Bytes, *};
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let index = s.block(Const::<u64>::with_params(s.output(), 1));
+///     let switch = s.switch::<Bytes, 2>();
+///     let stdout = s.write_stdout();
+///     let stderr = s.write_stderr();
+///     s.connect(&stdin.output, &switch.input);
+///     s.connect(&index.output, &switch.index);
+///     s.connect(&switch.outputs[0], &stdout.input);
+///     s.connect(&switch.outputs[1], &stderr.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Switch index=1
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Switch<T: Message, const N: usize> {
+    /// The input message stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The index of the output to route each message to.
+    #[input]
+    pub index: InputPort<u64>,
+
+    /// The output message streams.
+    #[output]
+    pub outputs: OutputPorts<T, N>,
+}
+
+impl<T: Message, const N: usize> Switch<T, N> {
+    pub fn new(input: InputPort<T>, index: InputPort<u64>, outputs: OutputPorts<T, N>) -> Self {
+        Self {
+            input,
+            index,
+            outputs,
+        }
+    }
+}
+
+impl<T: Message, const N: usize> Block for Switch<T, N> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.index)?;
+
+        let mut index = None;
+        let mut indexing = true;
+        loop {
+            if indexing {
+                match self.index.recv()? {
+                    Some(next) => index = Some(next),
+                    None => indexing = false,
+                }
+            }
+            let Some(index) = index else {
+                break; // the index stream ended without any index
+            };
+            let Some(output) = usize::try_from(index)
+                .ok()
+                .and_then(|index| self.outputs.as_slice().get(index))
+            else {
+                return Err(BlockError::Other(format!("invalid index: {}", index)));
+            };
+            let Some(message) = self.input.recv()? else {
+                break;
+            };
+            output.send(&message)?;
+        }
+
+        self.input.close()?;
+        self.index.close()?;
+        self.outputs.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + crate::prelude::ToString + 'static, const N: usize>
+    StdioSystem for Switch<T, N>
+{
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{Const, FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        let index = config
+            .params
+            .get("index")
+            .map(|v| v.as_str().parse::<u64>());
+        let index = match index {
+            None => return Err(StdioError::MissingParameter("index"))?,
+            Some(Err(_)) => return Err(StdioError::InvalidParameter("index"))?,
+            Some(Ok(index)) => index,
+        };
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let index_param = s.block(Const::<u64>::with_params(s.output(), index));
+            let switch = s.switch::<T, N>();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &switch.input);
+            s.connect(&index_param.output, &switch.index);
+            // Send the messages for the first output to stdout, and any
+            // others to stderr:
+            for (index, output) in switch.outputs.iter().enumerate() {
+                let message_encoder = s.encode_with::<T>(config.encoding);
+                s.connect(output, &message_encoder.input);
+                if index == 0 {
+                    let stdout = s.write_stdout();
+                    s.connect(&message_encoder.output, &stdout.input);
+                } else {
+                    let stderr = s.write_stderr();
+                    s.connect(&message_encoder.output, &stderr.input);
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Switch;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Switch::<i32, 2>::new(s.input(), s.input(), s.outputs()));
+        });
+    }
+}
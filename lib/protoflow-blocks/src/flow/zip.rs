@@ -0,0 +1,187 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{
+    prelude::{
+        bytes::{Buf, BufMut},
+        fmt, prost,
+    },
+    Block, BlockResult, BlockRuntime, InputPort, Message, OutputPort,
+};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that pairs up the messages from two input streams.
+///
+/// The first messages of both streams are paired, then the second ones,
+/// and so on. The output stream ends as soon as either input stream ends,
+/// dropping any unpaired message.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/flow/zip.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/flow/zip.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let hello = s.const_string("Hello");
+///     let world = s.const_string("World");
+///     let zipper = s.zip::<String, String>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&hello.output, &zipper.first);
+///     s.connect(&world.output, &zipper.second);
+///     s.connect(&zipper.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Zip
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Zip<A: Message, B: Message> {
+    /// The input stream of the first messages of the pairs.
+    #[input]
+    pub first: InputPort<A>,
+
+    /// The input stream of the second messages of the pairs.
+    #[input]
+    pub second: InputPort<B>,
+
+    /// The output stream of message pairs.
+    #[output]
+    pub output: OutputPort<Pair<A, B>>,
+}
+
+impl<A: Message, B: Message> Zip<A, B> {
+    pub fn new(first: InputPort<A>, second: InputPort<B>, output: OutputPort<Pair<A, B>>) -> Self {
+        Self {
+            first,
+            second,
+            output,
+        }
+    }
+}
+
+impl<A: Message, B: Message> Block for Zip<A, B> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.first)?;
+        runtime.wait_for(&self.second)?;
+
+        while let Some(first) = self.first.recv()? {
+            let Some(second) = self.second.recv()? else {
+                break;
+            };
+            self.output.send(&Pair::new(first, second))?;
+        }
+
+        self.first.close()?;
+        self.second.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+/// A pair of messages, as sent by [`Zip`].
+///
+/// Pairs are encoded as a message with the two messages as fields 1 and 2,
+/// and are displayed as the two messages separated by a tab.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Pair<A, B> {
+    pub first: A,
+    pub second: B,
+}
+
+impl<A: Message, B: Message> Pair<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: Message, B: Message> prost::Message for Pair<A, B> {
+    fn encode_raw(&self, buf: &mut impl BufMut) {
+        prost::encoding::message::encode(1, &self.first, buf);
+        prost::encoding::message::encode(2, &self.second, buf);
+    }
+
+    fn merge_field(
+        &mut self,
+        tag: u32,
+        wire_type: prost::encoding::WireType,
+        buf: &mut impl Buf,
+        ctx: prost::encoding::DecodeContext,
+    ) -> Result<(), prost::DecodeError> {
+        match tag {
+            1 => prost::encoding::message::merge(wire_type, &mut self.first, buf, ctx),
+            2 => prost::encoding::message::merge(wire_type, &mut self.second, buf, ctx),
+            _ => prost::encoding::skip_field(wire_type, tag, buf, ctx),
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        prost::encoding::message::encoded_len(1, &self.first)
+            + prost::encoding::message::encoded_len(2, &self.second)
+    }
+
+    fn clear(&mut self) {
+        self.first.clear();
+        self.second.clear();
+    }
+}
+
+impl<A: Message, B: Message> Message for Pair<A, B> {}
+
+impl<A: fmt::Display, B: fmt::Display> fmt::Display for Pair<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}\t{}", self.first, self.second)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Message + crate::prelude::FromStr + fmt::Display + 'static> StdioSystem for Zip<T, T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            // Pair up consecutive messages from stdin:
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let distributor = s.round_robin::<T, 2>();
+            let zipper = s.zip::<T, T>();
+            let pair_encoder = s.encode_with::<Pair<T, T>>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &distributor.input);
+            s.connect(&distributor.outputs[0], &zipper.first);
+            s.connect(&distributor.outputs[1], &zipper.second);
+            s.connect(&zipper.output, &pair_encoder.input);
+            s.connect(&pair_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zip;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Zip::<i32, u64>::new(s.input(), s.input(), s.output()));
+        });
+    }
+}
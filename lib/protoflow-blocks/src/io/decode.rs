@@ -4,14 +4,23 @@
 
 use crate::{Encoding, StdioConfig, StdioError, StdioSystem, System};
 use protoflow_core::{
-    prelude::{Bytes, FromStr, String},
-    Block, BlockResult, BlockRuntime, InputPort, Message, OutputPort,
+    prelude::{
+        bytes::{Buf, BytesMut},
+        format, prost, Bytes, FromStr, String,
+    },
+    Block, BlockResult, BlockRuntime, DecodeError, InputPort, Message, OutputPort, PortResult,
 };
 use protoflow_derive::Block;
 use simple_mermaid::mermaid;
 
 /// A block that decodes `T` messages from a byte stream.
 ///
+/// The byte stream can be split into chunks anywhere, including in the
+/// middle of a message. Messages without a length prefix extend to the end
+/// of the stream, so only one is decoded. Malformed input, including a
+/// length prefix over the 2 GiB limit of Protobuf messages, fails the block
+/// with `PortError::DecodeFailed`.
+///
 /// # Block Diagram
 #[doc = mermaid!("../../doc/io/decode.mmd")]
 ///
@@ -76,20 +85,120 @@ impl<T: Message + FromStr> Decode<T> {
             encoding,
         }
     }
+
+    /// Decodes the next complete message in the buffer, if any.
+    fn decode_next(&self, buffer: &mut BytesMut) -> PortResult<Option<T>> {
+        use Encoding::*;
+        match self.encoding {
+            ProtobufWithLengthPrefix => {
+                let Some((prefix_len, message_len)) = decode_length_prefix(buffer)? else {
+                    return Ok(None); // incomplete length prefix
+                };
+                let Some(len) = prefix_len.checked_add(message_len) else {
+                    return Err(DecodeError::new("message too large").into());
+                };
+                if buffer.len() < len {
+                    return Ok(None); // incomplete message
+                }
+                buffer.advance(prefix_len);
+                Ok(Some(T::decode(buffer.split_to(message_len).freeze())?))
+            }
+            ProtobufWithoutLengthPrefix => Ok(None), // decoded at the end of the stream
+            TextWithNewlineSuffix => {
+                let Some(newline) = buffer.iter().position(|&byte| byte == b'\n') else {
+                    return Ok(None); // incomplete line
+                };
+                let line = buffer.split_to(newline + 1);
+                Ok(Some(decode_line(&line[..newline])?))
+            }
+        }
+    }
+
+    /// Decodes what remains in the buffer at the end of the stream.
+    fn decode_rest(&self, buffer: BytesMut) -> PortResult<Option<T>> {
+        use Encoding::*;
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+        match self.encoding {
+            ProtobufWithLengthPrefix => Err(DecodeError::new("truncated message").into()),
+            ProtobufWithoutLengthPrefix => Ok(Some(T::decode(buffer.freeze())?)),
+            TextWithNewlineSuffix => Ok(Some(decode_line(&buffer)?)), // a final line without a newline
+        }
+    }
+}
+
+/// Decodes the varint length prefix at the start of the buffer, returning
+/// the lengths of the prefix and of the message, or `None` if the prefix is
+/// incomplete.
+fn decode_length_prefix(buffer: &[u8]) -> PortResult<Option<(usize, usize)>> {
+    const MAX_PREFIX_LEN: usize = 10;
+    /// Protobuf messages can't exceed 2 GiB.
+    const MAX_MESSAGE_LEN: usize = i32::MAX as usize;
+    match buffer
+        .iter()
+        .take(MAX_PREFIX_LEN)
+        .position(|&byte| byte < 0x80)
+    {
+        Some(end) => match prost::decode_length_delimiter(&buffer[..=end])? {
+            message_len if message_len > MAX_MESSAGE_LEN => {
+                Err(DecodeError::new("message too large").into())
+            }
+            message_len => Ok(Some((end + 1, message_len))),
+        },
+        None if buffer.len() < MAX_PREFIX_LEN => Ok(None),
+        None => Err(DecodeError::new("invalid length prefix").into()),
+    }
+}
+
+fn decode_line<T: FromStr>(line: &[u8]) -> PortResult<T> {
+    let line = core::str::from_utf8(line).map_err(|_| DecodeError::new("invalid UTF-8"))?;
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    T::from_str(line).map_err(|_| DecodeError::new(format!("invalid message: {:?}", line)).into())
 }
 
 impl<T: Message + FromStr> Block for Decode<T> {
-    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
-        unimplemented!() // TODO
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.input)?;
+
+        let mut buffer = BytesMut::new();
+        while let Some(chunk) = self.input.recv()? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(message) = self.decode_next(&mut buffer)? {
+                self.output.send(&message)?;
+            }
+        }
+        if let Some(message) = self.decode_rest(buffer)? {
+            self.output.send(&message)?;
+        }
+
+        self.input.close()?;
+        self.output.close()?;
+        Ok(())
     }
 }
 
 #[cfg(feature = "std")]
 impl StdioSystem for Decode {
-    fn build_system(_config: StdioConfig) -> Result<System, StdioError> {
-        //use crate::{CoreBlocks, SysBlocks, SystemBuilding};
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{IoBlocks, SysBlocks, SystemBuilding};
+
+        let encoding = match config.params.get("encoding") {
+            None => Encoding::default(),
+            Some(encoding) => encoding
+                .parse::<Encoding>()
+                .map_err(|_| StdioError::InvalidParameter("encoding"))?,
+        };
 
-        Ok(System::build(|_s| todo!()))
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<String>(encoding);
+            let message_encoder = s.encode_with::<String>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &message_encoder.input);
+            s.connect(&message_encoder.output, &stdout.input);
+        }))
     }
 }
 
@@ -58,10 +58,27 @@ pub static BLOCKS: &[(&str, &str)] = &[
     ("core", "Drop"),
     ("core", "Random"),
     // FlowBlocks
+    ("flow", "Broadcast"),
+    ("flow", "Merge"),
+    ("flow", "RoundRobin"),
+    ("flow", "Route"),
+    ("flow", "Select"),
+    ("flow", "Switch"),
+    ("flow", "Zip"),
     // IoBlocks
     ("io", "Decode"),
     ("io", "Encode"),
     // MathBlocks
+    ("math", "Add"),
+    ("math", "Div"),
+    ("math", "Max"),
+    ("math", "Mean"),
+    ("math", "Min"),
+    ("math", "Mul"),
+    ("math", "Percentiles"),
+    ("math", "Sub"),
+    ("math", "Sum"),
+    ("math", "Variance"),
     // SysBlocks
     #[cfg(feature = "std")]
     ("sys", "ReadDir"),
@@ -96,8 +113,26 @@ pub fn build_stdio_system(
         "Drop" => Drop::<String>::build_system(config)?,
         "Random" => Random::<u64>::build_system(config)?,
         // FlowBlocks
+        "Broadcast" => Broadcast::<String, 2>::build_system(config)?,
+        "Merge" => Merge::<String, 2>::build_system(config)?,
+        "RoundRobin" => RoundRobin::<String, 2>::build_system(config)?,
+        "Route" => Route::<String>::build_system(config)?,
+        "Select" => Select::<String, 2>::build_system(config)?,
+        "Switch" => Switch::<String, 2>::build_system(config)?,
+        "Zip" => Zip::<String, String>::build_system(config)?,
         // IoBlocks
+        "Decode" => Decode::<String>::build_system(config)?,
         // MathBlocks
+        "Add" => Add::<f64>::build_system(config)?,
+        "Div" => Div::<f64>::build_system(config)?,
+        "Max" => Max::<f64>::build_system(config)?,
+        "Mean" => Mean::<f64>::build_system(config)?,
+        "Min" => Min::<f64>::build_system(config)?,
+        "Mul" => Mul::<f64>::build_system(config)?,
+        "Percentiles" => Percentiles::<f64>::build_system(config)?,
+        "Sub" => Sub::<f64>::build_system(config)?,
+        "Sum" => Sum::<f64>::build_system(config)?,
+        "Variance" => Variance::<f64>::build_system(config)?,
         // SysBlocks
         "ReadDir" => ReadDir::build_system(config)?,
         "ReadEnv" => ReadEnv::<String>::build_system(config)?,
@@ -1,3 +1,172 @@
 // This is free and unencumbered software released into the public domain.
 
-pub trait MathBlocks {}
+use crate::prelude::format;
+use protoflow_core::{BlockError, BlockResult, InputPort, Message, OutputPort};
+
+pub trait MathBlocks {
+    fn add<T: Number>(&self) -> Add<T>;
+
+    fn add_by<T: Number>(&self, value: T) -> Add<T>;
+
+    fn div<T: Number>(&self) -> Div<T>;
+
+    fn div_by<T: Number>(&self, value: T) -> Div<T>;
+
+    fn max<T: Number>(&self) -> Max<T>;
+
+    fn mean<T: Number>(&self) -> Mean<T>;
+
+    fn min<T: Number>(&self) -> Min<T>;
+
+    fn mul<T: Number>(&self) -> Mul<T>;
+
+    fn mul_by<T: Number>(&self, value: T) -> Mul<T>;
+
+    fn percentiles<T: Number>(&self) -> Percentiles<T>;
+
+    fn sub<T: Number>(&self) -> Sub<T>;
+
+    fn sub_by<T: Number>(&self, value: T) -> Sub<T>;
+
+    fn sum<T: Number>(&self) -> Sum<T>;
+
+    fn variance<T: Number>(&self) -> Variance<T>;
+}
+
+/// A numeric message type, such as `i64` or `f64`, that the math blocks
+/// operate on.
+///
+/// The integer operations return `None` on overflow, and all operations on
+/// division by zero, so that dividing floats by zero fails like dividing
+/// integers does instead of producing infinities or NaN.
+pub trait Number: Message + Copy + PartialOrd + 'static {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_integer {
+    ($($type:ty),*) => {
+        $(impl Number for $type {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$type>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$type>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$type>::checked_mul(self, rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$type>::checked_div(self, rhs)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+macro_rules! impl_float {
+    ($($type:ty),*) => {
+        $(impl Number for $type {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                Some(self + rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                Some(self - rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                Some(self * rhs)
+            }
+
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                (rhs != 0.0).then(|| self / rhs)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+        })*
+    };
+}
+
+impl_integer!(i32, i64, u32, u64);
+impl_float!(f32, f64);
+
+/// Applies an arithmetic operation to each input message and either the
+/// constant value, if any, or else the next message from the operand stream.
+fn apply<T: Number>(
+    input: &mut InputPort<T>,
+    operand: &mut InputPort<T>,
+    output: &mut OutputPort<T>,
+    value: Option<T>,
+    symbol: &str,
+    operation: fn(T, T) -> Option<T>,
+) -> BlockResult {
+    while let Some(lhs) = input.recv()? {
+        let rhs = match value {
+            Some(value) => value,
+            None => match operand.recv()? {
+                Some(rhs) => rhs,
+                None => break,
+            },
+        };
+        let Some(result) = operation(lhs, rhs) else {
+            return Err(BlockError::Other(format!(
+                "invalid operation: {:?} {} {:?}",
+                lhs, symbol, rhs
+            )));
+        };
+        output.send(&result)?;
+    }
+
+    input.close()?;
+    if value.is_none() {
+        operand.close()?;
+    }
+    output.close()?;
+    Ok(())
+}
+
+mod add;
+pub use add::*;
+
+mod div;
+pub use div::*;
+
+mod max;
+pub use max::*;
+
+mod mean;
+pub use mean::*;
+
+mod min;
+pub use min::*;
+
+mod mul;
+pub use mul::*;
+
+mod percentiles;
+pub use percentiles::*;
+
+mod sub;
+pub use sub::*;
+
+mod sum;
+pub use sum::*;
+
+mod variance;
+pub use variance::*;
@@ -0,0 +1,122 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Number;
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPort, OutputPort};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that finds the smallest of the numbers it receives, sending it
+/// once the input stream ends.
+///
+/// Nothing is sent if the input stream is empty.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/math/min.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/math/min.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines::<f64>();
+///     let minimizer = s.min::<f64>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &minimizer.input);
+///     s.connect(&minimizer.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Min
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Min<T: Number> {
+    /// The input number stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output port for the smallest number.
+    #[output]
+    pub output: OutputPort<T>,
+
+    /// The internal state tracking the smallest number received.
+    #[state]
+    min: Option<T>,
+}
+
+impl<T: Number> Min<T> {
+    pub fn new(input: InputPort<T>, output: OutputPort<T>) -> Self {
+        Self {
+            input,
+            output,
+            min: None,
+        }
+    }
+}
+
+impl<T: Number> Block for Min<T> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        while let Some(number) = self.input.recv()? {
+            if self.min.map_or(true, |min| number < min) {
+                self.min = Some(number);
+            }
+        }
+
+        if let Some(min) = self.min {
+            runtime.wait_for(&self.output)?;
+            self.output.send(&min)?;
+        }
+
+        self.input.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Number + crate::prelude::FromStr + crate::prelude::ToString> StdioSystem for Min<T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{IoBlocks, MathBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let minimizer = s.min::<T>();
+            let min_encoder = s.encode_with::<T>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &minimizer.input);
+            s.connect(&minimizer.output, &min_encoder.input);
+            s.connect(&min_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Min;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Min::<i32>::new(s.input(), s.output()));
+        });
+    }
+}
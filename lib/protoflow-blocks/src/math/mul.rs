@@ -0,0 +1,154 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::{apply, Number};
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPort, OutputPort};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that multiplies each number it receives by a constant value, or
+/// else by the next message from a second input stream.
+///
+/// Integer overflow fails the block.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/math/mul.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/math/mul.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines::<i64>();
+///     let multiplier = s.mul_by::<i64>(2);
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &multiplier.input);
+///     s.connect(&multiplier.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Mul value=2
+/// ```
+///
+/// Without a value, consecutive numbers from stdin are multiplied pairwise:
+///
+/// ```console
+/// $ protoflow execute Mul
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Mul<T: Number> {
+    /// The input number stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The input stream of numbers to multiply by, if there is no constant value.
+    #[input]
+    pub operand: InputPort<T>,
+
+    /// The output stream of products.
+    #[output]
+    pub output: OutputPort<T>,
+
+    /// A parameter for the constant value to multiply by.
+    #[parameter]
+    pub value: Option<T>,
+}
+
+impl<T: Number> Mul<T> {
+    pub fn new(input: InputPort<T>, operand: InputPort<T>, output: OutputPort<T>) -> Self {
+        Self::with_params(input, operand, output, None)
+    }
+
+    pub fn with_params(
+        input: InputPort<T>,
+        operand: InputPort<T>,
+        output: OutputPort<T>,
+        value: Option<T>,
+    ) -> Self {
+        Self {
+            input,
+            operand,
+            output,
+            value,
+        }
+    }
+}
+
+impl<T: Number> Block for Mul<T> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.input)?;
+
+        apply(
+            &mut self.input,
+            &mut self.operand,
+            &mut self.output,
+            self.value,
+            "*",
+            T::checked_mul,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Number + crate::prelude::FromStr + crate::prelude::ToString> StdioSystem for Mul<T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, MathBlocks, SysBlocks, SystemBuilding};
+
+        let value = match config.params.get("value").map(|v| v.parse::<T>()) {
+            None => None,
+            Some(Err(_)) => return Err(StdioError::InvalidParameter("value"))?,
+            Some(Ok(value)) => Some(value),
+        };
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let message_encoder = s.encode_with::<T>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            if let Some(value) = value {
+                let multiplier = s.mul_by::<T>(value);
+                s.connect(&message_decoder.output, &multiplier.input);
+                s.connect(&multiplier.output, &message_encoder.input);
+            } else {
+                // Multiply consecutive numbers from stdin:
+                let distributor = s.round_robin::<T, 2>();
+                let multiplier = s.mul::<T>();
+                s.connect(&message_decoder.output, &distributor.input);
+                s.connect(&distributor.outputs[0], &multiplier.input);
+                s.connect(&distributor.outputs[1], &multiplier.operand);
+                s.connect(&multiplier.output, &message_encoder.input);
+            }
+            s.connect(&message_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mul;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Mul::<i32>::new(s.input(), s.input(), s.output()));
+        });
+    }
+}
@@ -0,0 +1,268 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Number;
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPort, OutputPort, Port};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that estimates the median (P50), P95, and P99 percentiles of the
+/// numbers it receives, sending them once the input stream ends.
+///
+/// The percentiles are estimated with the P² algorithm, which tracks each
+/// of them with five markers, however long the input stream. They are exact
+/// for up to five numbers. Nothing is sent if the input stream is empty,
+/// and NaNs are ignored.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/math/percentiles.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/math/percentiles.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines::<f64>();
+///     let percentiles = s.percentiles::<f64>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &percentiles.input);
+///     s.connect(&percentiles.p99, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// The percentiles are written to stdout separated by tabs:
+///
+/// ```console
+/// $ protoflow execute Percentiles
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Percentiles<T: Number> {
+    /// The input number stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The (optional) output port for the median.
+    #[output]
+    pub p50: OutputPort<f64>,
+
+    /// The (optional) output port for the 95th percentile.
+    #[output]
+    pub p95: OutputPort<f64>,
+
+    /// The (optional) output port for the 99th percentile.
+    #[output]
+    pub p99: OutputPort<f64>,
+
+    /// The internal state estimating each percentile.
+    #[state]
+    quantiles: [Quantile; 3],
+}
+
+impl<T: Number> Percentiles<T> {
+    pub fn new(
+        input: InputPort<T>,
+        p50: OutputPort<f64>,
+        p95: OutputPort<f64>,
+        p99: OutputPort<f64>,
+    ) -> Self {
+        Self {
+            input,
+            p50,
+            p95,
+            p99,
+            quantiles: [Quantile::new(0.5), Quantile::new(0.95), Quantile::new(0.99)],
+        }
+    }
+}
+
+impl<T: Number> Block for Percentiles<T> {
+    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
+        while let Some(number) = self.input.recv()? {
+            for quantile in self.quantiles.iter_mut() {
+                quantile.insert(number.to_f64());
+            }
+        }
+
+        let outputs = [&self.p50, &self.p95, &self.p99];
+        for (output, quantile) in outputs.into_iter().zip(self.quantiles.iter()) {
+            if let Some(estimate) = quantile.estimate() {
+                if output.is_connected() {
+                    output.send(&estimate)?;
+                }
+            }
+        }
+
+        self.input.close()?;
+        self.p50.close()?;
+        self.p95.close()?;
+        self.p99.close()?;
+        Ok(())
+    }
+}
+
+/// A streaming estimate of a quantile, using the P² algorithm by Jain and
+/// Chlamtac (1985).
+///
+/// The five markers track the minimum, the maximum, the quantile itself,
+/// and the quantiles halfway to either extreme. Their heights are adjusted
+/// with a piecewise-parabolic interpolation as their positions drift.
+#[derive(Clone, Debug)]
+struct Quantile {
+    count: usize,
+    quantile: f64,
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired_positions: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl Quantile {
+    fn new(quantile: f64) -> Self {
+        let p = quantile;
+        Self {
+            count: 0,
+            quantile,
+            heights: [0.0; 5],
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired_positions: [1.0, 1.0 + 2.0 * p, 1.0 + 4.0 * p, 3.0 + 2.0 * p, 5.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        if self.count < 5 {
+            self.heights[self.count] = value;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        self.count += 1;
+
+        // Find the cell of the value, extending the extremes if needed:
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (1..4).find(|&i| value < self.heights[i]).unwrap_or(4) - 1
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired_positions.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+
+        // Adjust the middle markers that are off their desired positions:
+        for i in 1..4 {
+            let drift = self.desired_positions[i] - self.positions[i];
+            let n = &self.positions;
+            if (drift >= 1.0 && n[i + 1] - n[i] > 1.0) || (drift <= -1.0 && n[i - 1] - n[i] < -1.0)
+            {
+                let d = if drift > 0.0 { 1.0 } else { -1.0 };
+                let height = self.parabolic(i, d);
+                self.heights[i] = if self.heights[i - 1] < height && height < self.heights[i + 1] {
+                    height
+                } else {
+                    self.linear(i, d)
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        q[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let (q, n) = (&self.heights, &self.positions);
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+    }
+
+    fn estimate(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            count if count <= 5 => {
+                // Interpolate between the exact values seen so far:
+                let mut values = self.heights;
+                values[..count].sort_by(f64::total_cmp);
+                let rank = self.quantile * (count - 1) as f64;
+                let (lower, fraction) = (rank as usize, rank - (rank as usize) as f64);
+                let upper = (lower + 1).min(count - 1);
+                Some(values[lower] + (values[upper] - values[lower]) * fraction)
+            }
+            _ => Some(self.heights[2]),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Number + crate::prelude::FromStr> StdioSystem for Percentiles<T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{FlowBlocks, IoBlocks, MathBlocks, Pair, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let percentiles = s.percentiles::<T>();
+            // Pair up the percentiles, to write them out on a single line:
+            let first_zipper = s.zip::<f64, f64>();
+            let second_zipper = s.zip::<Pair<f64, f64>, f64>();
+            let percentile_encoder = s.encode_with::<Pair<Pair<f64, f64>, f64>>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &percentiles.input);
+            s.connect(&percentiles.p50, &first_zipper.first);
+            s.connect(&percentiles.p95, &first_zipper.second);
+            s.connect(&first_zipper.output, &second_zipper.first);
+            s.connect(&percentiles.p99, &second_zipper.second);
+            s.connect(&second_zipper.output, &percentile_encoder.input);
+            s.connect(&percentile_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Percentiles;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Percentiles::<i32>::new(
+                s.input(),
+                s.output(),
+                s.output(),
+                s.output(),
+            ));
+        });
+    }
+}
@@ -0,0 +1,125 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Number;
+use crate::{prelude::format, StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockError, BlockResult, BlockRuntime, InputPort, OutputPort};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that sums up the numbers it receives, sending the sum once the
+/// input stream ends.
+///
+/// Integer overflow fails the block.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/math/sum.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/math/sum.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines::<i64>();
+///     let summer = s.sum::<i64>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &summer.input);
+///     s.connect(&summer.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Sum
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Sum<T: Number> {
+    /// The input number stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output port for the sum.
+    #[output]
+    pub output: OutputPort<T>,
+
+    /// The internal state accumulating the sum.
+    #[state]
+    sum: T,
+}
+
+impl<T: Number> Sum<T> {
+    pub fn new(input: InputPort<T>, output: OutputPort<T>) -> Self {
+        Self {
+            input,
+            output,
+            sum: T::default(),
+        }
+    }
+}
+
+impl<T: Number> Block for Sum<T> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        while let Some(number) = self.input.recv()? {
+            let Some(sum) = self.sum.checked_add(number) else {
+                return Err(BlockError::Other(format!(
+                    "invalid operation: {:?} + {:?}",
+                    self.sum, number
+                )));
+            };
+            self.sum = sum;
+        }
+
+        runtime.wait_for(&self.output)?;
+
+        self.output.send(&self.sum)?;
+
+        self.input.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Number + crate::prelude::FromStr + crate::prelude::ToString> StdioSystem for Sum<T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{IoBlocks, MathBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let summer = s.sum::<T>();
+            let sum_encoder = s.encode_with::<T>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &summer.input);
+            s.connect(&summer.output, &sum_encoder.input);
+            s.connect(&sum_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sum;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Sum::<i32>::new(s.input(), s.output()));
+        });
+    }
+}
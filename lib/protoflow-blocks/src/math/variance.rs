@@ -0,0 +1,135 @@
+// This is free and unencumbered software released into the public domain.
+
+use super::Number;
+use crate::{StdioConfig, StdioError, StdioSystem, System};
+use protoflow_core::{Block, BlockResult, BlockRuntime, InputPort, OutputPort};
+use protoflow_derive::Block;
+use simple_mermaid::mermaid;
+
+/// A block that computes the population variance of the numbers it
+/// receives, sending it once the input stream ends.
+///
+/// The variance is computed in a single pass, using Welford's algorithm.
+/// Nothing is sent if the input stream is empty.
+///
+/// # Block Diagram
+#[doc = mermaid!("../../doc/math/variance.mmd")]
+///
+/// # Sequence Diagram
+#[doc = mermaid!("../../doc/math/variance.seq.mmd" framed)]
+///
+/// # Examples
+///
+/// ## Using the block in a system
+///
+/// ```rust
+/// # use protoflow_blocks::*;
+/// # fn main() {
+/// System::build(|s| {
+///     let stdin = s.read_stdin();
+///     let line_decoder = s.decode_lines::<f64>();
+///     let variance = s.variance::<f64>();
+///     let line_encoder = s.encode_lines();
+///     let stdout = s.write_stdout();
+///     s.connect(&stdin.output, &line_decoder.input);
+///     s.connect(&line_decoder.output, &variance.input);
+///     s.connect(&variance.output, &line_encoder.input);
+///     s.connect(&line_encoder.output, &stdout.input);
+/// });
+/// # }
+/// ```
+///
+/// ## Running the block via the CLI
+///
+/// ```console
+/// $ protoflow execute Variance
+/// ```
+///
+#[derive(Block, Clone)]
+pub struct Variance<T: Number> {
+    /// The input number stream.
+    #[input]
+    pub input: InputPort<T>,
+
+    /// The output port for the variance.
+    #[output]
+    pub output: OutputPort<f64>,
+
+    /// The internal state counting the numbers received.
+    #[state]
+    count: u64,
+
+    /// The internal state tracking the running mean.
+    #[state]
+    mean: f64,
+
+    /// The internal state tracking the sum of squared deviations.
+    #[state]
+    m2: f64,
+}
+
+impl<T: Number> Variance<T> {
+    pub fn new(input: InputPort<T>, output: OutputPort<f64>) -> Self {
+        Self {
+            input,
+            output,
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+}
+
+impl<T: Number> Block for Variance<T> {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        while let Some(number) = self.input.recv()? {
+            let number = number.to_f64();
+            self.count += 1;
+            let delta = number - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (number - self.mean);
+        }
+
+        if self.count > 0 {
+            runtime.wait_for(&self.output)?;
+            self.output.send(&(self.m2 / self.count as f64))?;
+        }
+
+        self.input.close()?;
+        self.output.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Number + crate::prelude::FromStr> StdioSystem for Variance<T> {
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{IoBlocks, MathBlocks, SysBlocks, SystemBuilding};
+
+        Ok(System::build(|s| {
+            let stdin = s.read_stdin();
+            let message_decoder = s.decode_with::<T>(config.encoding);
+            let variance = s.variance::<T>();
+            let variance_encoder = s.encode_with::<f64>(config.encoding);
+            let stdout = s.write_stdout();
+            s.connect(&stdin.output, &message_decoder.input);
+            s.connect(&message_decoder.output, &variance.input);
+            s.connect(&variance.output, &variance_encoder.input);
+            s.connect(&variance_encoder.output, &stdout.input);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Variance;
+    use crate::{System, SystemBuilding};
+
+    #[test]
+    fn instantiate_block() {
+        // Check that the block is constructible:
+        let _ = System::build(|s| {
+            let _ = s.block(Variance::<i32>::new(s.input(), s.output()));
+        });
+    }
+}
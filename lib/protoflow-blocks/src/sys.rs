@@ -4,8 +4,10 @@ pub trait SysBlocks {
     fn read_dir(&self) -> ReadDir;
     fn read_env(&self) -> ReadEnv;
     fn read_file(&self) -> ReadFile;
+    fn read_file_with(&self, buffer_size: Option<usize>) -> ReadFile;
     fn read_stdin(&self) -> ReadStdin;
     fn write_file(&self) -> WriteFile;
+    fn write_file_with(&self, flags: WriteFlags) -> WriteFile;
     fn write_stderr(&self) -> WriteStderr;
     fn write_stdout(&self) -> WriteStdout;
 }
@@ -84,7 +84,7 @@ impl StdioSystem for ReadDir {
     fn build_system(config: StdioConfig) -> Result<System, StdioError> {
         use crate::{CoreBlocks, IoBlocks, SysBlocks, SystemBuilding};
 
-        let Some(path) = config.params.get("path").map(String::clone) else {
+        let Some(path) = config.params.get("path").cloned() else {
             return Err(StdioError::MissingParameter("path"))?;
         };
 
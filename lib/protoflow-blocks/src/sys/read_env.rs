@@ -80,7 +80,7 @@ impl<T: Message + FromStr> StdioSystem for ReadEnv<T> {
     fn build_system(config: StdioConfig) -> Result<System, StdioError> {
         use crate::{CoreBlocks, IoBlocks, SysBlocks, SystemBuilding};
 
-        let Some(name) = config.params.get("name").map(String::clone) else {
+        let Some(name) = config.params.get("name").cloned() else {
             return Err(StdioError::MissingParameter("name"))?;
         };
 
@@ -4,14 +4,20 @@ extern crate std;
 
 use crate::{StdioConfig, StdioError, StdioSystem, System};
 use protoflow_core::{
-    prelude::{Bytes, String},
+    prelude::{vec, Bytes, String},
     Block, BlockResult, BlockRuntime, InputPort, OutputPort,
 };
 use protoflow_derive::Block;
 use simple_mermaid::mermaid;
+use std::io::Read;
+
+/// The default buffer size for reading from a file.
+const DEFAULT_BUFFER_SIZE: usize = 1024;
 
 /// A block that reads bytes from the contents of a file.
 ///
+/// The file is streamed in chunks of at most `buffer_size` bytes.
+///
 /// # Block Diagram
 #[doc = mermaid!("../../doc/sys/read_file.mmd")]
 ///
@@ -26,7 +32,11 @@ use simple_mermaid::mermaid;
 /// # use protoflow_blocks::*;
 /// # fn main() {
 /// System::build(|s| {
-///     // TODO
+///     let path_param = s.const_string("/tmp/file.txt");
+///     let file_reader = s.read_file();
+///     let stdout = s.write_stdout();
+///     s.connect(&path_param.output, &file_reader.path);
+///     s.connect(&file_reader.output, &stdout.input);
 /// });
 /// # }
 /// ```
@@ -37,6 +47,10 @@ use simple_mermaid::mermaid;
 /// $ protoflow execute ReadFile path=/tmp/file.txt
 /// ```
 ///
+/// ```console
+/// $ protoflow execute ReadFile path=/tmp/file.txt buffer-size=4096
+/// ```
+///
 #[derive(Block, Clone)]
 pub struct ReadFile {
     /// The path to the file to read from.
@@ -46,26 +60,84 @@ pub struct ReadFile {
     /// The output message stream.
     #[output]
     pub output: OutputPort<Bytes>,
+
+    /// The maximum number of bytes to read at a time.
+    #[parameter]
+    pub buffer_size: usize,
 }
 
 impl ReadFile {
     pub fn new(path: InputPort<String>, output: OutputPort<Bytes>) -> Self {
-        Self { path, output }
+        Self::with_params(path, output, None)
+    }
+
+    pub fn with_params(
+        path: InputPort<String>,
+        output: OutputPort<Bytes>,
+        buffer_size: Option<usize>,
+    ) -> Self {
+        Self {
+            path,
+            output,
+            buffer_size: buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE),
+        }
     }
 }
 
 impl Block for ReadFile {
-    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
-        unimplemented!() // TODO
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.path)?;
+        let path = self.path.recv()?;
+        self.path.close()?;
+
+        if let Some(path) = path {
+            let mut file = std::fs::File::open(path)?;
+            let mut buffer = vec![0; self.buffer_size.max(1)];
+
+            runtime.wait_for(&self.output)?;
+
+            loop {
+                match file.read(&mut buffer) {
+                    Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err.into()),
+                    Ok(0) => break, // EOF
+                    Ok(buffer_len) => {
+                        let bytes = Bytes::copy_from_slice(&buffer[..buffer_len]);
+                        self.output.send(&bytes)?;
+                    }
+                }
+            }
+        }
+
+        self.output.close()?;
+        Ok(())
     }
 }
 
 #[cfg(feature = "std")]
 impl StdioSystem for ReadFile {
-    fn build_system(_config: StdioConfig) -> Result<System, StdioError> {
-        //use crate::{CoreBlocks, SysBlocks, SystemBuilding};
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{CoreBlocks, SysBlocks, SystemBuilding};
+
+        let Some(path) = config.params.get("path").cloned() else {
+            return Err(StdioError::MissingParameter("path"))?;
+        };
+        let buffer_size = config
+            .params
+            .get("buffer-size")
+            .map(|v| v.as_str().parse::<usize>());
+        if let Some(Err(_)) = buffer_size {
+            return Err(StdioError::InvalidParameter("buffer-size"))?;
+        }
+        let buffer_size = buffer_size.map(Result::unwrap);
 
-        Ok(System::build(|_s| todo!())) // TODO
+        Ok(System::build(|s| {
+            let path_param = s.const_string(path);
+            let file_reader = s.read_file_with(buffer_size);
+            let stdout = s.write_stdout();
+            s.connect(&path_param.output, &file_reader.path);
+            s.connect(&file_reader.output, &stdout.input);
+        }))
     }
 }
 
@@ -9,9 +9,13 @@ use protoflow_core::{
 };
 use protoflow_derive::Block;
 use simple_mermaid::mermaid;
+use std::io::Write;
 
 /// A block that writes or appends bytes to the contents of a file.
 ///
+/// How the file gets opened, and whether it gets synced to disk when the
+/// input stream ends, is configured with [`WriteFlags`].
+///
 /// # Block Diagram
 #[doc = mermaid!("../../doc/sys/write_file.mmd")]
 ///
@@ -26,7 +30,11 @@ use simple_mermaid::mermaid;
 /// # use protoflow_blocks::*;
 /// # fn main() {
 /// System::build(|s| {
-///     // TODO
+///     let path_param = s.const_string("/tmp/file.txt");
+///     let stdin = s.read_stdin();
+///     let file_writer = s.write_file();
+///     s.connect(&path_param.output, &file_writer.path);
+///     s.connect(&stdin.output, &file_writer.input);
 /// });
 /// # }
 /// ```
@@ -37,6 +45,10 @@ use simple_mermaid::mermaid;
 /// $ protoflow execute WriteFile path=/tmp/file.txt
 /// ```
 ///
+/// ```console
+/// $ protoflow execute WriteFile path=/tmp/file.txt append=true sync=true
+/// ```
+///
 #[derive(Block, Clone)]
 pub struct WriteFile {
     /// The path to the file to write to.
@@ -46,19 +58,88 @@ pub struct WriteFile {
     /// The input message stream.
     #[input]
     pub input: InputPort<Bytes>,
+
+    /// A configuration parameter for how to write to the file.
+    #[parameter]
+    pub flags: WriteFlags,
+}
+
+/// How to open the file to write to, and whether to sync it to disk.
+///
+/// By default, the file is created if need be and truncated.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct WriteFlags {
+    /// Whether to create the file if it doesn't exist.
+    pub create: bool,
+    /// Whether to truncate the file if it exists.
+    pub truncate: bool,
+    /// Whether to append to the end of the file.
+    pub append: bool,
+    /// Whether to sync the file to disk (fsync) when the input stream ends.
+    pub sync: bool,
+}
+
+impl Default for WriteFlags {
+    fn default() -> Self {
+        Self {
+            create: true,
+            truncate: true,
+            append: false,
+            sync: false,
+        }
+    }
+}
+
+impl WriteFlags {
+    /// Returns the flags for appending to the file, creating it if need be.
+    pub fn append() -> Self {
+        Self {
+            truncate: false,
+            append: true,
+            ..Self::default()
+        }
+    }
 }
 
 impl WriteFile {
     pub fn new(path: InputPort<String>, input: InputPort<Bytes>) -> Self {
-        Self { path, input }
+        Self::with_params(path, input, WriteFlags::default())
+    }
+
+    pub fn with_params(
+        path: InputPort<String>,
+        input: InputPort<Bytes>,
+        flags: WriteFlags,
+    ) -> Self {
+        Self { path, input, flags }
     }
 }
 
 impl Block for WriteFile {
-    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
-        while let Some(_message) = self.input.recv()? {
-            unimplemented!() // TODO
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.path)?;
+        let path = self.path.recv()?;
+        self.path.close()?;
+
+        if let Some(path) = path {
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .create(self.flags.create)
+                .truncate(self.flags.truncate)
+                .append(self.flags.append)
+                .open(path)?;
+
+            runtime.wait_for(&self.input)?;
+
+            while let Some(message) = self.input.recv()? {
+                file.write_all(&message)?;
+            }
+
+            if self.flags.sync {
+                file.sync_all()?;
+            }
         }
+
         self.input.close()?;
         Ok(())
     }
@@ -66,10 +147,33 @@ impl Block for WriteFile {
 
 #[cfg(feature = "std")]
 impl StdioSystem for WriteFile {
-    fn build_system(_config: StdioConfig) -> Result<System, StdioError> {
-        //use crate::{CoreBlocks, SysBlocks, SystemBuilding};
+    fn build_system(config: StdioConfig) -> Result<System, StdioError> {
+        use crate::{CoreBlocks, SysBlocks, SystemBuilding};
+
+        let Some(path) = config.params.get("path").cloned() else {
+            return Err(StdioError::MissingParameter("path"))?;
+        };
+        let flag = |name: &'static str, default: bool| match config.params.get(name) {
+            None => Ok(default),
+            Some(value) => value
+                .parse::<bool>()
+                .map_err(|_| StdioError::InvalidParameter(name)),
+        };
+        let append = flag("append", false)?;
+        let flags = WriteFlags {
+            create: flag("create", true)?,
+            truncate: flag("truncate", !append)?,
+            append,
+            sync: flag("sync", false)?,
+        };
 
-        Ok(System::build(|_s| todo!())) // TODO
+        Ok(System::build(|s| {
+            let path_param = s.const_string(path);
+            let stdin = s.read_stdin();
+            let file_writer = s.write_file_with(flags);
+            s.connect(&path_param.output, &file_writer.path);
+            s.connect(&stdin.output, &file_writer.input);
+        }))
     }
 }
 
@@ -3,13 +3,16 @@
 #![allow(dead_code)]
 
 use crate::{
-    prelude::{Arc, FromStr, Rc, String, ToString},
-    AllBlocks, Buffer, Const, CoreBlocks, Count, Decode, Delay, DelayType, Drop, Encode, Encoding,
-    FlowBlocks, IoBlocks, MathBlocks, Random, ReadDir, ReadEnv, ReadFile, ReadStdin, SysBlocks,
-    TextBlocks, WriteFile, WriteStderr, WriteStdout,
+    prelude::{Arc, FromStr, Rc, String, ToString, Vec},
+    Add, AllBlocks, Broadcast, Buffer, Const, CoreBlocks, Count, Decode, Delay, DelayType, Div,
+    Drop, Encode, Encoding, FlowBlocks, IoBlocks, MathBlocks, Max, Mean, Merge, Min, Mul, Number,
+    Percentiles, Random, ReadDir, ReadEnv, ReadFile, ReadStdin, RoundRobin, Route, RoutePredicate,
+    Select, Sub, Sum, Switch, SysBlocks, TextBlocks, Variance, WriteFile, WriteFlags, WriteStderr,
+    WriteStdout, Zip,
 };
 use protoflow_core::{
-    Block, BlockResult, InputPort, Message, OutputPort, Process, SystemBuilding, SystemExecution,
+    Block, BlockResult, ConnectionOptions, FailurePolicy, InputPort, InputPorts, Message,
+    OutputPort, OutputPorts, Process, RestartPolicy, SystemBuilding, SystemExecution,
 };
 
 type Transport = protoflow_core::transports::MpscTransport;
@@ -41,6 +44,21 @@ impl System {
     pub fn new(runtime: &Arc<Runtime>) -> Self {
         Self(protoflow_core::System::<Transport>::new(runtime))
     }
+
+    /// Instantiates a block inside the system, which gets restarted
+    /// according to the given policy.
+    pub fn block_with_restart<B: Block + Clone + 'static>(
+        &self,
+        block: B,
+        restart: RestartPolicy,
+    ) -> B {
+        self.0.block_with_restart(block, restart)
+    }
+
+    /// Sets what the system does when one of its blocks fails.
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        self.0.set_failure_policy(policy)
+    }
 }
 
 impl AllBlocks for System {}
@@ -92,7 +110,56 @@ impl CoreBlocks for System {
     }
 }
 
-impl FlowBlocks for System {}
+impl FlowBlocks for System {
+    fn broadcast<T: Message + 'static, const N: usize>(&self) -> Broadcast<T, N> {
+        self.0
+            .block(Broadcast::<T, N>::new(self.0.input(), self.0.outputs()))
+    }
+
+    fn merge<T: Message + 'static, const N: usize>(&self) -> Merge<T, N> {
+        self.0
+            .block(Merge::<T, N>::new(self.0.inputs(), self.0.output()))
+    }
+
+    fn round_robin<T: Message + 'static, const N: usize>(&self) -> RoundRobin<T, N> {
+        self.0
+            .block(RoundRobin::<T, N>::new(self.0.input(), self.0.outputs()))
+    }
+
+    fn route<T: Message + 'static>(&self, routes: Vec<(String, RoutePredicate)>) -> Route<T> {
+        let outputs = routes.iter().map(|_| self.0.output()).collect();
+        self.0.block(Route::<T>::with_params(
+            self.0.input(),
+            outputs,
+            self.0.output(),
+            routes,
+        ))
+    }
+
+    fn select<T: Message + 'static, const N: usize>(&self) -> Select<T, N> {
+        self.0.block(Select::<T, N>::new(
+            self.0.input(),
+            self.0.inputs(),
+            self.0.output(),
+        ))
+    }
+
+    fn switch<T: Message + 'static, const N: usize>(&self) -> Switch<T, N> {
+        self.0.block(Switch::<T, N>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.outputs(),
+        ))
+    }
+
+    fn zip<A: Message + 'static, B: Message + 'static>(&self) -> Zip<A, B> {
+        self.0.block(Zip::<A, B>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+        ))
+    }
+}
 
 impl IoBlocks for System {
     fn decode<T: Message + FromStr + 'static>(&self) -> Decode<T> {
@@ -122,7 +189,106 @@ impl IoBlocks for System {
     }
 }
 
-impl MathBlocks for System {}
+impl MathBlocks for System {
+    fn add<T: Number>(&self) -> Add<T> {
+        self.0.block(Add::<T>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+        ))
+    }
+
+    fn add_by<T: Number>(&self, value: T) -> Add<T> {
+        self.0.block(Add::<T>::with_params(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+            Some(value),
+        ))
+    }
+
+    fn div<T: Number>(&self) -> Div<T> {
+        self.0.block(Div::<T>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+        ))
+    }
+
+    fn div_by<T: Number>(&self, value: T) -> Div<T> {
+        self.0.block(Div::<T>::with_params(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+            Some(value),
+        ))
+    }
+
+    fn max<T: Number>(&self) -> Max<T> {
+        self.0.block(Max::<T>::new(self.0.input(), self.0.output()))
+    }
+
+    fn mean<T: Number>(&self) -> Mean<T> {
+        self.0
+            .block(Mean::<T>::new(self.0.input(), self.0.output()))
+    }
+
+    fn min<T: Number>(&self) -> Min<T> {
+        self.0.block(Min::<T>::new(self.0.input(), self.0.output()))
+    }
+
+    fn mul<T: Number>(&self) -> Mul<T> {
+        self.0.block(Mul::<T>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+        ))
+    }
+
+    fn mul_by<T: Number>(&self, value: T) -> Mul<T> {
+        self.0.block(Mul::<T>::with_params(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+            Some(value),
+        ))
+    }
+
+    fn percentiles<T: Number>(&self) -> Percentiles<T> {
+        self.0.block(Percentiles::<T>::new(
+            self.0.input(),
+            self.0.output(),
+            self.0.output(),
+            self.0.output(),
+        ))
+    }
+
+    fn sub<T: Number>(&self) -> Sub<T> {
+        self.0.block(Sub::<T>::new(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+        ))
+    }
+
+    fn sub_by<T: Number>(&self, value: T) -> Sub<T> {
+        self.0.block(Sub::<T>::with_params(
+            self.0.input(),
+            self.0.input(),
+            self.0.output(),
+            Some(value),
+        ))
+    }
+
+    fn sum<T: Number>(&self) -> Sum<T> {
+        self.0.block(Sum::<T>::new(self.0.input(), self.0.output()))
+    }
+
+    fn variance<T: Number>(&self) -> Variance<T> {
+        self.0
+            .block(Variance::<T>::new(self.0.input(), self.0.output()))
+    }
+}
 
 #[cfg(not(feature = "std"))]
 impl SysBlocks for System {}
@@ -141,6 +307,14 @@ impl SysBlocks for System {
         self.0.block(ReadFile::new(self.0.input(), self.0.output()))
     }
 
+    fn read_file_with(&self, buffer_size: Option<usize>) -> ReadFile {
+        self.0.block(ReadFile::with_params(
+            self.0.input(),
+            self.0.output(),
+            buffer_size,
+        ))
+    }
+
     fn read_stdin(&self) -> ReadStdin {
         self.0.block(ReadStdin::new(self.0.output()))
     }
@@ -149,6 +323,14 @@ impl SysBlocks for System {
         self.0.block(WriteFile::new(self.0.input(), self.0.input()))
     }
 
+    fn write_file_with(&self, flags: WriteFlags) -> WriteFile {
+        self.0.block(WriteFile::with_params(
+            self.0.input(),
+            self.0.input(),
+            flags,
+        ))
+    }
+
     fn write_stderr(&self) -> WriteStderr {
         self.0.block(WriteStderr::new(self.0.input()))
     }
@@ -169,6 +351,14 @@ impl SystemBuilding for System {
         self.0.output()
     }
 
+    fn inputs<M: Message + 'static, const N: usize>(&self) -> InputPorts<M, N> {
+        self.0.inputs()
+    }
+
+    fn outputs<M: Message + 'static, const N: usize>(&self) -> OutputPorts<M, N> {
+        self.0.outputs()
+    }
+
     fn block<B: Block + Clone + 'static>(&self, block: B) -> B {
         self.0.block(block)
     }
@@ -176,6 +366,15 @@ impl SystemBuilding for System {
     fn connect<M: Message>(&self, source: &OutputPort<M>, target: &InputPort<M>) -> bool {
         self.0.connect(source, target)
     }
+
+    fn connect_with<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+        options: ConnectionOptions,
+    ) -> bool {
+        self.0.connect_with(source, target, options)
+    }
 }
 
 impl SystemExecution for System {
@@ -0,0 +1,24 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{prelude::Box, BlockDescriptor, BlockResult, BlockRuntime};
+use futures::future::BoxFuture;
+
+/// An asynchronous block, whose computation can await I/O instead of
+/// blocking a thread.
+///
+/// Asynchronous blocks are executed by the `TokioRuntime`. They should use
+/// the async methods of their ports (`InputPort::recv_async` and
+/// `OutputPort::send_async`) and Tokio's timers instead of the blocking
+/// methods of the block runtime.
+pub trait AsyncBlock: BlockDescriptor + Send + Sync {
+    /// Prepares this block for execution.
+    ///
+    /// This is called once before the first call to `execute`.
+    /// This is where to open ports and allocate resources.
+    fn prepare<'a>(&'a mut self, _runtime: &'a dyn BlockRuntime) -> BoxFuture<'a, BlockResult> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Executes this block's computation.
+    fn execute<'a>(&'a mut self, runtime: &'a dyn BlockRuntime) -> BoxFuture<'a, BlockResult>;
+}
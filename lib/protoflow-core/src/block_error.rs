@@ -1,8 +1,8 @@
 // This is free and unencumbered software released into the public domain.
 
 use crate::{
-    prelude::{fmt, Box, Result, String, ToString},
-    PortError,
+    prelude::{fmt, Box, Result, String, ToString, Vec},
+    PortError, ProcessID,
 };
 
 #[cfg(feature = "std")]
@@ -15,18 +15,52 @@ pub enum BlockError {
     Terminated,
     PortError(PortError),
     Other(String),
+    /// One or more blocks of a system failed.
+    Failed(Vec<BlockFailure>),
     #[cfg(feature = "std")]
     Panic(Box<dyn std::any::Any + Send>),
 }
 
+/// A report of a failed block in a system.
+#[derive(Debug)]
+pub struct BlockFailure {
+    /// The name of the block, if it has one.
+    pub name: Option<String>,
+    /// The ID of the block's process.
+    pub process_id: ProcessID,
+    /// The error the block failed with, which could be a panic.
+    pub error: BlockError,
+}
+
+impl fmt::Display for BlockFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(ref name) => write!(f, "block {} (#{})", name, self.process_id)?,
+            None => write!(f, "block #{}", self.process_id)?,
+        }
+        write!(f, " failed: {}", self.error)
+    }
+}
+
 impl fmt::Display for BlockError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Terminated => write!(f, "Execution terminated"),
             Self::PortError(e) => write!(f, "{}", e),
             Self::Other(message) => write!(f, "{}", message),
+            Self::Failed(failures) => {
+                write!(f, "{} block(s) failed", failures.len())?;
+                for failure in failures {
+                    write!(f, "\n{}", failure)?;
+                }
+                Ok(())
+            }
             #[cfg(feature = "std")]
-            Self::Panic(e) => write!(f, "Panic: {:?}", e),
+            Self::Panic(e) => match (e.downcast_ref::<&str>(), e.downcast_ref::<String>()) {
+                (Some(message), _) => write!(f, "Panic: {}", message),
+                (_, Some(message)) => write!(f, "Panic: {}", message),
+                _ => write!(f, "Panic: {:?}", e),
+            },
         }
     }
 }
@@ -8,12 +8,22 @@ use crate::{
 pub trait BlockRuntime: Send + Sync {
     fn is_alive(&self) -> bool;
 
+    /// Returns the current time according to the runtime's clock.
+    fn now(&self) -> Instant;
+
     fn sleep_for(&self, duration: Duration) -> Result<(), BlockError>;
 
-    fn sleep_until(&self, instant: Instant) -> Result<(), BlockError>; // TODO
+    fn sleep_until(&self, instant: Instant) -> Result<(), BlockError>;
 
+    /// Blocks until the port is connected.
     fn wait_for(&self, port: &dyn Port) -> Result<(), BlockError>;
 
+    /// Blocks until the port is connected, or until the timeout elapses.
+    ///
+    /// Returns `Ok(true)` if the port is connected.
+    /// Returns `Ok(false)` if the timeout elapsed first.
+    fn wait_for_timeout(&self, port: &dyn Port, timeout: Duration) -> Result<bool, BlockError>;
+
     fn yield_now(&self) -> Result<(), BlockError>;
 
     fn random_duration(&self, range: Range<Duration>) -> Duration;
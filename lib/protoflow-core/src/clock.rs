@@ -0,0 +1,19 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::prelude::Instant;
+
+/// A source of monotonic time, against which a runtime's timers are driven.
+pub trait Clock: Send + Sync {
+    /// Returns the time elapsed since this clock's epoch.
+    fn now(&self) -> Instant;
+
+    /// Blocks the current thread until this clock reaches the `deadline`,
+    /// or until `is_interrupted` returns `true` after a call to `wake_all`.
+    ///
+    /// Returns `true` if the deadline was reached.
+    /// Returns `false` if the wait was interrupted.
+    fn wait_until(&self, deadline: Instant, is_interrupted: &dyn Fn() -> bool) -> bool;
+
+    /// Wakes up all threads currently waiting on this clock.
+    fn wake_all(&self);
+}
@@ -0,0 +1,11 @@
+// This is free and unencumbered software released into the public domain.
+
+#[cfg(feature = "std")]
+mod system;
+#[cfg(feature = "std")]
+pub use system::*;
+
+#[cfg(feature = "std")]
+mod r#virtual;
+#[cfg(feature = "std")]
+pub use r#virtual::*;
@@ -0,0 +1,61 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use crate::{prelude::Instant, utils::Condvar, Clock};
+use parking_lot::Mutex;
+
+/// A clock that follows the monotonic system time.
+///
+/// The clock's epoch is the time at which it was created.
+#[derive(Debug)]
+pub struct SystemClock {
+    epoch: std::time::Instant,
+    wakeup: (Mutex<()>, Condvar),
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: std::time::Instant::now(),
+            wakeup: (Mutex::new(()), Condvar::new()),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        self.epoch.elapsed()
+    }
+
+    fn wait_until(&self, deadline: Instant, is_interrupted: &dyn Fn() -> bool) -> bool {
+        let system_deadline = self.epoch.checked_add(deadline);
+        let (ref lock, ref cvar) = self.wakeup;
+        let mut guard = lock.lock();
+        loop {
+            if is_interrupted() {
+                return false;
+            }
+            match system_deadline {
+                None => cvar.wait(&mut guard),
+                Some(system_deadline) => {
+                    if cvar.wait_until(&mut guard, system_deadline) {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn wake_all(&self) {
+        let (ref lock, ref cvar) = self.wakeup;
+        let _guard = lock.lock();
+        cvar.notify_all();
+    }
+}
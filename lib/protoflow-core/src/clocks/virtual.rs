@@ -0,0 +1,55 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{Duration, Instant},
+    utils::Condvar,
+    Clock,
+};
+use parking_lot::Mutex;
+
+/// A clock that only moves forward when explicitly advanced.
+///
+/// This is useful for deterministically testing blocks that sleep.
+#[derive(Debug, Default)]
+pub struct VirtualClock {
+    now: Mutex<Instant>,
+    wakeup: Condvar,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Moves the clock forward, waking up any threads whose deadline has
+    /// been reached.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock();
+        *now = now.saturating_add(duration);
+        self.wakeup.notify_all();
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock()
+    }
+
+    fn wait_until(&self, deadline: Instant, is_interrupted: &dyn Fn() -> bool) -> bool {
+        let mut now = self.now.lock();
+        loop {
+            if is_interrupted() {
+                return false;
+            }
+            if *now >= deadline {
+                return true;
+            }
+            self.wakeup.wait(&mut now);
+        }
+    }
+
+    fn wake_all(&self) {
+        let _now = self.now.lock();
+        self.wakeup.notify_all();
+    }
+}
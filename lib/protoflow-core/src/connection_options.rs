@@ -0,0 +1,78 @@
+// This is free and unencumbered software released into the public domain.
+
+/// The default number of messages buffered by a connection.
+pub const DEFAULT_CONNECTION_CAPACITY: usize = 1;
+
+/// What happens when a message is sent on a connection whose buffer is full.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum OverflowPolicy {
+    /// The sender blocks until there is room in the buffer.
+    #[default]
+    Block,
+
+    /// The message being sent is dropped.
+    DropNewest,
+
+    /// The oldest buffered message is dropped to make room.
+    DropOldest,
+
+    /// The sender gets a `PortError::Overflow` error.
+    Error,
+}
+
+/// How an output port connected to several input ports distributes its
+/// messages among them.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FanOut {
+    /// Every message is sent to all the input ports.
+    #[default]
+    Broadcast,
+
+    /// Each message is sent to the next input port in turn.
+    RoundRobin,
+}
+
+/// Options for a connection between an output port and an input port.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ConnectionOptions {
+    /// The maximum number of messages buffered by the connection.
+    ///
+    /// The connections fanning into an input port share its buffer, so must
+    /// all agree on its capacity and overflow policy.
+    pub capacity: usize,
+
+    /// What happens when the buffer is full.
+    pub overflow: OverflowPolicy,
+
+    /// How the output port distributes its messages, if it is connected to
+    /// several input ports. The latest connection's policy applies.
+    pub fanout: FanOut,
+}
+
+impl ConnectionOptions {
+    pub const fn new() -> Self {
+        Self {
+            capacity: DEFAULT_CONNECTION_CAPACITY,
+            overflow: OverflowPolicy::Block,
+            fanout: FanOut::Broadcast,
+        }
+    }
+
+    pub const fn with_capacity(self, capacity: usize) -> Self {
+        Self { capacity, ..self }
+    }
+
+    pub const fn with_overflow(self, overflow: OverflowPolicy) -> Self {
+        Self { overflow, ..self }
+    }
+
+    pub const fn with_fanout(self, fanout: FanOut) -> Self {
+        Self { fanout, ..self }
+    }
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
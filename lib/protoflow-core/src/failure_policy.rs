@@ -0,0 +1,16 @@
+// This is free and unencumbered software released into the public domain.
+
+/// What a running system does when one of its blocks fails.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum FailurePolicy {
+    /// Terminates all the other blocks of the system on the first failure.
+    FailFast,
+
+    /// Keeps the other blocks of the system running.
+    #[default]
+    Continue,
+
+    /// Prepares and executes the failed block again, for as long as the
+    /// runtime is alive.
+    Restart,
+}
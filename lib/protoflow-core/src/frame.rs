@@ -0,0 +1,85 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{Bytes, String, ToString, Vec},
+    PortError, PortResult, SharedMessage,
+};
+
+/// A frame on a connection between an output port and an input port.
+///
+/// Data frames carry messages, while control frames carry the end of the
+/// stream, errors, and flush requests.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Frame {
+    /// An encoded message, possibly in an envelope with metadata.
+    Data(Bytes),
+
+    /// A message passed by reference, without being encoded.
+    Shared(SharedMessage),
+
+    /// The end of the stream, after the output port was closed or
+    /// disconnected.
+    Eos,
+
+    /// An error reported by the output port.
+    Error(String),
+
+    /// A request to flush any buffered messages downstream.
+    Flush,
+}
+
+const DATA_TAG: u8 = 0;
+const EOS_TAG: u8 = 1;
+const ERROR_TAG: u8 = 2;
+const FLUSH_TAG: u8 = 3;
+
+impl Frame {
+    /// Checks whether this is a data frame.
+    pub fn is_data(&self) -> bool {
+        matches!(self, Self::Data(_) | Self::Shared(_))
+    }
+
+    /// Checks whether this frame ends the stream.
+    pub fn is_eos(&self) -> bool {
+        matches!(self, Self::Eos)
+    }
+
+    /// Checks whether this is a control frame that can be sent with
+    /// `Transport::send_control`.
+    pub fn is_control(&self) -> bool {
+        matches!(self, Self::Error(_) | Self::Flush)
+    }
+
+    /// Encodes the frame for transports that carry bytes, as a tag byte
+    /// followed by the payload.
+    ///
+    /// Messages passed by reference get encoded.
+    pub fn encode(&self) -> Bytes {
+        let (tag, payload) = match self {
+            Self::Data(message) => (DATA_TAG, message.as_ref()),
+            Self::Shared(message) => return Self::Data(message.encode()).encode(),
+            Self::Eos => (EOS_TAG, &[][..]),
+            Self::Error(error) => (ERROR_TAG, error.as_bytes()),
+            Self::Flush => (FLUSH_TAG, &[][..]),
+        };
+        let mut buffer = Vec::with_capacity(1 + payload.len());
+        buffer.push(tag);
+        buffer.extend_from_slice(payload);
+        Bytes::from(buffer)
+    }
+
+    /// Decodes a frame encoded with [`Frame::encode`].
+    pub fn decode(mut bytes: Bytes) -> PortResult<Self> {
+        if bytes.is_empty() {
+            return Err(PortError::RecvFailed);
+        }
+        let tag = bytes.split_to(1)[0];
+        match tag {
+            DATA_TAG => Ok(Self::Data(bytes)),
+            EOS_TAG => Ok(Self::Eos),
+            ERROR_TAG => Ok(Self::Error(String::from_utf8_lossy(&bytes).to_string())),
+            FLUSH_TAG => Ok(Self::Flush),
+            _ => Err(PortError::RecvFailed),
+        }
+    }
+}
@@ -1,10 +1,14 @@
 // This is free and unencumbered software released into the public domain.
 
 use crate::{
-    prelude::{fmt, Arc, PhantomData},
-    InputPortID, Message, MessageReceiver, Port, PortID, PortResult, PortState, System, Transport,
+    prelude::{fmt, Arc, Bytes, Duration, PhantomData},
+    Frame, InputPortID, Message, MessageMetadata, MessageReceiver, Port, PortError, PortID,
+    PortResult, PortState, SharedMessage, System, Transport,
 };
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[derive(Clone)] //, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub struct InputPort<T: Message> {
     pub(crate) id: InputPortID,
@@ -27,29 +31,132 @@ impl<T: Message> InputPort<T> {
         self.transport.close(PortID::Input(self.id))
     }
 
+    /// Receives a message, blocking until one is available.
+    ///
+    /// Returns `Ok(None)` at the end of the stream, or if the port is closed.
+    /// Returns `Err(PortError::Upstream(_))` if the output port reported an
+    /// error. Returns `Err(PortError::Closed)` if the runtime was shut down.
+    /// Flush requests are skipped.
     pub fn recv(&self) -> PortResult<Option<T>> {
-        match self.transport.recv(self.id)? {
-            None => Ok(None), // EOS (port closed)
-            Some(encoded_message) => {
-                if encoded_message.len() == 0 {
-                    Ok(None) // EOS (port disconnected)
-                } else {
-                    match T::decode_length_delimited(encoded_message) {
-                        Ok(message) => Ok(Some(message)),
-                        Err(err) => Err(err.into()),
+        self.decode(self.recv_data(|| self.transport.recv(self.id))?)
+    }
+
+    /// Receives a message, blocking until one is available or the timeout
+    /// elapses.
+    pub fn recv_timeout(&self, timeout: Duration) -> PortResult<Option<T>> {
+        #[cfg(feature = "std")]
+        if let Some(deadline) = std::time::Instant::now().checked_add(timeout) {
+            return self.recv_deadline(deadline);
+        }
+        self.decode(self.recv_data(|| self.transport.recv_timeout(self.id, timeout))?)
+    }
+
+    /// Receives a message, blocking until one is available or the deadline
+    /// passes.
+    #[cfg(feature = "std")]
+    pub fn recv_deadline(&self, deadline: std::time::Instant) -> PortResult<Option<T>> {
+        self.decode(self.recv_data(|| self.transport.recv_deadline(self.id, deadline))?)
+    }
+
+    /// Receives a message by reference, without decoding it if it was sent
+    /// over a zero-copy transport.
+    pub fn recv_shared(&self) -> PortResult<Option<Arc<T>>> {
+        match self.recv_data(|| self.transport.recv(self.id))? {
+            Some(Frame::Shared(message)) => Self::downcast(message).map(Some),
+            frame => Ok(self.decode(frame)?.map(Arc::new)),
+        }
+    }
+
+    /// Receives a message along with its metadata, which is the default
+    /// metadata if the message was sent without any.
+    pub fn recv_with_metadata(&self) -> PortResult<Option<(T, MessageMetadata)>> {
+        let frame = self.recv_data(|| self.transport.recv(self.id))?;
+        let Some((message, metadata)) = self.decode_envelope(frame)? else {
+            return Ok(None); // EOS
+        };
+        let metadata = match metadata {
+            None => MessageMetadata::default(),
+            Some(metadata) => prost::Message::decode(metadata)?,
+        };
+        Ok(Some((message, metadata)))
+    }
+
+    /// Receives frames until one that isn't a flush request.
+    fn recv_data(&self, recv: impl Fn() -> PortResult<Option<Frame>>) -> PortResult<Option<Frame>> {
+        loop {
+            match recv()? {
+                Some(Frame::Flush) => continue,
+                frame => return Ok(frame),
+            }
+        }
+    }
+
+    pub(crate) fn decode(&self, frame: Option<Frame>) -> PortResult<Option<T>> {
+        Ok(self.decode_envelope(frame)?.map(|(message, _)| message))
+    }
+
+    /// Decodes a received frame into its message and encoded metadata.
+    fn decode_envelope(&self, frame: Option<Frame>) -> PortResult<Option<(T, Option<Bytes>)>> {
+        match frame {
+            None => Ok(None),             // EOS (port closed)
+            Some(Frame::Eos) => Ok(None), // EOS (port disconnected)
+            Some(Frame::Error(error)) => Err(PortError::Upstream(error)),
+            Some(Frame::Flush) => unreachable!("flush requests are skipped"),
+            Some(Frame::Shared(message)) => {
+                let message = Self::downcast(message)?;
+                let message = Arc::try_unwrap(message).unwrap_or_else(|m| (*m).clone());
+                Ok(Some((message, None)))
+            }
+            Some(Frame::Data(encoded_message)) => {
+                let (metadata, message) = MessageMetadata::split_envelope(encoded_message)?;
+                match T::decode_length_delimited(message) {
+                    Ok(message) => Ok(Some((message, metadata))),
+                    Err(err) => Err(err.into()),
+                }
+            }
+        }
+    }
+
+    fn downcast(message: SharedMessage) -> PortResult<Arc<T>> {
+        match message.downcast::<T>() {
+            Ok(message) => Ok(message),
+            // The message is of a different, but wire-compatible, type:
+            Err(message) => Ok(Arc::new(T::decode_length_delimited(message.encode())?)),
+        }
+    }
+
+    /// Receives a message without blocking the current task.
+    ///
+    /// This is cancel-safe on transports that natively support asynchronous
+    /// receives, such as `MpscTransport`. On other transports, the receive is
+    /// carried out by a blocking Tokio task, so this must be called from
+    /// within a Tokio runtime, and a message received after cancellation is
+    /// lost.
+    #[cfg(feature = "tokio")]
+    pub async fn recv_async(&self) -> PortResult<Option<T>> {
+        loop {
+            let frame = match self.transport.recv_async(self.id) {
+                Some(future) => future.await?,
+                None => {
+                    let (transport, input) = (self.transport.clone(), self.id);
+                    match tokio::task::spawn_blocking(move || transport.recv(input)).await {
+                        Ok(result) => result?,
+                        Err(_) => return Err(PortError::Closed), // the runtime is shutting down
                     }
                 }
+            };
+            match frame {
+                Some(Frame::Flush) => continue,
+                frame => return self.decode(frame),
             }
         }
     }
 
     pub fn try_recv(&self) -> PortResult<Option<T>> {
-        match self.transport.try_recv(self.id)? {
-            None => Ok(None), // EOS
-            Some(encoded_message) => match T::decode(encoded_message) {
-                Ok(message) => Ok(Some(message)),
-                Err(err) => Err(err.into()),
-            },
+        match self.recv_data(|| self.transport.try_recv(self.id))? {
+            None => Ok(None), // no message available
+            Some(Frame::Eos) => Err(PortError::Disconnected),
+            frame => self.decode(frame),
         }
     }
 }
@@ -78,6 +185,15 @@ impl<T: Message> MessageReceiver<T> for InputPort<T> {
     fn try_recv(&self) -> PortResult<Option<T>> {
         InputPort::try_recv(self)
     }
+
+    fn recv_timeout(&self, timeout: Duration) -> PortResult<Option<T>> {
+        InputPort::recv_timeout(self, timeout)
+    }
+
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: std::time::Instant) -> PortResult<Option<T>> {
+        InputPort::recv_deadline(self, deadline)
+    }
 }
 
 impl<T: Message> fmt::Display for InputPort<T> {
@@ -4,8 +4,10 @@
 
 use crate::{
     prelude::{fmt, slice, AsRef, Deref, Index},
-    InputPort, Message, MessageReceiver, PortResult, System, Transport,
+    Frame, InputPort, InputPortID, Message, MessageReceiver, PortError, PortResult, System,
+    Transport,
 };
+use core::array;
 
 #[derive(Clone)]
 pub struct InputPorts<T: Message, const N: usize> {
@@ -38,22 +40,84 @@ impl<T: Message, const N: usize> InputPorts<T, N> {
         self.array.get(index)
     }
 
-    pub fn iter(&self) -> slice::Iter<InputPort<T>> {
-        self.into_iter()
+    pub fn iter(&self) -> slice::Iter<'_, InputPort<T>> {
+        self.array.iter()
     }
 
     pub const fn as_slice(&self) -> &[InputPort<T>] {
         self.array.as_slice()
     }
+
+    /// Closes all the ports.
+    ///
+    /// Returns `Ok(true)` if any of the ports was closed by this call.
+    pub fn close(&mut self) -> PortResult<bool> {
+        let mut closed = false;
+        for port in self.array.iter_mut() {
+            closed |= port.close()?;
+        }
+        Ok(closed)
+    }
+
+    /// Receives a message from any of the ports, blocking until one is
+    /// available, and returns it along with the index of its port.
+    ///
+    /// The ports are preferred in turn starting from the given index, so
+    /// that callers can rotate the starting index to avoid starving any
+    /// port. All ports must belong to the same system.
+    ///
+    /// Returns `Ok(None)` once no port is connected anymore.
+    pub fn recv_from(&self, start: usize) -> PortResult<Option<(usize, T)>> {
+        let Some(port) = self.array.first() else {
+            return Ok(None);
+        };
+        let inputs: [InputPortID; N] = array::from_fn(|offset| self.array[(start + offset) % N].id);
+        loop {
+            let Some((offset, frame)) = port.transport.recv_any(&inputs)? else {
+                return Ok(None);
+            };
+            let index = (start + offset) % N;
+            match frame {
+                // Ports that reached their end are skipped from then on:
+                None | Some(Frame::Eos) | Some(Frame::Flush) => continue,
+                frame => return Ok(self.array[index].decode(frame)?.map(|m| (index, m))),
+            }
+        }
+    }
 }
 
 impl<T: Message, const N: usize> MessageReceiver<T> for InputPorts<T, N> {
+    /// Receives a message from any of the ports, blocking until one is
+    /// available.
+    ///
+    /// Returns `Ok(None)` once no port is connected anymore.
     fn recv(&self) -> PortResult<Option<T>> {
-        todo!("InputPort::recv") // TODO
+        Ok(self.recv_from(0)?.map(|(_, message)| message))
     }
 
+    /// Tries to receive a message from any of the ports, returning
+    /// immediately.
+    ///
+    /// Returns `Ok(None)` if no message was immediately available on any
+    /// port still connected.
+    /// Returns `Err(PortError::Disconnected)` if no port is connected anymore.
+    /// Returns `Err(PortError::Closed)` if all ports are closed.
     fn try_recv(&self) -> PortResult<Option<T>> {
-        todo!("InputPort::try_recv") // TODO
+        let (mut connected, mut disconnected) = (0, 0);
+        for port in self.iter() {
+            match port.try_recv() {
+                Ok(Some(message)) => return Ok(Some(message)),
+                Ok(None) => connected += 1,
+                Err(PortError::Closed) => {}
+                Err(PortError::Disconnected) => disconnected += 1,
+                Err(error) => return Err(error),
+            }
+        }
+        match (connected, disconnected) {
+            (0, 0) => Err(PortError::Closed),
+            (0, _) => Err(PortError::Disconnected),
+            _ => Ok(None),
+        }
     }
 }
 
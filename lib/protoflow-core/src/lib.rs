@@ -8,6 +8,11 @@ extern crate self as protoflow_core;
 #[doc(hidden)]
 pub mod prelude;
 
+#[cfg(feature = "tokio")]
+mod async_block;
+#[cfg(feature = "tokio")]
+pub use async_block::*;
+
 mod block;
 pub use block::*;
 
@@ -20,6 +25,20 @@ pub use block_error::*;
 mod block_runtime;
 pub use block_runtime::*;
 
+mod clock;
+pub use clock::*;
+
+pub mod clocks;
+
+mod connection_options;
+pub use connection_options::*;
+
+mod failure_policy;
+pub use failure_policy::*;
+
+mod frame;
+pub use frame::*;
+
 mod function_block;
 pub use function_block::*;
 
@@ -35,6 +54,9 @@ pub use message::*;
 mod message_buffer;
 pub use message_buffer::*;
 
+mod message_metadata;
+pub use message_metadata::*;
+
 mod message_receiver;
 pub use message_receiver::*;
 
@@ -65,14 +87,25 @@ pub use port_state::*;
 mod process;
 pub use process::*;
 
+mod restart_policy;
+pub use restart_policy::*;
+
 mod runtime;
 pub use runtime::*;
 
 pub mod runtimes;
 
+mod shared_message;
+pub use shared_message::*;
+
 mod system;
 pub use system::*;
 
+#[cfg(feature = "std")]
+mod system_handle;
+#[cfg(feature = "std")]
+pub use system_handle::*;
+
 mod transport;
 pub use transport::*;
 
@@ -80,6 +113,16 @@ pub mod transports;
 
 #[allow(unused_imports)]
 pub(crate) mod utils {
+    #[cfg(feature = "std")]
+    mod block_on;
+    #[cfg(feature = "std")]
+    pub use block_on::*;
+
+    #[cfg(feature = "std")]
+    mod condvar;
+    #[cfg(feature = "std")]
+    pub use condvar::*;
+
     mod rw_condvar;
     pub use rw_condvar::*;
 }
@@ -2,7 +2,7 @@
 
 use crate::prelude::{Bytes, String, Vec};
 
-pub trait Message: prost::Message + Clone + Default {}
+pub trait Message: prost::Message + Clone + Default + 'static {}
 
 impl Message for bool {} // google.protobuf.BoolValue
 impl Message for u32 {} // google.protobuf.UInt32Value
@@ -0,0 +1,112 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{BTreeMap, Bytes, String, Vec},
+    types::Timestamp,
+    Message, PortError, PortResult,
+};
+use prost::Message as _;
+
+#[cfg(feature = "std")]
+extern crate std;
+
+/// Metadata carried along with a message, in an envelope.
+///
+/// Envelopes are encoded into the frames that transports deliver, so every
+/// transport carries them. Messages sent without metadata have default
+/// metadata when received.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MessageMetadata {
+    /// When the message was sent.
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: Option<Timestamp>,
+
+    /// The message's position in its stream.
+    #[prost(uint64, tag = "2")]
+    pub sequence: u64,
+
+    /// An ID correlating related messages, such as a request and its
+    /// response.
+    #[prost(string, tag = "3")]
+    pub correlation_id: String,
+
+    /// The trace context, such as a W3C `traceparent` header value.
+    #[prost(string, tag = "4")]
+    pub trace_context: String,
+
+    /// Any other headers.
+    #[prost(btree_map = "string, string", tag = "5")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl MessageMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timestamp(self, timestamp: Timestamp) -> Self {
+        Self {
+            timestamp: Some(timestamp),
+            ..self
+        }
+    }
+
+    /// Timestamps the metadata with the current system time.
+    #[cfg(feature = "std")]
+    pub fn with_current_time(self) -> Self {
+        self.with_timestamp(Timestamp::from(std::time::SystemTime::now()))
+    }
+
+    pub fn with_sequence(self, sequence: u64) -> Self {
+        Self { sequence, ..self }
+    }
+
+    pub fn with_correlation_id(self, correlation_id: impl Into<String>) -> Self {
+        Self {
+            correlation_id: correlation_id.into(),
+            ..self
+        }
+    }
+
+    pub fn with_trace_context(self, trace_context: impl Into<String>) -> Self {
+        Self {
+            trace_context: trace_context.into(),
+            ..self
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Encodes a message in an envelope with this metadata.
+    ///
+    /// The envelope is the length-delimited metadata followed by the
+    /// length-delimited message, and so it is never mistaken for a frame
+    /// with a bare message, whose single length-delimited field spans the
+    /// whole frame.
+    pub(crate) fn encode_envelope<T: Message>(&self, message: &T) -> Bytes {
+        let capacity = prost::length_delimiter_len(self.encoded_len())
+            + self.encoded_len()
+            + prost::length_delimiter_len(message.encoded_len())
+            + message.encoded_len();
+        let mut buffer = Vec::with_capacity(capacity);
+        self.encode_length_delimited(&mut buffer).unwrap();
+        message.encode_length_delimited(&mut buffer).unwrap();
+        Bytes::from(buffer)
+    }
+
+    /// Splits a frame into its encoded metadata, if any, and its
+    /// length-delimited message.
+    pub(crate) fn split_envelope(frame: Bytes) -> PortResult<(Option<Bytes>, Bytes)> {
+        let length = prost::decode_length_delimiter(frame.as_ref())?;
+        let start = prost::length_delimiter_len(length);
+        let end = start.saturating_add(length);
+        match end {
+            end if end == frame.len() => Ok((None, frame)), // a bare message
+            end if end < frame.len() => Ok((Some(frame.slice(start..end)), frame.slice(end..))),
+            _ => Err(PortError::from(prost::DecodeError::new("truncated frame"))),
+        }
+    }
+}
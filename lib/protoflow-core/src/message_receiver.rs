@@ -2,7 +2,13 @@
 
 //! Common methods for receiving messages.
 
-use crate::{prelude::ToString, Message, PortError, PortResult};
+use crate::{
+    prelude::{Duration, ToString},
+    Message, PortError, PortResult,
+};
+
+#[cfg(feature = "std")]
+extern crate std;
 
 pub trait MessageReceiver<T: Message> {
     /// Receives a message, blocking until one is available.
@@ -24,4 +30,27 @@ pub trait MessageReceiver<T: Message> {
     fn try_recv(&self) -> PortResult<Option<T>> {
         Err(PortError::Other("not implemented".to_string()))
     }
+
+    /// Receives a message, blocking until one is available or the timeout
+    /// elapses.
+    ///
+    /// Returns `Ok(Some(message))` if a message was received.
+    /// Returns `Ok(None)` if the port is closed or disconnected.
+    /// Returns `Err(PortError::Timeout)` if the timeout elapsed first.
+    /// Returns `Err(PortError)` if another error occurs.
+    fn recv_timeout(&self, _timeout: Duration) -> PortResult<Option<T>> {
+        Err(PortError::Other("not implemented".to_string()))
+    }
+
+    /// Receives a message, blocking until one is available or the deadline
+    /// passes.
+    ///
+    /// Returns `Ok(Some(message))` if a message was received.
+    /// Returns `Ok(None)` if the port is closed or disconnected.
+    /// Returns `Err(PortError::Timeout)` if the deadline passed first.
+    /// Returns `Err(PortError)` if another error occurs.
+    #[cfg(feature = "std")]
+    fn recv_deadline(&self, deadline: std::time::Instant) -> PortResult<Option<T>> {
+        self.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now()))
+    }
 }
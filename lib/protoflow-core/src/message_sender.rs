@@ -2,7 +2,10 @@
 
 //! Common methods for sending messages.
 
-use crate::{prelude::ToString, Message, PortError, PortResult};
+use crate::{
+    prelude::{Duration, ToString},
+    Message, PortError, PortResult,
+};
 
 pub trait MessageSender<T: Message> {
     /// Sends a message, blocking until it has been sent.
@@ -18,6 +21,21 @@ pub trait MessageSender<T: Message> {
         Err(PortError::Other("not implemented".to_string()))
     }
 
+    /// Sends a message, blocking until it has been sent or the timeout
+    /// elapses.
+    ///
+    /// Returns `Ok(())` if the message was sent.
+    /// Returns `Err(PortError::Timeout)` if the timeout elapsed first.
+    /// Returns `Err(PortError::Disconnected)` if the port is disconnected.
+    /// Returns `Err(PortError::Closed)` if the port is closed.
+    /// Returns `Err(PortError)` if another error occurs.
+    fn send_timeout<'a>(&self, _message: impl Into<&'a T>, _timeout: Duration) -> PortResult<()>
+    where
+        T: 'a,
+    {
+        Err(PortError::Other("not implemented".to_string()))
+    }
+
     /// Tries to send a message, returning immediately.
     ///
     /// Returns `Ok(true)` if the message was sent.
@@ -1,8 +1,9 @@
 // This is free and unencumbered software released into the public domain.
 
 use crate::{
-    prelude::{fmt, Arc, Bytes, PhantomData},
-    Message, MessageSender, OutputPortID, Port, PortID, PortResult, PortState, System, Transport,
+    prelude::{fmt, Arc, Bytes, Duration, PhantomData, ToString},
+    Frame, Message, MessageMetadata, MessageSender, OutputPortID, Port, PortID, PortResult,
+    PortState, SharedMessage, System, Transport,
 };
 
 #[derive(Clone)] //, Debug, Eq, Ord, PartialEq, PartialOrd)]
@@ -32,9 +33,82 @@ impl<T: Message> OutputPort<T> {
         T: 'a,
     {
         let message: &T = message.into();
+        if self.transport.is_zero_copy() {
+            return self.send_shared(Arc::new(message.clone()));
+        }
         let bytes = Bytes::from(message.encode_length_delimited_to_vec());
         self.transport.send(self.id, bytes)
     }
+
+    /// Sends a message in an envelope with the given metadata.
+    pub fn send_with_metadata<'a>(
+        &self,
+        message: impl Into<&'a T>,
+        metadata: &MessageMetadata,
+    ) -> PortResult<()>
+    where
+        T: 'a,
+    {
+        let bytes = metadata.encode_envelope(message.into());
+        self.transport.send(self.id, bytes)
+    }
+
+    /// Sends a message by reference, without encoding it if the transport
+    /// is zero-copy.
+    pub fn send_shared(&self, message: Arc<T>) -> PortResult<()> {
+        let message = SharedMessage::new(message);
+        self.transport.send_shared(self.id, message, None)
+    }
+
+    /// Asks the connected blocks to flush any buffered messages downstream.
+    pub fn flush(&self) -> PortResult<()> {
+        self.transport.send_control(self.id, Frame::Flush)
+    }
+
+    /// Reports an error to the connected blocks, whose next receive fails
+    /// with `PortError::Upstream`.
+    pub fn send_error(&self, error: impl ToString) -> PortResult<()> {
+        let frame = Frame::Error(error.to_string());
+        self.transport.send_control(self.id, frame)
+    }
+
+    /// Sends a message, blocking until it has been sent or the timeout
+    /// elapses.
+    pub fn send_timeout<'a>(&self, message: impl Into<&'a T>, timeout: Duration) -> PortResult<()>
+    where
+        T: 'a,
+    {
+        let message: &T = message.into();
+        if self.transport.is_zero_copy() {
+            let message = SharedMessage::new(Arc::new(message.clone()));
+            return self.transport.send_shared(self.id, message, Some(timeout));
+        }
+        let bytes = Bytes::from(message.encode_length_delimited_to_vec());
+        self.transport.send_timeout(self.id, bytes, timeout)
+    }
+
+    /// Sends a message without blocking the current task.
+    ///
+    /// On transports that don't natively support asynchronous sends, the send
+    /// is carried out by a blocking Tokio task, so this must be called from
+    /// within a Tokio runtime, and the message is still sent after
+    /// cancellation.
+    #[cfg(feature = "tokio")]
+    pub async fn send_async<'a>(&self, message: impl Into<&'a T>) -> PortResult<()>
+    where
+        T: 'a,
+    {
+        let message: &T = message.into();
+        let bytes = Bytes::from(message.encode_length_delimited_to_vec());
+        if let Some(future) = self.transport.send_async(self.id, bytes.clone()) {
+            return future.await;
+        }
+        let (transport, output) = (self.transport.clone(), self.id);
+        match tokio::task::spawn_blocking(move || transport.send(output, bytes)).await {
+            Ok(result) => result,
+            Err(_) => Err(crate::PortError::Closed), // the runtime is shutting down
+        }
+    }
 }
 
 impl<T: Message> Port for OutputPort<T> {
@@ -60,6 +134,13 @@ impl<T: Message> MessageSender<T> for OutputPort<T> {
     {
         OutputPort::send(self, message)
     }
+
+    fn send_timeout<'a>(&self, message: impl Into<&'a T>, timeout: Duration) -> PortResult<()>
+    where
+        T: 'a,
+    {
+        OutputPort::send_timeout(self, message, timeout)
+    }
 }
 
 impl<T: Message> fmt::Display for OutputPort<T> {
@@ -4,7 +4,7 @@
 
 use crate::{
     prelude::{fmt, slice, AsRef, Deref, Index},
-    Message, MessageSender, OutputPort, PortResult, System, Transport,
+    Message, MessageSender, OutputPort, Port, PortError, PortResult, System, Transport,
 };
 
 #[derive(Clone)]
@@ -38,21 +38,44 @@ impl<T: Message, const N: usize> OutputPorts<T, N> {
         self.array.get(index)
     }
 
-    pub fn iter(&self) -> slice::Iter<OutputPort<T>> {
-        self.into_iter()
+    pub fn iter(&self) -> slice::Iter<'_, OutputPort<T>> {
+        self.array.iter()
     }
 
     pub const fn as_slice(&self) -> &[OutputPort<T>] {
         self.array.as_slice()
     }
+
+    /// Closes all the ports.
+    ///
+    /// Returns `Ok(true)` if any of the ports was closed by this call.
+    pub fn close(&mut self) -> PortResult<bool> {
+        let mut closed = false;
+        for port in self.array.iter_mut() {
+            closed |= port.close()?;
+        }
+        Ok(closed)
+    }
 }
 
 impl<T: Message, const N: usize> MessageSender<T> for OutputPorts<T, N> {
-    fn send<'a>(&self, _message: impl Into<&'a T>) -> PortResult<()>
+    /// Sends a message to all the connected ports.
+    ///
+    /// Returns `Err(PortError::Disconnected)` if no port is connected.
+    fn send<'a>(&self, message: impl Into<&'a T>) -> PortResult<()>
     where
         T: 'a,
     {
-        todo!("OutputPorts::send") // TODO
+        let message: &T = message.into();
+        let mut sent = false;
+        for port in self.iter().filter(|port| port.is_connected()) {
+            port.send(message)?;
+            sent = true;
+        }
+        match sent {
+            true => Ok(()),
+            false => Err(PortError::Disconnected),
+        }
     }
 }
 
@@ -6,6 +6,8 @@ use crate::{
 };
 
 pub struct PortDescriptor {
+    /// The ID of this port, if it is backed by a transport.
+    id: Option<PortID>,
     /// The current state of this port.
     state: PortState,
     /// The machine-readable name of this port.
@@ -16,7 +18,7 @@ pub struct PortDescriptor {
 
 impl Port for PortDescriptor {
     fn id(&self) -> Option<PortID> {
-        None
+        self.id
     }
 
     fn state(&self) -> PortState {
@@ -35,6 +37,7 @@ impl Port for PortDescriptor {
 impl<T: Message> From<&InputPort<T>> for PortDescriptor {
     fn from(port: &InputPort<T>) -> Self {
         Self {
+            id: port.id(),
             state: port.state(),
             name: port.name().map(|s| s.to_string()),
             label: port.label().map(|s| s.to_string()),
@@ -45,6 +48,7 @@ impl<T: Message> From<&InputPort<T>> for PortDescriptor {
 impl<T: Message> From<&OutputPort<T>> for PortDescriptor {
     fn from(port: &OutputPort<T>) -> Self {
         Self {
+            id: port.id(),
             state: port.state(),
             name: port.name().map(|s| s.to_string()),
             label: port.label().map(|s| s.to_string()),
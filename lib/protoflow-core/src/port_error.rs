@@ -15,9 +15,13 @@ pub enum PortError {
     Invalid(PortID),
     Closed,
     Disconnected,
+    Timeout,
+    Overflow,
     RecvFailed,
     SendFailed,
     DecodeFailed(DecodeError),
+    /// An error reported by the connected output port.
+    Upstream(String),
     Other(String),
 }
 
@@ -27,9 +31,12 @@ impl fmt::Display for PortError {
             Self::Invalid(port) => write!(f, "Port #{} is invalid", port),
             Self::Closed => write!(f, "Port is closed"),
             Self::Disconnected => write!(f, "Port is not connected"),
+            Self::Timeout => write!(f, "Port operation timed out"),
+            Self::Overflow => write!(f, "Port buffer is full"),
             Self::RecvFailed => write!(f, "Port receive failed"),
             Self::SendFailed => write!(f, "Port send failed"),
             Self::DecodeFailed(error) => write!(f, "Port decode failed: {}", error),
+            Self::Upstream(message) => write!(f, "Port upstream failed: {}", message),
             Self::Other(message) => write!(f, "{}", message),
         }
     }
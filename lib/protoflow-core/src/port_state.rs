@@ -1,6 +1,6 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::PortID;
+use crate::{FanOut, PortID};
 
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum PortState {
@@ -8,6 +8,12 @@ pub enum PortState {
     Closed,
     Open,
     Connected(PortID),
+    /// An output port connected to several input ports, with the number of
+    /// input ports.
+    FannedOut(FanOut, usize),
+    /// An input port connected to several output ports, with the number of
+    /// output ports.
+    FannedIn(usize),
 }
 
 impl PortState {
@@ -23,7 +29,10 @@ impl PortState {
 
     /// Checks whether the port state is currently connected.
     pub fn is_connected(&self) -> bool {
-        matches!(self, PortState::Connected(_))
+        matches!(
+            self,
+            PortState::Connected(_) | PortState::FannedOut(..) | PortState::FannedIn(_)
+        )
     }
 
     pub fn to_str(&self) -> &str {
@@ -31,7 +40,7 @@ impl PortState {
         match self {
             Closed => "closed",
             Open => "open",
-            Connected(_) => "connected",
+            Connected(_) | FannedOut(..) | FannedIn(_) => "connected",
         }
     }
 }
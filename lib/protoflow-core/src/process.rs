@@ -1,11 +1,77 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::BlockResult;
+use crate::{
+    prelude::{Duration, Rc, String, Vec},
+    BlockError, BlockFailure, BlockResult,
+};
 
 pub type ProcessID = usize;
 
 pub trait Process {
     fn id(&self) -> ProcessID;
+
+    /// The name of the block this process is executing, if any.
+    fn name(&self) -> Option<String> {
+        None
+    }
+
     fn is_alive(&self) -> bool;
     fn join(&self) -> BlockResult;
+
+    /// Requests a cooperative shutdown, returning immediately.
+    ///
+    /// All ports are closed, discarding any messages still buffered on them,
+    /// and blocks blocked on receiving, sending, sleeping, or waiting are
+    /// woken up with `BlockError::Terminated`.
+    ///
+    /// By default, this does nothing, leaving the process to exit on its own.
+    fn shutdown(&self) -> BlockResult {
+        Ok(())
+    }
+
+    /// Requests a cooperative shutdown, and waits for the process to exit.
+    fn terminate(&self) -> BlockResult {
+        self.shutdown()?;
+        self.join()
+    }
+
+    /// Requests a graceful shutdown, and waits for the process to exit.
+    ///
+    /// The blocks without input ports are stopped first, and the other
+    /// blocks are given until the timeout elapses to drain the messages
+    /// already sent to them, after which the process is shut down as per
+    /// [`Process::shutdown`].
+    ///
+    /// By default, this shuts down the process right away, as per
+    /// [`Process::terminate`].
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        let _ = timeout;
+        self.terminate()
+    }
+}
+
+/// Joins all the given processes, reporting every failed one.
+///
+/// Processes that were merely terminated are not reported as failures.
+pub(crate) fn join_all(processes: &[Rc<dyn Process>]) -> BlockResult {
+    let mut failures = Vec::new();
+    let mut is_terminated = false;
+    for process in processes {
+        match process.join() {
+            Ok(()) => {}
+            Err(BlockError::Terminated) => is_terminated = true,
+            Err(error) => failures.push(BlockFailure {
+                name: process.name(),
+                process_id: process.id(),
+                error,
+            }),
+        }
+    }
+    if !failures.is_empty() {
+        Err(BlockError::Failed(failures))
+    } else if is_terminated {
+        Err(BlockError::Terminated)
+    } else {
+        Ok(())
+    }
 }
@@ -0,0 +1,197 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{Arc, AtomicBool, Box, Duration, String, Vec},
+    Block, BlockDescriptor, BlockResult, BlockRuntime, PortDescriptor,
+};
+
+#[cfg(feature = "tokio")]
+use crate::AsyncBlock;
+
+/// When a supervised block gets restarted.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Restart {
+    /// The block is never restarted.
+    #[default]
+    Never,
+
+    /// The block is restarted when it fails or panics.
+    OnFailure,
+
+    /// The block is restarted whenever it exits, successfully or not.
+    Always,
+}
+
+/// How a supervised block gets restarted.
+///
+/// A restarted block is a fresh clone of the block as it was first added to
+/// the system, prepared and executed again using the same ports.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RestartPolicy {
+    /// When the block gets restarted.
+    pub restart: Restart,
+
+    /// The maximum number of restarts, if any.
+    pub max_restarts: Option<usize>,
+
+    /// The delay before the first restart, doubled after each restart.
+    pub backoff: Duration,
+
+    /// The maximum delay between restarts.
+    pub max_backoff: Duration,
+}
+
+impl RestartPolicy {
+    /// A policy that never restarts the block.
+    pub const fn never() -> Self {
+        Self::new(Restart::Never)
+    }
+
+    /// A policy that restarts the block when it fails or panics.
+    pub const fn on_failure() -> Self {
+        Self::new(Restart::OnFailure)
+    }
+
+    /// A policy that restarts the block whenever it exits.
+    pub const fn always() -> Self {
+        Self::new(Restart::Always)
+    }
+
+    pub const fn new(restart: Restart) -> Self {
+        Self {
+            restart,
+            max_restarts: None,
+            backoff: Duration::ZERO,
+            max_backoff: Duration::MAX,
+        }
+    }
+
+    pub const fn with_max_restarts(self, max_restarts: usize) -> Self {
+        Self {
+            max_restarts: Some(max_restarts),
+            ..self
+        }
+    }
+
+    pub const fn with_backoff(self, backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            backoff,
+            max_backoff,
+            ..self
+        }
+    }
+
+    /// Returns the delay before the given restart, counting from zero.
+    pub fn backoff_for(&self, restart: usize) -> Duration {
+        let factor = 1u32.checked_shl(restart.min(31) as u32).unwrap_or(u32::MAX);
+        self.backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+/// A block to execute, together with how to restart it.
+pub(crate) struct SupervisedBlock {
+    pub(crate) block: AnyBlock,
+    pub(crate) restart: RestartPolicy,
+    /// Creates a fresh clone of the block, if possible.
+    pub(crate) respawn: Option<Box<dyn Fn() -> AnyBlock + Send>>,
+    /// Set once the block is removed from its system, so that it doesn't
+    /// get restarted.
+    pub(crate) removed: Arc<AtomicBool>,
+}
+
+impl SupervisedBlock {
+    pub(crate) fn new(block: Box<dyn Block>) -> Self {
+        Self {
+            block: AnyBlock::Sync(block),
+            restart: RestartPolicy::never(),
+            respawn: None,
+            removed: Arc::default(),
+        }
+    }
+}
+
+/// A synchronous or an asynchronous block.
+pub(crate) enum AnyBlock {
+    Sync(Box<dyn Block>),
+    #[cfg(feature = "tokio")]
+    Async(Box<dyn AsyncBlock>),
+}
+
+impl AnyBlock {
+    /// Prepares and executes the block on the current thread.
+    ///
+    /// Asynchronous blocks are driven to completion in place, suspending the
+    /// current `PoolRuntime` task, if any, while they await.
+    pub(crate) fn run(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        match self {
+            Self::Sync(block) => {
+                Block::prepare(block.as_mut(), runtime)?;
+                Block::execute(block.as_mut(), runtime)
+            }
+            #[cfg(feature = "tokio")]
+            Self::Async(_) => crate::utils::block_on(self.run_async(runtime)),
+        }
+    }
+
+    /// Prepares and executes the block as an asynchronous task.
+    ///
+    /// Synchronous blocks are executed in place, blocking the task.
+    #[cfg(feature = "tokio")]
+    pub(crate) async fn run_async(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        match self {
+            Self::Sync(block) => {
+                Block::prepare(block.as_mut(), runtime)?;
+                Block::execute(block.as_mut(), runtime)
+            }
+            Self::Async(block) => {
+                block.prepare(runtime).await?;
+                block.execute(runtime).await
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn is_async(&self) -> bool {
+        !matches!(self, Self::Sync(_))
+    }
+}
+
+impl BlockDescriptor for AnyBlock {
+    fn name(&self) -> Option<String> {
+        match self {
+            Self::Sync(block) => block.name(),
+            #[cfg(feature = "tokio")]
+            Self::Async(block) => block.name(),
+        }
+    }
+
+    fn label(&self) -> Option<String> {
+        match self {
+            Self::Sync(block) => block.label(),
+            #[cfg(feature = "tokio")]
+            Self::Async(block) => block.label(),
+        }
+    }
+
+    fn inputs(&self) -> Vec<PortDescriptor> {
+        match self {
+            Self::Sync(block) => block.inputs(),
+            #[cfg(feature = "tokio")]
+            Self::Async(block) => block.inputs(),
+        }
+    }
+
+    fn outputs(&self) -> Vec<PortDescriptor> {
+        match self {
+            Self::Sync(block) => block.outputs(),
+            #[cfg(feature = "tokio")]
+            Self::Async(block) => block.outputs(),
+        }
+    }
+}
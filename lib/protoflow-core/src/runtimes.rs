@@ -5,6 +5,25 @@ mod std;
 #[cfg(feature = "std")]
 pub use std::*;
 
+#[cfg(feature = "pool")]
+mod pool;
+#[cfg(feature = "pool")]
+pub use pool::*;
+
+/// Runs a blocking operation that the pool can't suspend.
+///
+/// Without the `pool` feature, there is no `PoolRuntime`, and this simply
+/// calls `f`.
+#[cfg(not(feature = "pool"))]
+pub fn block_in_place<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tokio")]
+pub use tokio::*;
+
 //#[cfg(feature = "web")]
 //mod web;
 //#[cfg(feature = "web")]
@@ -0,0 +1,588 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{
+        Arc, AtomicUsize, BTreeMap, Box, Duration, Instant, Ordering, Range, Rc, String, Vec,
+        VecDeque,
+    },
+    process::join_all,
+    restart_policy::SupervisedBlock,
+    runtimes::StdRuntime,
+    transport::Transport,
+    transports::MpscTransport,
+    utils, Block, BlockDescriptor, BlockResult, BlockRuntime, FailurePolicy, Port, Process,
+    ProcessID, Runtime, System,
+};
+use core::sync::atomic::AtomicPtr;
+use corosensei::{stack::DefaultStack, Coroutine, CoroutineResult, Yielder};
+use parking_lot::{Condvar, Mutex};
+
+extern crate std;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The stack size of each task, the same as the default for Rust threads.
+const TASK_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+std::thread_local! {
+    /// The task that the current thread is executing, if any.
+    static CURRENT_TASK: std::cell::RefCell<Option<Arc<Task>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Runs a blocking operation that the pool can't suspend, handing the
+/// current worker thread's slot in its pool (if any) over to another thread
+/// for the duration of the operation.
+///
+/// Waiting on the ports of the MPSC transport, on a timer, or for a
+/// connection suspends the current block instead, returning its worker
+/// thread to the pool. Other transports call this around their blocking
+/// operations, so that they don't keep the other blocks scheduled on a
+/// `PoolRuntime` from making progress, at the cost of an extra thread. Outside
+/// of a pool worker thread, this simply calls `f`.
+pub fn block_in_place<R>(f: impl FnOnce() -> R) -> R {
+    let task = CURRENT_TASK.with(|task| task.borrow_mut().take());
+    let Some(task) = task else {
+        return f(); // not a worker thread, or already in place
+    };
+    task.pool.enter_blocking();
+    let result = f();
+    task.pool.leave_blocking();
+    CURRENT_TASK.with(|current| *current.borrow_mut() = Some(task));
+    result
+}
+
+/// A runtime that multiplexes the blocks of a system onto a worker pool.
+///
+/// Each block executes as a task, with a stack of its own, on one of
+/// `worker_count` worker threads. A block waiting on a port, a timer, or a
+/// connection is suspended, returning its worker thread to the pool for the
+/// next scheduled block, and is resumed once woken up. Hence, any number of
+/// blocked blocks are multiplexed onto the same `worker_count` threads.
+///
+/// A suspended block may be resumed on another worker thread, which is why
+/// creating the runtime is `unsafe`: see [`PoolRuntime::new`]. Blocking
+/// operations that can't be suspended go through [`block_in_place`].
+///
+/// Requires the `pool` feature.
+pub struct PoolRuntime<T: Transport = MpscTransport> {
+    runtime: Arc<StdRuntime<T>>,
+    pool: Arc<WorkerPool>,
+}
+
+impl<T: Transport> PoolRuntime<T> {
+    /// Creates a pool runtime that executes blocks on `worker_count` workers,
+    /// using the transport and clock of the given runtime.
+    ///
+    /// # Safety
+    ///
+    /// A block executed by the runtime may be suspended on one worker thread
+    /// and resumed on another whenever it sends, receives, sleeps, or waits
+    /// for a connection. The caller must ensure that no block executed by
+    /// the runtime holds a value that isn't `Send` across those operations,
+    /// such as an `Rc`, a `MutexGuard`, or a borrow of thread-local state.
+    pub unsafe fn new(runtime: &Arc<StdRuntime<T>>, worker_count: usize) -> BlockResult<Arc<Self>> {
+        Ok(Arc::new(Self {
+            runtime: runtime.clone(),
+            pool: Arc::new(WorkerPool::new(worker_count.max(1))),
+        }))
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.pool.worker_count
+    }
+
+    /// Returns the number of OS threads currently owned by the pool.
+    pub fn thread_count(&self) -> usize {
+        self.pool.state.lock().threads
+    }
+
+    /// Marks the runtime as terminated, waking up all sleeping blocks and
+    /// closing all ports.
+    pub fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    /// Shuts down the runtime gracefully, as per
+    /// [`StdRuntime::shutdown_timeout`].
+    pub fn shutdown_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)
+    }
+}
+
+impl<T: Transport> Drop for PoolRuntime<T> {
+    fn drop(&mut self) {
+        self.pool.shutdown();
+    }
+}
+
+impl<T: Transport + 'static> PoolRuntime<T> {
+    fn spawn_block(
+        self: &Arc<Self>,
+        block: SupervisedBlock,
+        policy: FailurePolicy,
+    ) -> BlockResult<Rc<dyn Process>> {
+        self.runtime.register_block(&block);
+        let block_runtime = Arc::new(self.clone()) as Arc<dyn BlockRuntime>;
+        let runtime = self.runtime.clone();
+        let block_process = Rc::new(PooledBlock {
+            id: self.runtime.process_id.fetch_add(1, Ordering::SeqCst),
+            name: block.block.name(),
+            runtime: self.clone(),
+            result: Arc::new((Mutex::new(None), utils::Condvar::new())),
+        });
+        let result = block_process.result.clone();
+        self.pool.submit(Box::new(move || {
+            let block_result = runtime.run_block(block, block_runtime.as_ref(), policy);
+            let (ref lock, ref cvar) = *result;
+            *lock.lock() = Some(block_result);
+            cvar.notify_all();
+        }))?;
+        Ok(block_process)
+    }
+}
+
+impl<T: Transport + 'static> Runtime for Arc<PoolRuntime<T>> {
+    fn execute_block(&mut self, block: Box<dyn Block>) -> BlockResult<Rc<dyn Process>> {
+        self.spawn_block(SupervisedBlock::new(block), FailurePolicy::default())
+    }
+
+    fn execute<X: Transport + Default>(
+        &mut self,
+        system: System<X>,
+    ) -> BlockResult<Rc<dyn Process>> {
+        let mut system_process = PooledSystem {
+            id: self.runtime.process_id.fetch_add(1, Ordering::SeqCst),
+            runtime: self.clone(),
+            blocks: Vec::new(),
+        };
+
+        while let Some(block) = system.blocks.borrow_mut().pop_front() {
+            let block_process = self.spawn_block(block, system.failure_policy)?;
+            system_process.blocks.push(block_process);
+        }
+
+        Ok(Rc::new(system_process))
+    }
+}
+
+impl<T: Transport> BlockRuntime for Arc<PoolRuntime<T>> {
+    fn is_alive(&self) -> bool {
+        self.runtime.is_alive()
+    }
+
+    fn now(&self) -> Instant {
+        self.runtime.now()
+    }
+
+    fn sleep_for(&self, duration: Duration) -> BlockResult {
+        self.runtime.sleep_for(duration)
+    }
+
+    fn sleep_until(&self, instant: Instant) -> BlockResult {
+        self.runtime.sleep_until(instant)
+    }
+
+    fn wait_for(&self, port: &dyn Port) -> BlockResult {
+        self.runtime.wait_for(port)
+    }
+
+    fn wait_for_timeout(&self, port: &dyn Port, timeout: Duration) -> BlockResult<bool> {
+        self.runtime.wait_for_timeout(port, timeout)
+    }
+
+    fn yield_now(&self) -> BlockResult {
+        self.runtime.yield_now()
+    }
+
+    fn random_duration(&self, range: Range<Duration>) -> Duration {
+        self.runtime.random_duration(range)
+    }
+}
+
+struct PooledBlock<T: Transport> {
+    id: ProcessID,
+    name: Option<String>,
+    runtime: Arc<PoolRuntime<T>>,
+    result: Arc<(Mutex<Option<BlockResult>>, utils::Condvar)>,
+}
+
+impl<T: Transport> Process for PooledBlock<T> {
+    fn id(&self) -> ProcessID {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn is_alive(&self) -> bool {
+        self.result.0.lock().is_none()
+    }
+
+    fn join(&self) -> BlockResult {
+        let (ref lock, ref cvar) = *self.result;
+        let mut result = lock.lock();
+        while result.is_none() {
+            cvar.wait(&mut result);
+        }
+        result.take().unwrap()
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
+    }
+}
+
+struct PooledSystem<T: Transport> {
+    id: ProcessID,
+    runtime: Arc<PoolRuntime<T>>,
+    blocks: Vec<Rc<dyn Process>>,
+}
+
+impl<T: Transport> Process for PooledSystem<T> {
+    fn id(&self) -> ProcessID {
+        self.id
+    }
+
+    fn is_alive(&self) -> bool {
+        self.blocks.iter().any(|block| block.is_alive())
+    }
+
+    fn join(&self) -> BlockResult {
+        join_all(&self.blocks)
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
+    }
+}
+
+struct WorkerPool {
+    worker_count: usize,
+    state: Mutex<WorkerPoolState>,
+    job_available: Condvar,
+}
+
+#[derive(Default)]
+struct WorkerPoolState {
+    /// Tasks waiting for a worker.
+    queue: VecDeque<Arc<Task>>,
+    /// Suspended tasks to wake up once their deadline passes, by deadline.
+    timers: BTreeMap<TimerKey, TaskWaker>,
+    /// The number of timers ever set, to tell apart equal deadlines.
+    timer_count: usize,
+    /// Threads currently running a task that isn't blocked.
+    active: usize,
+    /// Threads currently waiting for a task.
+    idle: usize,
+    /// All threads owned by the pool.
+    threads: usize,
+    is_shutdown: bool,
+}
+
+/// A timer's deadline, and the number of timers set before it.
+type TimerKey = (std::time::Instant, usize);
+
+impl WorkerPool {
+    fn new(worker_count: usize) -> Self {
+        Self {
+            worker_count,
+            state: Mutex::new(WorkerPoolState::default()),
+            job_available: Condvar::new(),
+        }
+    }
+
+    fn submit(self: &Arc<Self>, job: Job) -> BlockResult {
+        let task = Task::new(self.clone(), job)?;
+        let mut state = self.state.lock();
+        state.queue.push_back(task);
+        self.schedule(&mut state);
+        Ok(())
+    }
+
+    fn shutdown(&self) {
+        let mut state = self.state.lock();
+        state.is_shutdown = true;
+        self.job_available.notify_all();
+    }
+
+    /// Queues a suspended task to be resumed.
+    fn wake(self: &Arc<Self>, task: &Arc<Task>) {
+        let mut state = self.state.lock();
+        if Self::make_runnable(&mut state, task) {
+            self.schedule(&mut state);
+        }
+    }
+
+    /// Marks a task as woken up, queueing it if it's suspended.
+    ///
+    /// Returns `true` if the task was queued.
+    fn make_runnable(state: &mut WorkerPoolState, task: &Arc<Task>) -> bool {
+        loop {
+            let (current, new) = match task.state.load(Ordering::Acquire) {
+                TASK_RUNNING => (TASK_RUNNING, TASK_NOTIFIED),
+                TASK_SUSPENDED => (TASK_SUSPENDED, TASK_QUEUED),
+                _ => return false, // already woken up, or done
+            };
+            let exchanged =
+                task.state
+                    .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire);
+            if exchanged.is_ok() {
+                if new == TASK_QUEUED {
+                    state.queue.push_back(task.clone());
+                    return true;
+                }
+                return false; // requeued once it suspends
+            }
+        }
+    }
+
+    /// Wakes up a suspended task once the deadline passes, returning the
+    /// key to cancel the timer with.
+    fn set_timer(&self, deadline: std::time::Instant, waker: TaskWaker) -> TimerKey {
+        let mut state = self.state.lock();
+        state.timer_count += 1;
+        let key = (deadline, state.timer_count);
+        let is_earliest = state
+            .timers
+            .keys()
+            .next()
+            .map_or(true, |first| key < *first);
+        state.timers.insert(key, waker);
+        if is_earliest {
+            self.job_available.notify_all(); // for idle threads to wait less
+        }
+        key
+    }
+
+    /// Removes a timer, if it hasn't expired yet.
+    fn cancel_timer(&self, key: TimerKey) {
+        self.state.lock().timers.remove(&key);
+    }
+
+    /// Queues the tasks whose timers have expired.
+    fn expire_timers(state: &mut WorkerPoolState) {
+        let now = std::time::Instant::now();
+        while let Some(entry) = state.timers.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            let waker = entry.remove();
+            if waker.is_current() {
+                Self::make_runnable(state, &waker.task);
+            }
+        }
+    }
+
+    /// Hands queued tasks to idle threads, spawning new threads as needed,
+    /// while fewer than `worker_count` tasks are actively running.
+    fn schedule(self: &Arc<Self>, state: &mut WorkerPoolState) {
+        Self::expire_timers(state);
+        let runnable = self.worker_count.saturating_sub(state.active);
+        let wanted = state.queue.len().min(runnable);
+        if wanted == 0 {
+            return;
+        }
+        self.job_available.notify_all();
+        for _ in state.idle..wanted {
+            state.threads += 1;
+            let pool = self.clone();
+            std::thread::Builder::new()
+                .name("protoflow-worker".into())
+                .spawn(move || pool.run_worker())
+                .unwrap();
+        }
+        state.idle = state.idle.max(wanted); // count the new threads as idle
+    }
+
+    fn run_worker(self: Arc<Self>) {
+        let mut state = self.state.lock();
+        loop {
+            Self::expire_timers(&mut state);
+            if state.active < self.worker_count {
+                if let Some(task) = state.queue.pop_front() {
+                    state.idle -= 1;
+                    state.active += 1;
+                    drop(state);
+                    task.resume();
+                    state = self.state.lock();
+                    state.active -= 1;
+                    state.idle += 1;
+                    self.schedule(&mut state);
+                    continue;
+                }
+            }
+            if state.is_shutdown || state.idle > self.worker_count {
+                break; // retire spare threads
+            }
+            match state.timers.keys().next() {
+                None => self.job_available.wait(&mut state),
+                Some(&(deadline, _)) => {
+                    self.job_available.wait_until(&mut state, deadline);
+                }
+            }
+        }
+        state.idle -= 1;
+        state.threads -= 1;
+    }
+
+    fn enter_blocking(self: &Arc<Self>) {
+        let mut state = self.state.lock();
+        state.active -= 1;
+        self.schedule(&mut state);
+    }
+
+    fn leave_blocking(&self) {
+        let mut state = self.state.lock();
+        state.active += 1;
+    }
+}
+
+const TASK_QUEUED: usize = 0;
+const TASK_RUNNING: usize = 1;
+/// Running, but woken up before it could suspend.
+const TASK_NOTIFIED: usize = 2;
+const TASK_SUSPENDED: usize = 3;
+const TASK_DONE: usize = 4;
+
+/// A job executing on a stack of its own, which can suspend itself while
+/// waiting, and be resumed on any worker thread of its pool.
+pub(crate) struct Task {
+    pool: Arc<WorkerPool>,
+    coroutine: Mutex<Option<TaskCoroutine>>,
+    state: AtomicUsize,
+    /// The number of times the task has waited, to ignore stale wakeups.
+    waits: AtomicUsize,
+    /// The yielder of the coroutine, set once it starts executing.
+    yielder: AtomicPtr<Yielder<(), ()>>,
+}
+
+struct TaskCoroutine(Coroutine<(), (), ()>);
+
+// SAFETY: A task's coroutine is only ever resumed by one worker thread at a
+// time, and suspends itself only from within `utils::Condvar`, after having
+// released the lock it waits on. The caller of `PoolRuntime::new` ensures
+// that blocks don't hold any other values that aren't `Send` across the
+// operations that suspend them.
+unsafe impl Send for TaskCoroutine {}
+
+impl Task {
+    fn new(pool: Arc<WorkerPool>, job: Job) -> BlockResult<Arc<Self>> {
+        let stack = DefaultStack::new(TASK_STACK_SIZE)?;
+        let coroutine = Coroutine::with_stack(stack, move |yielder: &Yielder<(), ()>, ()| {
+            if let Some(task) = Task::current() {
+                let yielder = yielder as *const Yielder<(), ()>;
+                task.yielder.store(yielder.cast_mut(), Ordering::Release);
+            }
+            job();
+        });
+        Ok(Arc::new(Self {
+            pool,
+            coroutine: Mutex::new(Some(TaskCoroutine(coroutine))),
+            state: AtomicUsize::new(TASK_QUEUED),
+            waits: AtomicUsize::new(0),
+            yielder: AtomicPtr::new(core::ptr::null_mut()),
+        }))
+    }
+
+    /// Returns the task that the current thread is executing, if any.
+    pub(crate) fn current() -> Option<Arc<Self>> {
+        CURRENT_TASK.with(|task| task.borrow().clone())
+    }
+
+    /// Starts a new wait, returning a waker for it.
+    pub(crate) fn waker(self: &Arc<Self>) -> TaskWaker {
+        TaskWaker {
+            task: self.clone(),
+            wait: self.waits.fetch_add(1, Ordering::AcqRel) + 1,
+        }
+    }
+
+    /// Suspends the current task, which must be this one, until woken up,
+    /// or until the optional deadline passes.
+    pub(crate) fn suspend(self: &Arc<Self>, deadline: Option<std::time::Instant>) {
+        let timer = deadline.map(|deadline| {
+            let waker = TaskWaker {
+                task: self.clone(),
+                wait: self.waits.load(Ordering::Acquire),
+            };
+            self.pool.set_timer(deadline, waker)
+        });
+        let yielder = self.yielder.load(Ordering::Acquire);
+        // SAFETY: The current task is executing on its coroutine's stack,
+        // which holds the yielder for as long as the coroutine runs.
+        unsafe { &*yielder }.suspend(());
+        if let Some(timer) = timer {
+            self.pool.cancel_timer(timer); // in case it was woken up early
+        }
+    }
+
+    /// Resumes the task on the current thread, until it suspends itself or
+    /// is done.
+    fn resume(self: &Arc<Self>) {
+        self.state.store(TASK_RUNNING, Ordering::Release);
+        let mut coroutine = self.coroutine.lock();
+        let Some(TaskCoroutine(ref mut running)) = *coroutine else {
+            return; // already done
+        };
+        CURRENT_TASK.with(|task| *task.borrow_mut() = Some(self.clone()));
+        let result = running.resume(());
+        CURRENT_TASK.with(|task| task.borrow_mut().take());
+        match result {
+            CoroutineResult::Yield(()) => {
+                let suspended = self.state.compare_exchange(
+                    TASK_RUNNING,
+                    TASK_SUSPENDED,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+                if suspended.is_err() {
+                    // Woken up while suspending, so requeue it right away:
+                    self.state.store(TASK_QUEUED, Ordering::Release);
+                    let mut state = self.pool.state.lock();
+                    state.queue.push_back(self.clone());
+                }
+            }
+            CoroutineResult::Return(()) => {
+                self.state.store(TASK_DONE, Ordering::Release);
+                *coroutine = None; // free the stack
+            }
+        }
+    }
+}
+
+/// Wakes up a suspended task, unless it has moved on from the wait it was
+/// created for.
+#[derive(Clone)]
+pub(crate) struct TaskWaker {
+    task: Arc<Task>,
+    wait: usize,
+}
+
+impl TaskWaker {
+    pub(crate) fn wake(&self) {
+        if self.is_current() {
+            self.task.pool.wake(&self.task);
+        }
+    }
+
+    /// Checks whether this waker is for the same wait as the other.
+    pub(crate) fn is(&self, other: &TaskWaker) -> bool {
+        Arc::ptr_eq(&self.task, &other.task) && self.wait == other.wait
+    }
+
+    fn is_current(&self) -> bool {
+        self.task.waits.load(Ordering::Acquire) == self.wait
+    }
+}
@@ -1,14 +1,21 @@
 // This is free and unencumbered software released into the public domain.
 
 use crate::{
+    clocks::SystemClock,
     prelude::{
-        Arc, AtomicBool, AtomicUsize, Box, Duration, Instant, Ordering, Range, Rc, RefCell,
+        Arc, AtomicBool, AtomicUsize, Box, Duration, Instant, Ordering, Range, Rc, RefCell, String,
         ToString, Vec,
     },
+    process::join_all,
+    restart_policy::SupervisedBlock,
     transport::Transport,
     transports::MpscTransport,
-    Block, BlockError, BlockResult, BlockRuntime, Port, Process, ProcessID, Runtime, System,
+    utils::Condvar,
+    Block, BlockDescriptor, BlockError, BlockResult, BlockRuntime, Clock, FailurePolicy,
+    OutputPortID, Port, PortError, PortID, Process, ProcessID, Restart, RestartPolicy, Runtime,
+    System,
 };
+use parking_lot::Mutex;
 
 #[cfg(feature = "std")]
 extern crate std;
@@ -17,34 +24,149 @@ extern crate std;
 pub struct StdRuntime<T: Transport = MpscTransport> {
     pub(crate) transport: Arc<T>,
     is_alive: AtomicBool,
-    process_id: AtomicUsize,
+    is_draining: AtomicBool,
+    pub(crate) process_id: AtomicUsize,
+    clock: Arc<dyn Clock>,
+    /// The output ports of the blocks without input ports, which are closed
+    /// first on a graceful shutdown.
+    sources: Mutex<Vec<OutputPortID>>,
+    /// The number of blocks that haven't yet exited.
+    running: Mutex<usize>,
+    /// Notified whenever a block exits.
+    block_exited: Condvar,
 }
 
 #[allow(unused)]
 impl<T: Transport> StdRuntime<T> {
     pub fn new(transport: T) -> Result<Arc<Self>, BlockError> {
+        Self::with_clock(transport, Arc::new(SystemClock::new()))
+    }
+
+    pub fn with_clock(transport: T, clock: Arc<dyn Clock>) -> Result<Arc<Self>, BlockError> {
         Ok(Arc::new(Self {
             transport: Arc::new(transport),
             is_alive: AtomicBool::new(true),
+            is_draining: AtomicBool::new(false),
             process_id: AtomicUsize::new(1),
+            clock,
+            sources: Mutex::new(Vec::new()),
+            running: Mutex::new(0),
+            block_exited: Condvar::new(),
         }))
     }
+
+    /// Returns the transport that connects the ports of the blocks.
+    pub fn transport(&self) -> &Arc<T> {
+        &self.transport
+    }
+
+    /// Marks the runtime as terminated, waking up all sleeping blocks and
+    /// closing all ports.
+    ///
+    /// Any messages still buffered on the ports are discarded.
+    pub fn shutdown(&self) -> BlockResult {
+        self.is_alive.store(false, Ordering::SeqCst);
+        self.clock.wake_all();
+        self.transport.close_all()?;
+        Ok(())
+    }
+
+    /// Shuts down the runtime gracefully, giving its blocks until the
+    /// timeout elapses to drain the messages buffered on their ports.
+    ///
+    /// The output ports of the blocks without input ports are closed first,
+    /// so that the end of the stream cascades through the blocks downstream
+    /// of them, once they have received everything sent before. Should any
+    /// blocks still be running once the timeout elapses, the runtime is shut
+    /// down as per [`StdRuntime::shutdown`]. Otherwise, the ports are left
+    /// open, with the messages that reached ports outside of the blocks.
+    pub fn shutdown_timeout(&self, timeout: Duration) -> BlockResult {
+        self.is_draining.store(true, Ordering::SeqCst);
+        let sources = core::mem::take(&mut *self.sources.lock());
+        for output in sources {
+            let _ = self.transport.close_output(output); // may already be closed
+        }
+        let deadline = std::time::Instant::now().checked_add(timeout);
+        let mut running = self.running.lock();
+        while *running > 0 {
+            match deadline {
+                None => self.block_exited.wait(&mut running),
+                Some(deadline) => {
+                    if self.block_exited.wait_until(&mut running, deadline) {
+                        break;
+                    }
+                }
+            }
+        }
+        if *running > 0 {
+            drop(running);
+            return self.shutdown();
+        }
+        self.is_alive.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Checks whether the runtime is shutting down, whether gracefully or
+    /// not.
+    fn is_shutting_down(&self) -> bool {
+        !self.is_alive.load(Ordering::SeqCst) || self.is_draining.load(Ordering::SeqCst)
+    }
+
+    /// Registers a block about to be spawned, so that a graceful shutdown
+    /// waits for it to exit.
+    pub(crate) fn register_block(&self, block: &SupervisedBlock) {
+        if block.block.inputs().is_empty() {
+            let outputs = block.block.outputs();
+            self.sources
+                .lock()
+                .extend(outputs.iter().filter_map(|port| match port.id() {
+                    Some(PortID::Output(output)) => Some(output),
+                    _ => None,
+                }));
+        }
+        *self.running.lock() += 1;
+    }
+
+    fn wait_for_port(&self, port: &dyn Port, timeout: Option<Duration>) -> BlockResult<bool> {
+        if !self.is_alive.load(Ordering::SeqCst) {
+            return Err(BlockError::Terminated);
+        }
+        let Some(port_id) = port.id() else {
+            return Ok(port.is_connected()); // nothing to wait on
+        };
+        match self.transport.wait_for(port_id, timeout) {
+            Err(PortError::Closed) if !self.is_alive.load(Ordering::SeqCst) => {
+                Err(BlockError::Terminated)
+            }
+            result => Ok(result?),
+        }
+    }
 }
 
-impl<T: Transport + 'static> Runtime for Arc<StdRuntime<T>> {
-    fn execute_block(&mut self, block: Box<dyn Block>) -> BlockResult<Rc<dyn Process>> {
-        let block_runtime = Arc::new((*self).clone()) as Arc<dyn BlockRuntime>;
+impl<T: Transport + 'static> StdRuntime<T> {
+    pub(crate) fn spawn_block(
+        self: &Arc<Self>,
+        block: SupervisedBlock,
+        policy: FailurePolicy,
+    ) -> BlockResult<Rc<dyn Process>> {
+        self.register_block(&block);
+        let block_runtime = Arc::new(self.clone()) as Arc<dyn BlockRuntime>;
+        let runtime = self.clone();
         let block_process = Rc::new(RunningBlock {
             id: self.process_id.fetch_add(1, Ordering::SeqCst),
+            name: block.block.name(),
             runtime: self.clone(),
             handle: RefCell::new(Some(
                 std::thread::Builder::new()
-                    .name(block.name().unwrap_or_else(|| "<unnamed>".to_string()))
+                    .name(
+                        block
+                            .block
+                            .name()
+                            .unwrap_or_else(|| "<unnamed>".to_string()),
+                    )
                     .spawn(move || {
-                        let mut block = block;
                         std::thread::park();
-                        Block::prepare(block.as_mut(), block_runtime.as_ref())
-                            .and_then(|_| Block::execute(block.as_mut(), block_runtime.as_ref()))
+                        runtime.run_block(block, block_runtime.as_ref(), policy)
                     })
                     .unwrap(),
             )),
@@ -58,6 +180,12 @@ impl<T: Transport + 'static> Runtime for Arc<StdRuntime<T>> {
             .unpark();
         Ok(block_process)
     }
+}
+
+impl<T: Transport + 'static> Runtime for Arc<StdRuntime<T>> {
+    fn execute_block(&mut self, block: Box<dyn Block>) -> BlockResult<Rc<dyn Process>> {
+        self.spawn_block(SupervisedBlock::new(block), FailurePolicy::default())
+    }
 
     fn execute<X: Transport + Default>(
         &mut self,
@@ -71,7 +199,8 @@ impl<T: Transport + 'static> Runtime for Arc<StdRuntime<T>> {
         };
 
         while let Some(block) = system.blocks.borrow_mut().pop_front() {
-            system_process.blocks.push(self.execute_block(block)?);
+            let block_process = self.spawn_block(block, system.failure_policy)?;
+            system_process.blocks.push(block_process);
         }
 
         Ok(Rc::new(system_process))
@@ -83,31 +212,34 @@ impl<T: Transport> BlockRuntime for Arc<StdRuntime<T>> {
         self.is_alive.load(Ordering::SeqCst)
     }
 
+    fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
     fn sleep_for(&self, duration: Duration) -> BlockResult {
-        #[cfg(feature = "std")]
-        std::thread::sleep(duration);
-        #[cfg(not(feature = "std"))]
-        unimplemented!("std::thread::sleep requires the 'std' feature");
-        Ok(())
+        self.sleep_until(self.now().saturating_add(duration))
     }
 
-    fn sleep_until(&self, _instant: Instant) -> BlockResult {
-        todo!() // TODO
+    fn sleep_until(&self, instant: Instant) -> BlockResult {
+        if self.clock.wait_until(instant, &|| !self.is_alive()) {
+            Ok(())
+        } else {
+            Err(BlockError::Terminated)
+        }
+    }
+
+    fn wait_for(&self, port: &dyn Port) -> BlockResult {
+        self.wait_for_port(port, None).map(|_| ())
     }
 
-    fn wait_for(&self, _port: &dyn Port) -> BlockResult {
-        // while self.is_alive() && !port.is_connected() {
-        //     self.yield_now()?;
-        // }
-        // if self.is_alive() {
-        //     Ok(())
-        // } else {
-        //     Err(BlockError::Terminated)
-        // }
-        Ok(()) // TODO
+    fn wait_for_timeout(&self, port: &dyn Port, timeout: Duration) -> BlockResult<bool> {
+        self.wait_for_port(port, Some(timeout))
     }
 
     fn yield_now(&self) -> Result<(), BlockError> {
+        if !self.is_alive() {
+            return Err(BlockError::Terminated);
+        }
         #[cfg(feature = "std")]
         std::thread::yield_now();
         #[cfg(not(feature = "std"))]
@@ -126,15 +258,135 @@ impl<T: Transport> BlockRuntime for Arc<StdRuntime<T>> {
         }
         #[cfg(not(all(feature = "std", feature = "rand")))]
         {
-            drop(range);
-            let mut _rng = todo!();
+            let _ = range;
+            todo!() // TODO
+        }
+    }
+}
+
+impl<T: Transport> StdRuntime<T> {
+    /// Executes a registered block to completion, as per
+    /// [`StdRuntime::supervise_block`], and then accounts for its exit.
+    pub(crate) fn run_block(
+        &self,
+        supervised: SupervisedBlock,
+        runtime: &dyn BlockRuntime,
+        policy: FailurePolicy,
+    ) -> BlockResult {
+        let result = self.supervise_block(supervised, runtime, policy);
+        self.unregister_block();
+        result
+    }
+
+    /// Accounts for the exit of a registered block.
+    pub(crate) fn unregister_block(&self) {
+        *self.running.lock() -= 1;
+        self.block_exited.notify_all();
+    }
+
+    /// Prepares and executes a block to completion, restarting it according
+    /// to its restart policy, and applying the failure policy should it fail
+    /// or panic for good.
+    fn supervise_block(
+        &self,
+        supervised: SupervisedBlock,
+        runtime: &dyn BlockRuntime,
+        policy: FailurePolicy,
+    ) -> BlockResult {
+        let SupervisedBlock {
+            mut block,
+            restart,
+            respawn,
+            removed,
+        } = supervised;
+        let restart = Self::restart_policy(restart, policy);
+        let mut restarts = 0;
+        loop {
+            let result =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| block.run(runtime)))
+                    .unwrap_or_else(|panic| Err(panic.into()));
+            let (result, backoff) =
+                match self.supervise_exit(result, &restart, restarts, policy, &removed) {
+                    Exit::Return(result) => return result,
+                    Exit::Restart(result, backoff) => (result, backoff),
+                };
+            if let Err(error) = runtime.sleep_for(backoff).and_then(|_| runtime.yield_now()) {
+                return result.and(Err(error)); // the runtime was shut down meanwhile
+            }
+            if let Some(ref respawn) = respawn {
+                block = respawn();
+            }
+            restarts += 1;
+        }
+    }
+
+    /// Returns the restart policy of a block, as overridden by the failure
+    /// policy of its system.
+    pub(crate) fn restart_policy(restart: RestartPolicy, policy: FailurePolicy) -> RestartPolicy {
+        if policy == FailurePolicy::Restart && restart.restart == Restart::Never {
+            return RestartPolicy::on_failure();
+        }
+        restart
+    }
+
+    /// Decides whether a supervised block that exited with the given result
+    /// gets restarted, applying the failure policy should it fail for good.
+    ///
+    /// A block removed from its system is never restarted, and its failure
+    /// is left to whoever removed it.
+    pub(crate) fn supervise_exit(
+        &self,
+        result: BlockResult,
+        restart: &RestartPolicy,
+        restarts: usize,
+        policy: FailurePolicy,
+        removed: &AtomicBool,
+    ) -> Exit {
+        if removed.load(Ordering::SeqCst) {
+            return Exit::Return(result);
         }
+        let result = match result {
+            Err(BlockError::Terminated) => return Exit::Return(Err(BlockError::Terminated)),
+            // Closed ports are expected after a shutdown:
+            Err(BlockError::PortError(PortError::Closed)) if self.is_shutting_down() => {
+                return Exit::Return(Err(BlockError::Terminated))
+            }
+            result => result,
+        };
+        let should_restart = match (&result, restart.restart) {
+            _ if self.is_shutting_down() => false,
+            (_, Restart::Never) => false,
+            (Ok(()), Restart::OnFailure) => false,
+            (Err(_), Restart::OnFailure) | (_, Restart::Always) => {
+                restart.max_restarts.map_or(true, |max| restarts < max)
+            }
+        };
+        if should_restart {
+            return Exit::Restart(result, restart.backoff_for(restarts));
+        }
+        Exit::Return(match result {
+            Err(error) if policy == FailurePolicy::FailFast => {
+                let _ = self.shutdown();
+                Err(error)
+            }
+            result => result,
+        })
     }
 }
 
+/// What happens to a supervised block once it exits.
+pub(crate) enum Exit {
+    /// The block is done, with the given result.
+    Return(BlockResult),
+    /// The block exited with the given result, and gets restarted after the
+    /// given backoff.
+    Restart(BlockResult, Duration),
+}
+
 #[allow(unused)]
 struct RunningBlock<T: Transport> {
     id: ProcessID,
+    name: Option<String>,
     runtime: Arc<StdRuntime<T>>,
     handle: RefCell<Option<std::thread::JoinHandle<BlockResult>>>,
 }
@@ -151,6 +403,10 @@ impl<T: Transport> Process for RunningBlock<T> {
         self.id
     }
 
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
     fn is_alive(&self) -> bool {
         self.handle
             .borrow()
@@ -161,9 +417,16 @@ impl<T: Transport> Process for RunningBlock<T> {
 
     fn join(&self) -> BlockResult {
         let handle = self.handle.take().unwrap();
-        handle
-            .join()
-            .map_err(<Box<dyn core::any::Any + Send>>::from)?
+        handle.join().map_err(BlockError::from)?
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
     }
 }
 
@@ -185,9 +448,15 @@ impl<T: Transport> Process for RunningSystem<T> {
     }
 
     fn join(&self) -> BlockResult {
-        for block in self.blocks.iter() {
-            block.join()?;
-        }
-        Ok(())
+        join_all(&self.blocks)
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
     }
 }
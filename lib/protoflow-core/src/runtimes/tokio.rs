@@ -0,0 +1,303 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{Arc, Box, Duration, Instant, Ordering, Range, Rc, String, Vec},
+    process::join_all,
+    restart_policy::{AnyBlock, SupervisedBlock},
+    runtimes::{Exit, StdRuntime},
+    transport::Transport,
+    transports::MpscTransport,
+    utils::Condvar,
+    AsyncBlock, Block, BlockDescriptor, BlockError, BlockResult, BlockRuntime, FailurePolicy, Port,
+    Process, ProcessID, RestartPolicy, Runtime, System,
+};
+use core::panic::AssertUnwindSafe;
+use futures::FutureExt;
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+
+/// A runtime that executes blocks as tasks on a Tokio runtime.
+///
+/// Asynchronous blocks are spawned as regular tasks, whereas synchronous
+/// blocks are spawned as blocking tasks, so that they don't hold up the
+/// Tokio worker threads. Both are supervised as per their restart policies
+/// and the failure policy of their system.
+pub struct TokioRuntime<T: Transport = MpscTransport> {
+    runtime: Arc<StdRuntime<T>>,
+    handle: Handle,
+}
+
+impl<T: Transport> TokioRuntime<T> {
+    /// Creates a Tokio runtime that spawns blocks using the given handle,
+    /// and the transport and clock of the given runtime.
+    pub fn new(runtime: &Arc<StdRuntime<T>>, handle: Handle) -> BlockResult<Arc<Self>> {
+        Ok(Arc::new(Self {
+            runtime: runtime.clone(),
+            handle,
+        }))
+    }
+
+    /// Creates a Tokio runtime that spawns blocks on the current Tokio
+    /// runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn current(runtime: &Arc<StdRuntime<T>>) -> BlockResult<Arc<Self>> {
+        Self::new(runtime, Handle::current())
+    }
+
+    /// Marks the runtime as terminated, waking up all sleeping blocks and
+    /// closing all ports.
+    pub fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    /// Shuts down the runtime gracefully, as per
+    /// [`StdRuntime::shutdown_timeout`].
+    pub fn shutdown_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)
+    }
+}
+
+impl<T: Transport + 'static> TokioRuntime<T> {
+    fn spawn_block(
+        self: &Arc<Self>,
+        block: SupervisedBlock,
+        policy: FailurePolicy,
+    ) -> BlockResult<Rc<dyn Process>> {
+        self.runtime.register_block(&block);
+        let block_process = TokioBlock {
+            id: self.runtime.process_id.fetch_add(1, Ordering::SeqCst),
+            name: block.block.name(),
+            runtime: self.clone(),
+            result: Arc::new((Mutex::new(None), Condvar::new())),
+        };
+        let exit = BlockExit(block_process.result.clone());
+        if block.block.is_async() {
+            let runtime = self.clone();
+            self.handle.spawn(async move {
+                let result = runtime.supervise_async_block(block, policy).await;
+                runtime.runtime.unregister_block();
+                exit.send(result);
+            });
+        } else {
+            let block_runtime = Arc::new(self.clone()) as Arc<dyn BlockRuntime>;
+            let runtime = self.runtime.clone();
+            self.handle.spawn_blocking(move || {
+                exit.send(runtime.run_block(block, block_runtime.as_ref(), policy));
+            });
+        }
+        Ok(Rc::new(block_process))
+    }
+
+    /// Executes an asynchronous block to completion, as per
+    /// [`StdRuntime::supervise_block`], but without holding up a thread
+    /// while the block awaits.
+    async fn supervise_async_block(
+        self: &Arc<Self>,
+        supervised: SupervisedBlock,
+        policy: FailurePolicy,
+    ) -> BlockResult {
+        let block_runtime = Arc::new(self.clone()) as Arc<dyn BlockRuntime>;
+        let SupervisedBlock {
+            mut block,
+            restart,
+            respawn,
+            removed,
+        } = supervised;
+        let restart = StdRuntime::<T>::restart_policy(restart, policy);
+        let mut restarts = 0;
+        loop {
+            let result = AssertUnwindSafe(block.run_async(block_runtime.as_ref()))
+                .catch_unwind()
+                .await
+                .unwrap_or_else(|panic| Err(panic.into()));
+            let (result, backoff) = match self
+                .runtime
+                .supervise_exit(result, &restart, restarts, policy, &removed)
+            {
+                Exit::Return(result) => return result,
+                Exit::Restart(result, backoff) => (result, backoff),
+            };
+            // Sleep on the runtime's clock, which may be a virtual one:
+            let sleeper = block_runtime.clone();
+            let slept = self
+                .handle
+                .spawn_blocking(move || {
+                    sleeper.sleep_for(backoff).and_then(|_| sleeper.yield_now())
+                })
+                .await
+                .unwrap_or(Err(BlockError::Terminated));
+            if let Err(error) = slept {
+                return result.and(Err(error)); // the runtime was shut down meanwhile
+            }
+            if let Some(ref respawn) = respawn {
+                block = respawn();
+            }
+            restarts += 1;
+        }
+    }
+
+    /// Spawns an asynchronous block as a Tokio task.
+    pub fn execute_async_block(
+        self: &Arc<Self>,
+        block: Box<dyn AsyncBlock>,
+    ) -> BlockResult<Rc<dyn Process>> {
+        let block = SupervisedBlock {
+            block: AnyBlock::Async(block),
+            restart: RestartPolicy::never(),
+            respawn: None,
+            removed: Arc::default(),
+        };
+        self.spawn_block(block, FailurePolicy::default())
+    }
+}
+
+impl<T: Transport + 'static> Runtime for Arc<TokioRuntime<T>> {
+    fn execute_block(&mut self, block: Box<dyn Block>) -> BlockResult<Rc<dyn Process>> {
+        self.spawn_block(SupervisedBlock::new(block), FailurePolicy::default())
+    }
+
+    fn execute<X: Transport + Default>(
+        &mut self,
+        system: System<X>,
+    ) -> BlockResult<Rc<dyn Process>> {
+        let mut system_process = TokioSystem {
+            id: self.runtime.process_id.fetch_add(1, Ordering::SeqCst),
+            runtime: self.clone(),
+            blocks: Vec::new(),
+        };
+
+        while let Some(block) = system.blocks.borrow_mut().pop_front() {
+            let block_process = self.spawn_block(block, system.failure_policy)?;
+            system_process.blocks.push(block_process);
+        }
+
+        Ok(Rc::new(system_process))
+    }
+}
+
+impl<T: Transport> BlockRuntime for Arc<TokioRuntime<T>> {
+    fn is_alive(&self) -> bool {
+        self.runtime.is_alive()
+    }
+
+    fn now(&self) -> Instant {
+        self.runtime.now()
+    }
+
+    fn sleep_for(&self, duration: Duration) -> BlockResult {
+        self.runtime.sleep_for(duration)
+    }
+
+    fn sleep_until(&self, instant: Instant) -> BlockResult {
+        self.runtime.sleep_until(instant)
+    }
+
+    fn wait_for(&self, port: &dyn Port) -> BlockResult {
+        self.runtime.wait_for(port)
+    }
+
+    fn wait_for_timeout(&self, port: &dyn Port, timeout: Duration) -> BlockResult<bool> {
+        self.runtime.wait_for_timeout(port, timeout)
+    }
+
+    fn yield_now(&self) -> BlockResult {
+        self.runtime.yield_now()
+    }
+
+    fn random_duration(&self, range: Range<Duration>) -> Duration {
+        self.runtime.random_duration(range)
+    }
+}
+
+struct TokioBlock<T: Transport> {
+    id: ProcessID,
+    name: Option<String>,
+    runtime: Arc<TokioRuntime<T>>,
+    result: Arc<(Mutex<Option<BlockResult>>, Condvar)>,
+}
+
+impl<T: Transport> Process for TokioBlock<T> {
+    fn id(&self) -> ProcessID {
+        self.id
+    }
+
+    fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    fn is_alive(&self) -> bool {
+        self.result.0.lock().is_none()
+    }
+
+    /// Waits for the block to exit.
+    ///
+    /// This blocks the current thread, so is best not called from an
+    /// asynchronous task.
+    fn join(&self) -> BlockResult {
+        let (ref lock, ref cvar) = *self.result;
+        let mut result = lock.lock();
+        while result.is_none() {
+            cvar.wait(&mut result);
+        }
+        result.take().unwrap()
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
+    }
+}
+
+/// Reports the result of a block's task, or that it was cancelled, should
+/// the task be dropped without completing.
+struct BlockExit(Arc<(Mutex<Option<BlockResult>>, Condvar)>);
+
+impl BlockExit {
+    fn send(self, result: BlockResult) {
+        *self.0 .0.lock() = Some(result);
+    }
+}
+
+impl Drop for BlockExit {
+    fn drop(&mut self) {
+        let (ref lock, ref cvar) = *self.0;
+        lock.lock().get_or_insert(Err(BlockError::Terminated));
+        cvar.notify_all();
+    }
+}
+
+struct TokioSystem<T: Transport> {
+    id: ProcessID,
+    runtime: Arc<TokioRuntime<T>>,
+    blocks: Vec<Rc<dyn Process>>,
+}
+
+impl<T: Transport> Process for TokioSystem<T> {
+    fn id(&self) -> ProcessID {
+        self.id
+    }
+
+    fn is_alive(&self) -> bool {
+        self.blocks.iter().any(|block| block.is_alive())
+    }
+
+    fn join(&self) -> BlockResult {
+        join_all(&self.blocks)
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.runtime.shutdown_timeout(timeout)?;
+        self.join()
+    }
+}
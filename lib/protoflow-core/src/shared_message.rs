@@ -0,0 +1,67 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{fmt, Arc, Bytes},
+    Message,
+};
+use core::any::Any;
+
+/// A message passed by reference between ports in the same process,
+/// without being encoded.
+///
+/// Transports that can't pass messages by reference encode them instead.
+#[derive(Clone)]
+pub struct SharedMessage(Arc<dyn ErasedMessage>);
+
+impl SharedMessage {
+    pub fn new<T: Message>(message: Arc<T>) -> Self {
+        Self(message)
+    }
+
+    /// Returns the message, if it is of type `T`.
+    pub fn downcast<T: Message>(self) -> Result<Arc<T>, Self> {
+        if self.0.as_any().is::<T>() {
+            Ok(self.0.into_any().downcast::<T>().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Returns the length-delimited encoding of the message.
+    pub fn encode(&self) -> Bytes {
+        self.0.encode()
+    }
+}
+
+/// Messages passed by reference are equal only if they are the same message.
+impl PartialEq for SharedMessage {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for SharedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SharedMessage").field(&self.0).finish()
+    }
+}
+
+trait ErasedMessage: Any + Send + Sync + fmt::Debug {
+    fn as_any(&self) -> &dyn Any;
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+    fn encode(&self) -> Bytes;
+}
+
+impl<T: Message> ErasedMessage for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+
+    fn encode(&self) -> Bytes {
+        Bytes::from(self.encode_length_delimited_to_vec())
+    }
+}
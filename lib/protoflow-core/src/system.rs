@@ -2,11 +2,16 @@
 
 use crate::{
     prelude::{Arc, Box, PhantomData, Rc, RefCell, VecDeque},
+    restart_policy::{AnyBlock, SupervisedBlock},
     runtimes::StdRuntime,
     transports::MpscTransport,
-    Block, BlockResult, InputPort, Message, OutputPort, Process, Runtime, Transport,
+    Block, BlockResult, ConnectionOptions, FailurePolicy, InputPort, InputPorts, Message,
+    OutputPort, OutputPorts, Process, RestartPolicy, Runtime, Transport,
 };
 
+#[cfg(feature = "tokio")]
+use crate::AsyncBlock;
+
 pub trait SystemBuilding {
     /// Creates a new input port inside the system.
     fn input<M: Message + 'static>(&self) -> InputPort<M>;
@@ -14,6 +19,12 @@ pub trait SystemBuilding {
     /// Creates a new output port inside the system.
     fn output<M: Message + 'static>(&self) -> OutputPort<M>;
 
+    /// Creates a new array of input ports inside the system.
+    fn inputs<M: Message + 'static, const N: usize>(&self) -> InputPorts<M, N>;
+
+    /// Creates a new array of output ports inside the system.
+    fn outputs<M: Message + 'static, const N: usize>(&self) -> OutputPorts<M, N>;
+
     /// Instantiates a block inside the system.
     fn block<B: Block + Clone + 'static>(&self, block: B) -> B;
 
@@ -21,6 +32,17 @@ pub trait SystemBuilding {
     ///
     /// Both ports must be of the same message type.
     fn connect<M: Message>(&self, source: &OutputPort<M>, target: &InputPort<M>) -> bool;
+
+    /// Connects two ports of two blocks in the system, buffering messages
+    /// as per the given connection options.
+    ///
+    /// Both ports must be of the same message type.
+    fn connect_with<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+        options: ConnectionOptions,
+    ) -> bool;
 }
 
 pub trait SystemExecution {
@@ -33,7 +55,10 @@ pub struct System<X: Transport + Default + 'static = MpscTransport> {
     pub(crate) runtime: Arc<StdRuntime<X>>,
 
     /// The registered blocks in the system.
-    pub(crate) blocks: RefCell<VecDeque<Box<dyn Block>>>,
+    pub(crate) blocks: RefCell<VecDeque<SupervisedBlock>>,
+
+    /// What to do when one of the blocks fails.
+    pub(crate) failure_policy: FailurePolicy,
 
     _phantom: PhantomData<X>,
 }
@@ -55,6 +80,7 @@ impl<X: Transport + Default + 'static> System<X> {
         Self {
             runtime: runtime.clone(),
             blocks: RefCell::new(VecDeque::new()),
+            failure_policy: FailurePolicy::default(),
             _phantom: PhantomData,
         }
     }
@@ -64,6 +90,23 @@ impl<X: Transport + Default + 'static> System<X> {
         runtime.execute(self)
     }
 
+    /// Executes the system, returning a handle for reconfiguring it while
+    /// it runs.
+    #[cfg(feature = "std")]
+    pub fn spawn(self) -> BlockResult<crate::SystemHandle<X>> {
+        crate::SystemHandle::spawn(self)
+    }
+
+    /// Returns what the system does when one of its blocks fails.
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// Sets what the system does when one of its blocks fails.
+    pub fn set_failure_policy(&mut self, policy: FailurePolicy) {
+        self.failure_policy = policy;
+    }
+
     pub fn input<M: Message + 'static>(&self) -> InputPort<M> {
         InputPort::new(self)
     }
@@ -72,8 +115,61 @@ impl<X: Transport + Default + 'static> System<X> {
         OutputPort::new(self)
     }
 
+    pub fn inputs<M: Message + 'static, const N: usize>(&self) -> InputPorts<M, N> {
+        InputPorts::new(self)
+    }
+
+    pub fn outputs<M: Message + 'static, const N: usize>(&self) -> OutputPorts<M, N> {
+        OutputPorts::new(self)
+    }
+
     pub fn block<B: Block + Clone + 'static>(&self, block: B) -> B {
-        self.blocks.borrow_mut().push_back(Box::new(block.clone()));
+        self.block_with_restart(block, RestartPolicy::never())
+    }
+
+    /// Instantiates a block inside the system, which gets restarted
+    /// according to the given policy.
+    pub fn block_with_restart<B: Block + Clone + 'static>(
+        &self,
+        block: B,
+        restart: RestartPolicy,
+    ) -> B {
+        let pristine = block.clone();
+        self.blocks.borrow_mut().push_back(SupervisedBlock {
+            block: AnyBlock::Sync(Box::new(block.clone())),
+            restart,
+            respawn: Some(Box::new(move || AnyBlock::Sync(Box::new(pristine.clone())))),
+            removed: Arc::default(),
+        });
+        block
+    }
+
+    /// Instantiates an asynchronous block inside the system.
+    ///
+    /// The block executes as a task on a `TokioRuntime`, or is driven to
+    /// completion on a thread of its own by other runtimes.
+    #[cfg(feature = "tokio")]
+    pub fn async_block<B: AsyncBlock + Clone + 'static>(&self, block: B) -> B {
+        self.async_block_with_restart(block, RestartPolicy::never())
+    }
+
+    /// Instantiates an asynchronous block inside the system, which gets
+    /// restarted according to the given policy.
+    #[cfg(feature = "tokio")]
+    pub fn async_block_with_restart<B: AsyncBlock + Clone + 'static>(
+        &self,
+        block: B,
+        restart: RestartPolicy,
+    ) -> B {
+        let pristine = block.clone();
+        self.blocks.borrow_mut().push_back(SupervisedBlock {
+            block: AnyBlock::Async(Box::new(block.clone())),
+            restart,
+            respawn: Some(Box::new(move || {
+                AnyBlock::Async(Box::new(pristine.clone()))
+            })),
+            removed: Arc::default(),
+        });
         block
     }
 
@@ -82,6 +178,19 @@ impl<X: Transport + Default + 'static> System<X> {
         let transport = runtime.transport.as_ref();
         transport.connect(source.id, target.id).unwrap()
     }
+
+    pub fn connect_with<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+        options: ConnectionOptions,
+    ) -> bool {
+        let runtime = self.runtime.as_ref();
+        let transport = runtime.transport.as_ref();
+        transport
+            .connect_with(source.id, target.id, options)
+            .unwrap()
+    }
 }
 
 impl SystemBuilding for System {
@@ -93,6 +202,14 @@ impl SystemBuilding for System {
         System::output(self)
     }
 
+    fn inputs<M: Message + 'static, const N: usize>(&self) -> InputPorts<M, N> {
+        System::inputs(self)
+    }
+
+    fn outputs<M: Message + 'static, const N: usize>(&self) -> OutputPorts<M, N> {
+        System::outputs(self)
+    }
+
     fn block<B: Block + Clone + 'static>(&self, block: B) -> B {
         System::block(self, block)
     }
@@ -100,6 +217,15 @@ impl SystemBuilding for System {
     fn connect<M: Message>(&self, source: &OutputPort<M>, target: &InputPort<M>) -> bool {
         System::connect(self, source, target)
     }
+
+    fn connect_with<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+        options: ConnectionOptions,
+    ) -> bool {
+        System::connect_with(self, source, target, options)
+    }
 }
 
 impl SystemExecution for System {
@@ -0,0 +1,171 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{
+    prelude::{Arc, AtomicBool, Duration, Ordering, Rc, RefCell, Vec},
+    process::join_all,
+    transports::MpscTransport,
+    Block, BlockDescriptor, BlockError, BlockResult, ConnectionOptions, InputPort, Message,
+    OutputPort, Port, PortError, PortID, PortResult, Process, ProcessID, System, Transport,
+};
+
+/// A handle for reconfiguring a running system.
+///
+/// Returned by [`System::spawn`], the handle can add and remove blocks, and
+/// connect and disconnect ports, while the system is running.
+pub struct SystemHandle<X: Transport + Default + 'static = MpscTransport> {
+    id: ProcessID,
+    system: System<X>,
+    blocks: RefCell<Vec<SpawnedBlock>>,
+}
+
+struct SpawnedBlock {
+    process: Rc<dyn Process>,
+    ports: Vec<PortID>,
+    removed: Arc<AtomicBool>,
+}
+
+impl<X: Transport + Default + 'static> SystemHandle<X> {
+    pub(crate) fn spawn(system: System<X>) -> BlockResult<Self> {
+        let handle = Self {
+            id: system.runtime.process_id.fetch_add(1, Ordering::SeqCst),
+            system,
+            blocks: RefCell::new(Vec::new()),
+        };
+        handle.spawn_pending()?;
+        Ok(handle)
+    }
+
+    /// Creates a new input port inside the system.
+    pub fn input<M: Message + 'static>(&self) -> InputPort<M> {
+        self.system.input()
+    }
+
+    /// Creates a new output port inside the system.
+    pub fn output<M: Message + 'static>(&self) -> OutputPort<M> {
+        self.system.output()
+    }
+
+    /// Instantiates and executes a block inside the system.
+    ///
+    /// Returns the ID of the block's process, for use with
+    /// [`remove_block`](Self::remove_block).
+    pub fn block<B: Block + Clone + 'static>(&self, block: B) -> BlockResult<ProcessID> {
+        self.system.block(block);
+        let mut ids = self.spawn_pending()?;
+        Ok(ids.pop().unwrap())
+    }
+
+    /// Stops a block of the system and waits for it to exit.
+    ///
+    /// All the block's ports are closed, so that it sees the end of its
+    /// input streams and fails to send any further messages. The ports
+    /// connected to it remain open. Any messages the block had yet to
+    /// receive are dropped.
+    ///
+    /// Only the ports listed by the block's descriptor get closed.
+    pub fn remove_block(&self, id: ProcessID) -> BlockResult {
+        let mut blocks = self.blocks.borrow_mut();
+        let Some(index) = blocks.iter().position(|block| block.process.id() == id) else {
+            return Err(BlockError::Other("no such block".into()));
+        };
+        let block = blocks.remove(index);
+        drop(blocks);
+        // Keep the block from getting restarted once its ports are closed:
+        block.removed.store(true, Ordering::SeqCst);
+        let transport = self.system.runtime.transport.as_ref();
+        for port in block.ports {
+            transport.close(port)?;
+        }
+        match block.process.join() {
+            // Closing the ports is how the block was asked to stop:
+            Err(BlockError::PortError(PortError::Closed)) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Returns the processes of the blocks currently in the system.
+    pub fn blocks(&self) -> Vec<Rc<dyn Process>> {
+        let blocks = self.blocks.borrow();
+        blocks.iter().map(|block| block.process.clone()).collect()
+    }
+
+    /// Connects two ports in the system.
+    pub fn connect<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+    ) -> PortResult<bool> {
+        self.connect_with(source, target, ConnectionOptions::default())
+    }
+
+    /// Connects two ports in the system, buffering messages as per the given
+    /// connection options.
+    pub fn connect_with<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        let transport = self.system.runtime.transport.as_ref();
+        transport.connect_with(source.id, target.id, options)
+    }
+
+    /// Disconnects two ports in the system, delivering the end of the stream
+    /// to the input port unless it remains connected to other output ports.
+    pub fn disconnect<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        target: &InputPort<M>,
+    ) -> PortResult<bool> {
+        let transport = self.system.runtime.transport.as_ref();
+        transport.disconnect(source.id, target.id)
+    }
+
+    /// Executes the blocks registered in the system but not yet running,
+    /// returning the IDs of their processes.
+    fn spawn_pending(&self) -> BlockResult<Vec<ProcessID>> {
+        let runtime = &self.system.runtime;
+        let mut ids = Vec::new();
+        while let Some(block) = self.system.blocks.borrow_mut().pop_front() {
+            let ports = block
+                .block
+                .inputs()
+                .iter()
+                .chain(block.block.outputs().iter())
+                .filter_map(|port| port.id())
+                .collect();
+            let removed = block.removed.clone();
+            let process = runtime.spawn_block(block, self.system.failure_policy)?;
+            ids.push(process.id());
+            self.blocks.borrow_mut().push(SpawnedBlock {
+                process,
+                ports,
+                removed,
+            });
+        }
+        Ok(ids)
+    }
+}
+
+impl<X: Transport + Default + 'static> Process for SystemHandle<X> {
+    fn id(&self) -> ProcessID {
+        self.id
+    }
+
+    fn is_alive(&self) -> bool {
+        self.blocks().iter().any(|block| block.is_alive())
+    }
+
+    fn join(&self) -> BlockResult {
+        join_all(&self.blocks())
+    }
+
+    fn shutdown(&self) -> BlockResult {
+        self.system.runtime.shutdown()
+    }
+
+    fn terminate_timeout(&self, timeout: Duration) -> BlockResult {
+        self.system.runtime.shutdown_timeout(timeout)?;
+        self.join()
+    }
+}
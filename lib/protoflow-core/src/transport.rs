@@ -1,6 +1,20 @@
 // This is free and unencumbered software released into the public domain.
 
-use crate::{prelude::Bytes, InputPortID, OutputPortID, PortID, PortResult, PortState};
+use crate::{
+    prelude::{Bytes, Duration},
+    ConnectionOptions, Frame, InputPortID, OutputPortID, PortError, PortID, PortResult, PortState,
+    SharedMessage,
+};
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+use crate::prelude::futures::future::BoxFuture;
+
+/// How long the default [`Transport::recv_any`] waits on one port before
+/// polling the others again.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
 
 #[allow(unused)]
 pub trait Transport: AsTransport + Send + Sync {
@@ -17,10 +31,184 @@ pub trait Transport: AsTransport + Send + Sync {
 
     fn close_input(&self, input: InputPortID) -> PortResult<bool>;
     fn close_output(&self, output: OutputPortID) -> PortResult<bool>;
-    fn connect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool>;
+
+    /// Closes all ports on a shutdown, discarding any buffered messages, and
+    /// waking up any threads blocked on them.
+    ///
+    /// Receiving from any of the ports then returns
+    /// `Err(PortError::Closed)`, rather than the end of the stream.
+    fn close_all(&self) -> PortResult<()>;
+
+    fn connect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+        self.connect_with(source, target, ConnectionOptions::default())
+    }
+
+    /// Connects two ports, buffering messages as per the given options.
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool>;
+
+    /// Disconnects two connected ports, leaving both open.
+    ///
+    /// The input port receives the end of the stream, unless it remains
+    /// connected to other output ports.
+    ///
+    /// Returns `Ok(false)` if the ports weren't connected.
+    fn disconnect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+        Err(PortError::Other(
+            "disconnecting ports is not supported by this transport".into(),
+        ))
+    }
+
+    /// Blocks until the port is connected, or until the timeout elapses.
+    ///
+    /// Returns `Ok(true)` if the port is connected.
+    /// Returns `Ok(false)` if the timeout elapsed first.
+    /// Returns `Err(PortError::Closed)` if the port is, or becomes, closed.
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool>;
+
     fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()>;
-    fn recv(&self, input: InputPortID) -> PortResult<Option<Bytes>>;
-    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Bytes>>;
+
+    /// Sends a message, blocking until it has been sent or the timeout
+    /// elapses.
+    ///
+    /// Returns `Err(PortError::Timeout)` if the timeout elapsed first.
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        timeout: Duration,
+    ) -> PortResult<()>;
+
+    /// Sends a control frame, either [`Frame::Error`] or [`Frame::Flush`],
+    /// to all the input ports connected to the output port.
+    ///
+    /// Control frames are never dropped by the connection's overflow
+    /// policy. The end of the stream is sent by closing or disconnecting the
+    /// output port.
+    ///
+    /// Returns `Err(PortError::Invalid(_))` for any other frame.
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()>;
+
+    /// Receives a frame, blocking until one is available.
+    ///
+    /// Returns `Ok(Some(Frame::Eos))` at the end of the stream, after which
+    /// the port is no longer connected.
+    /// Returns `Ok(None)` if the port is closed.
+    /// Returns `Err(PortError::Closed)` if all ports were closed on a
+    /// shutdown.
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>>;
+
+    /// Receives a frame, blocking until one is available or the timeout
+    /// elapses.
+    ///
+    /// Returns `Err(PortError::Timeout)` if the timeout elapsed first.
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>>;
+
+    /// Receives a frame, blocking until one is available or the deadline
+    /// passes.
+    ///
+    /// Returns `Err(PortError::Timeout)` if the deadline passed first.
+    #[cfg(feature = "std")]
+    fn recv_deadline(
+        &self,
+        input: InputPortID,
+        deadline: std::time::Instant,
+    ) -> PortResult<Option<Frame>> {
+        let timeout = deadline.saturating_duration_since(std::time::Instant::now());
+        self.recv_timeout(input, timeout)
+    }
+
+    /// Tries to receive a frame, returning immediately.
+    ///
+    /// Returns `Ok(Some(frame))` if a frame was received.
+    /// Returns `Ok(None)` if no frame was immediately available.
+    /// Returns `Err(PortError::Disconnected)` if the port is disconnected.
+    /// Returns `Err(PortError::Closed)` if the port is closed.
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>>;
+
+    /// Receives a frame from whichever of the given input ports has one
+    /// available first, blocking until then.
+    ///
+    /// Ports earlier in the given order are preferred when several have a
+    /// frame available, and ports that aren't connected are skipped.
+    ///
+    /// Returns the index of the port in the given order, along with its frame
+    /// as per [`Transport::recv`].
+    /// Returns `Ok(None)` if none of the ports is connected.
+    ///
+    /// The default implementation polls the ports in turn, waiting on one of
+    /// them for a short interval in between.
+    fn recv_any(&self, inputs: &[InputPortID]) -> PortResult<Option<(usize, Option<Frame>)>> {
+        loop {
+            let mut waiting = None;
+            for (index, input) in inputs.iter().enumerate() {
+                match self.try_recv(*input) {
+                    Ok(Some(frame)) => return Ok(Some((index, Some(frame)))),
+                    Ok(None) => waiting = waiting.or(Some(index)),
+                    Err(PortError::Closed | PortError::Disconnected) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            let Some(index) = waiting else {
+                return Ok(None);
+            };
+            match self.recv_timeout(inputs[index], POLL_INTERVAL) {
+                Err(PortError::Timeout) => {}
+                result => return result.map(|frame| Some((index, frame))),
+            }
+        }
+    }
+
+    /// Checks whether the transport can pass messages between ports by
+    /// reference, without encoding them.
+    fn is_zero_copy(&self) -> bool {
+        false
+    }
+
+    /// Sends a message by reference, blocking until it has been sent or the
+    /// optional timeout elapses.
+    ///
+    /// Zero-copy transports deliver it as a [`Frame::Shared`], while other
+    /// transports send the encoded message instead.
+    fn send_shared(
+        &self,
+        output: OutputPortID,
+        message: SharedMessage,
+        timeout: Option<Duration>,
+    ) -> PortResult<()> {
+        match timeout {
+            None => self.send(output, message.encode()),
+            Some(timeout) => self.send_timeout(output, message.encode(), timeout),
+        }
+    }
+
+    /// Sends a message asynchronously, for transports that natively
+    /// support it.
+    ///
+    /// Returns `None` if the transport can't, in which case callers fall back
+    /// to a blocking `send` on a separate thread.
+    #[cfg(feature = "std")]
+    fn send_async(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+    ) -> Option<BoxFuture<'_, PortResult<()>>> {
+        None
+    }
+
+    /// Receives a frame asynchronously, for transports that natively
+    /// support it.
+    ///
+    /// Returns `None` if the transport can't, in which case callers fall back
+    /// to a blocking `recv` on a separate thread.
+    #[cfg(feature = "std")]
+    fn recv_async(&self, input: InputPortID) -> Option<BoxFuture<'_, PortResult<Option<Frame>>>> {
+        None
+    }
 }
 
 pub trait AsTransport {
@@ -1,9 +1,26 @@
 // This is free and unencumbered software released into the public domain.
 
 #[cfg(feature = "std")]
-pub type MockTransport = MpscTransport;
+mod frame_stash;
+#[cfg(feature = "std")]
+pub use frame_stash::*;
+
+#[cfg(feature = "std")]
+mod mock;
+#[cfg(feature = "std")]
+pub use mock::*;
 
 #[cfg(feature = "std")]
 mod mpsc;
 #[cfg(feature = "std")]
 pub use mpsc::*;
+
+#[cfg(feature = "std")]
+mod port_channel;
+#[cfg(feature = "std")]
+pub use port_channel::*;
+
+#[cfg(feature = "std")]
+mod port_table;
+#[cfg(feature = "std")]
+pub use port_table::*;
@@ -0,0 +1,81 @@
+// This is free and unencumbered software released into the public domain.
+
+use crate::{prelude::VecDeque, Frame};
+use core::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::Mutex;
+
+/// A frame numbered in the order in which it was sent over a channel.
+pub type NumberedFrame = (u64, Frame);
+
+/// The frames taken out of a bounded channel without being received, kept
+/// until they are received in the order in which they were sent.
+///
+/// A sender can't push a frame back to the front of a channel, so control
+/// frames evicted by the `DropOldest` overflow policy get stashed here, as
+/// do control frames sent while the channel is full, as control frames are
+/// never dropped. Every frame sent over the channel is numbered, so that the
+/// receiver can tell whether a stashed frame was sent before the frame it
+/// took out of the channel.
+#[derive(Debug, Default)]
+pub struct FrameStash {
+    /// The number of the next frame sent over the channel.
+    next: AtomicU64,
+    frames: Mutex<VecDeque<NumberedFrame>>,
+}
+
+impl FrameStash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks whether no frames are stashed.
+    pub fn is_empty(&self) -> bool {
+        self.frames.lock().is_empty()
+    }
+
+    /// Numbers a frame about to be sent over the channel.
+    pub fn number(&self, frame: Frame) -> NumberedFrame {
+        (self.next.fetch_add(1, Ordering::Relaxed), frame)
+    }
+
+    /// Stashes a frame that doesn't fit in the channel.
+    pub fn stash(&self, frame: NumberedFrame) {
+        insert(&mut self.frames.lock(), frame);
+    }
+
+    /// Evicts the oldest frame from the channel, stashing it if it's a
+    /// control frame.
+    ///
+    /// The stash is held while evicting, so that a receiver can't take a
+    /// newer frame out of the channel and find the stash without the evicted
+    /// frame.
+    pub fn evict(&self, take_oldest: impl FnOnce() -> Option<NumberedFrame>) {
+        let mut frames = self.frames.lock();
+        if let Some(frame) = take_oldest() {
+            if frame.1.is_control() {
+                insert(&mut frames, frame);
+            }
+        }
+    }
+
+    /// Returns the next frame to receive, given the frame just taken out of
+    /// the channel, if any: either that frame, or a stashed frame sent
+    /// before it, in which case the taken frame gets stashed.
+    pub fn next(&self, taken: Option<NumberedFrame>) -> Option<Frame> {
+        let mut frames = self.frames.lock();
+        match taken {
+            Some(taken) if frames.front().map_or(true, |(n, _)| *n > taken.0) => Some(taken.1),
+            Some(taken) => {
+                insert(&mut frames, taken);
+                frames.pop_front().map(|(_, frame)| frame)
+            }
+            None => frames.pop_front().map(|(_, frame)| frame),
+        }
+    }
+}
+
+/// Inserts a frame into the stash, in the order of the frame numbers.
+fn insert(frames: &mut VecDeque<NumberedFrame>, frame: NumberedFrame) {
+    let position = frames.partition_point(|(n, _)| *n < frame.0);
+    frames.insert(position, frame);
+}
@@ -3,67 +3,216 @@
 extern crate std;
 
 use crate::{
-    prelude::{vec, Bytes, ToString, Vec},
+    prelude::{vec, BTreeMap, Bytes, Duration, ToString, Vec, VecDeque},
     transport::Transport,
-    InputPortID, MessageBuffer, OutputPortID, PortError, PortID, PortResult, PortState,
+    utils::RwCondvar,
+    ConnectionOptions, Frame, InputPort, InputPortID, Message, MessageMetadata, OutputPort,
+    OutputPortID, Port, PortError, PortID, PortResult, PortState,
 };
-use parking_lot::RwLock;
-use std::sync::{Condvar, Mutex};
+use parking_lot::{RwLock, RwLockWriteGuard};
+use std::time::Instant;
 
+/// A transport for testing blocks, which records everything that happens
+/// to its ports.
+///
+/// Messages are buffered in unbounded inboxes, so sending never blocks and
+/// connection options are ignored. Tests can inspect the messages sent by
+/// each output port and the state transitions of all ports, inject
+/// messages into input ports, and simulate failures and disconnections.
+///
+/// Receiving on a closed input port behaves as with `MpscTransport`: `recv`
+/// returns the end of the stream, or `PortError::Closed` once all ports were
+/// closed by a shutdown, and `try_recv` fails with `PortError::Closed`.
 #[derive(Debug, Default)]
 pub struct MockTransport {
-    pub state: RwLock<MockTransportState>,
-    pub(crate) wakeup: (Mutex<bool>, Condvar),
+    state: RwLock<MockTransportState>,
+    /// Notified whenever a message is sent or injected, or a port changes
+    /// state.
+    state_changed: RwCondvar,
 }
 
 #[derive(Debug, Default)]
-pub struct MockTransportState {
+struct MockTransportState {
     outputs: Vec<PortState>,
     inputs: Vec<PortState>,
-    inboxes: Vec<MessageBuffer>,
+    inboxes: Vec<VecDeque<Frame>>,
+    /// Every message sent by each output port.
+    sent: Vec<Vec<Bytes>>,
+    /// Every port state transition, in order.
+    transitions: Vec<(PortID, PortState)>,
+    /// The errors that the next operation on a port will fail with.
+    failures: BTreeMap<PortID, PortError>,
+    /// Whether all ports were closed by a shutdown.
+    is_terminated: bool,
 }
 
 impl MockTransport {
     pub fn new() -> Self {
-        Self {
-            state: RwLock::new(MockTransportState::default()),
-            wakeup: (Mutex::new(false), Condvar::new()),
-        }
+        Self::default()
     }
 
     pub fn with_ports(input: usize, output: usize) -> Self {
         let mut inboxes = Vec::with_capacity(input);
-        inboxes.resize_with(input, MessageBuffer::new);
+        inboxes.resize_with(input, VecDeque::new);
         Self {
             state: RwLock::new(MockTransportState {
                 outputs: vec![PortState::Open; output],
                 inputs: vec![PortState::Open; input],
                 inboxes,
+                sent: vec![Vec::new(); output],
+                ..Default::default()
             }),
-            wakeup: (Mutex::new(false), Condvar::new()),
+            state_changed: RwCondvar::new(),
+        }
+    }
+
+    /// Returns every message sent by an output port.
+    pub fn sent<M: Message>(&self, output: &OutputPort<M>) -> PortResult<Vec<M>> {
+        self.sent_bytes(output_id(output)?)?
+            .into_iter()
+            .map(|message| {
+                let (_, message) = MessageMetadata::split_envelope(message)?;
+                M::decode_length_delimited(message).map_err(PortError::from)
+            })
+            .collect()
+    }
+
+    /// Returns every encoded message sent by an output port.
+    pub fn sent_bytes(&self, output: OutputPortID) -> PortResult<Vec<Bytes>> {
+        let state = self.state.read();
+        match state.sent.get(output.index()) {
+            None => Err(PortError::Invalid(output.into())),
+            Some(sent) => Ok(sent.clone()),
+        }
+    }
+
+    /// Delivers a message to an input port, whether connected or not.
+    pub fn inject<M: Message>(&self, input: &InputPort<M>, message: &M) -> PortResult<()> {
+        let bytes = Bytes::from(message.encode_length_delimited_to_vec());
+        self.inject_bytes(input_id(input)?, bytes)
+    }
+
+    /// Delivers an encoded message to an input port, whether connected or
+    /// not.
+    pub fn inject_bytes(&self, input: InputPortID, message: Bytes) -> PortResult<()> {
+        self.inject_frame(input, Frame::Data(message))
+    }
+
+    /// Delivers a frame to an input port, whether connected or not.
+    pub fn inject_frame(&self, input: InputPortID, frame: Frame) -> PortResult<()> {
+        let mut state = self.state.write();
+        match state.inputs.get(input.index()) {
+            None => return Err(PortError::Invalid(input.into())),
+            Some(PortState::Closed) => return Err(PortError::Closed),
+            Some(_) => state.inboxes[input.index()].push_back(frame),
+        }
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(())
+    }
+
+    /// Returns every port state transition so far, in order.
+    pub fn transitions(&self) -> Vec<(PortID, PortState)> {
+        self.state.read().transitions.clone()
+    }
+
+    /// Makes the next send on an output port, or the next receive on an
+    /// input port, fail with the given error.
+    pub fn fail_next(&self, port: impl Into<PortID>, error: PortError) {
+        self.state.write().failures.insert(port.into(), error);
+        self.state_changed.notify_all();
+    }
+
+    /// Disconnects a port from its peer, as if the connection was lost,
+    /// leaving both ports open.
+    ///
+    /// Unlike [`Transport::disconnect`], no end of stream is delivered, and
+    /// receiving on the input port with no buffered messages fails with
+    /// `PortError::Disconnected`.
+    pub fn break_connection(&self, port: impl Into<PortID>) -> PortResult<bool> {
+        let port = port.into();
+        let mut state = self.state.write();
+        let (input, output) = match (port, state.port_state(port)?) {
+            (PortID::Input(input), PortState::Connected(PortID::Output(output))) => (input, output),
+            (PortID::Output(output), PortState::Connected(PortID::Input(input))) => (input, output),
+            _ => return Ok(false), // not connected
+        };
+        state.set_state(input.into(), PortState::Open);
+        if state.outputs[output.index()] == PortState::Connected(input.into()) {
+            state.set_state(output.into(), PortState::Open);
+        }
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+}
+
+impl MockTransportState {
+    fn port_state(&self, port: PortID) -> PortResult<PortState> {
+        let state = match port {
+            PortID::Input(input) => self.inputs.get(input.index()),
+            PortID::Output(output) => self.outputs.get(output.index()),
+        };
+        state.copied().ok_or(PortError::Invalid(port))
+    }
+
+    fn set_state(&mut self, port: PortID, new_state: PortState) {
+        let state = match port {
+            PortID::Input(input) => &mut self.inputs[input.index()],
+            PortID::Output(output) => &mut self.outputs[output.index()],
+        };
+        if *state != new_state {
+            *state = new_state;
+            self.transitions.push((port, new_state));
+        }
+    }
+
+    /// Receives a frame if one is buffered, or if there is nothing more
+    /// to wait for. Returns `None` otherwise.
+    fn take(&mut self, input: InputPortID) -> Option<PortResult<Option<Frame>>> {
+        let input_state = match self.port_state(input.into()) {
+            Ok(input_state) => input_state,
+            Err(error) => return Some(Err(error)),
+        };
+        if let Some(error) = self.failures.remove(&input.into()) {
+            return Some(Err(error));
+        }
+        if input_state.is_closed() && self.is_terminated {
+            return Some(Err(PortError::Closed));
+        }
+        if input_state.is_closed() {
+            return Some(Ok(None)); // EOS (port closed)
+        }
+        if let Some(frame) = self.inboxes[input.index()].pop_front() {
+            if frame.is_eos() {
+                self.disconnect_input(input);
+            }
+            return Some(Ok(Some(frame)));
+        }
+        match input_state {
+            PortState::Open => Some(Err(PortError::Disconnected)),
+            _ => None, // keep waiting
+        }
+    }
+
+    fn disconnect_input(&mut self, input: InputPortID) {
+        if let PortState::Connected(PortID::Output(output)) = self.inputs[input.index()] {
+            if self.outputs[output.index()] != PortState::Connected(input.into()) {
+                self.set_state(input.into(), PortState::Open);
+            }
         }
     }
 }
 
 impl Transport for MockTransport {
     fn state(&self, port: PortID) -> PortResult<PortState> {
-        let state = self.state.read();
-        match port {
-            PortID::Input(inport) => match state.inputs.get(inport.index()) {
-                None => Err(PortError::Invalid(port)),
-                Some(state) => Ok(*state),
-            },
-            PortID::Output(outport) => match state.outputs.get(outport.index()) {
-                None => Err(PortError::Invalid(port)),
-                Some(state) => Ok(*state),
-            },
-        }
+        self.state.read().port_state(port)
     }
 
     fn open_input(&self) -> PortResult<InputPortID> {
         let mut state = self.state.write();
         state.inputs.push(PortState::Open);
-        state.inboxes.push(MessageBuffer::new());
+        state.inboxes.push(VecDeque::new());
 
         InputPortID::try_from(-(state.inputs.len() as isize))
             .map_err(|s| PortError::Other(s.to_string()))
@@ -72,150 +221,234 @@ impl Transport for MockTransport {
     fn open_output(&self) -> PortResult<OutputPortID> {
         let mut state = self.state.write();
         state.outputs.push(PortState::Open);
+        state.sent.push(Vec::new());
 
         OutputPortID::try_from(state.outputs.len() as isize)
             .map_err(|s| PortError::Other(s.to_string()))
     }
 
     fn close_input(&self, input: InputPortID) -> PortResult<bool> {
-        let input_index = input.index();
-        let mut state = self.state.upgradable_read();
-        Ok(match state.inputs.get(input_index) {
-            None => return Err(PortError::Invalid(input.into())),
-            Some(input_state) => match input_state {
-                PortState::Closed => false, // already closed
-                PortState::Open => {
-                    state.with_upgraded(|state| {
-                        state.inputs[input_index] = PortState::Closed;
-                    });
-                    true
-                }
-                PortState::Connected(PortID::Output(output)) => {
-                    let output_index = output.index();
-                    debug_assert!(matches!(
-                        state.outputs[output_index],
-                        PortState::Connected(PortID::Input(_))
-                    ));
-                    state.with_upgraded(|state| {
-                        state.outputs[output_index] = PortState::Open;
-                        state.inputs[input_index] = PortState::Closed;
-                        state.inboxes[input_index].clear();
-                    });
-                    true
+        let mut state = self.state.write();
+        match state.port_state(input.into())? {
+            PortState::Closed => return Ok(false), // already closed
+            PortState::Open => {}
+            PortState::Connected(PortID::Output(output)) => {
+                if state.outputs[output.index()] == PortState::Connected(input.into()) {
+                    state.set_state(output.into(), PortState::Open);
                 }
-                PortState::Connected(PortID::Input(_)) => unreachable!(),
-            },
-        })
+            }
+            PortState::Connected(PortID::Input(_))
+            | PortState::FannedOut(..)
+            | PortState::FannedIn(_) => unreachable!(),
+        }
+        state.set_state(input.into(), PortState::Closed);
+        state.inboxes[input.index()].clear();
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(true)
     }
 
     fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
-        let output_index = output.index();
-        let mut state = self.state.upgradable_read();
-        Ok(match state.outputs.get(output_index) {
-            None => return Err(PortError::Invalid(output.into())),
-            Some(output_state) => match output_state {
-                PortState::Closed => false, // already closed
-                PortState::Open => {
-                    state.with_upgraded(|state| {
-                        state.outputs[output_index] = PortState::Closed;
-                    });
-                    true
-                }
-                PortState::Connected(PortID::Input(input)) => {
-                    let input = input.clone();
-                    let input_index = input.index();
-                    debug_assert!(matches!(
-                        state.inputs[input_index],
-                        PortState::Connected(PortID::Output(_))
-                    ));
-                    state.with_upgraded(|state| {
-                        state.outputs[output_index] = PortState::Closed;
-                        state.inputs[input_index] = PortState::Open;
-                    });
-                    drop(state);
-                    self.recv_notify(input); // wake up the receiving thread
-                    true
-                }
-                PortState::Connected(PortID::Output(_)) => unreachable!(),
-            },
-        })
+        let mut state = self.state.write();
+        match state.port_state(output.into())? {
+            PortState::Closed => return Ok(false), // already closed
+            PortState::Open => {}
+            PortState::Connected(PortID::Input(input)) => {
+                // The input stays connected until it receives the EOS:
+                state.inboxes[input.index()].push_back(Frame::Eos);
+            }
+            PortState::Connected(PortID::Output(_))
+            | PortState::FannedOut(..)
+            | PortState::FannedIn(_) => unreachable!(),
+        }
+        state.set_state(output.into(), PortState::Closed);
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(true)
     }
 
-    fn connect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+    fn close_all(&self) -> PortResult<()> {
+        let mut state = self.state.write();
+        state.is_terminated = true;
+        for index in 0..state.inputs.len() {
+            let input = InputPortID::try_from(-(index as isize + 1)).unwrap();
+            state.set_state(input.into(), PortState::Closed);
+        }
+        for index in 0..state.outputs.len() {
+            let output = OutputPortID::try_from(index as isize + 1).unwrap();
+            state.set_state(output.into(), PortState::Closed);
+        }
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(())
+    }
+
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        _options: ConnectionOptions,
+    ) -> PortResult<bool> {
         let mut state = self.state.write();
         match (
             state.outputs.get(source.index()),
             state.inputs.get(target.index()),
         ) {
             (Some(PortState::Open), Some(PortState::Open)) => {
-                state.outputs[source.index()] = PortState::Connected(PortID::Input(target));
-                state.inputs[target.index()] = PortState::Connected(PortID::Output(source));
+                state.set_state(source.into(), PortState::Connected(target.into()));
+                state.set_state(target.into(), PortState::Connected(source.into()));
             }
             _ => return Err(PortError::Invalid(PortID::Output(source))), // TODO: better errors
         };
+        drop(state);
+        self.state_changed.notify_all();
         Ok(true)
     }
 
-    fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
-        let input = {
-            let state = self.state.read();
-            match state.outputs.get(output.index()) {
-                None => return Err(PortError::Invalid(PortID::Output(output))),
-                Some(PortState::Closed) => return Err(PortError::Closed),
-                Some(PortState::Open) => return Err(PortError::Disconnected),
-                Some(PortState::Connected(PortID::Output(_))) => unreachable!(),
-                Some(PortState::Connected(PortID::Input(input))) => *input,
+    fn disconnect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+        let mut state = self.state.write();
+        state.port_state(target.into())?;
+        if state.port_state(source.into())? != PortState::Connected(target.into()) {
+            return Ok(false); // not connected
+        }
+        // The input stays connected until it receives the EOS:
+        state.inboxes[target.index()].push_back(Frame::Eos);
+        state.set_state(source.into(), PortState::Open);
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.state.read();
+        loop {
+            match state.port_state(port)? {
+                PortState::Closed => return Err(PortError::Closed),
+                PortState::Connected(_) | PortState::FannedOut(..) | PortState::FannedIn(_) => {
+                    return Ok(true)
+                }
+                PortState::Open => {}
+            }
+            match deadline {
+                None => self.state_changed.wait(&mut state),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Ok(false); // timed out
+                    }
+                    self.state_changed.wait_for(&mut state, deadline - now);
+                }
             }
-        };
-        {
-            let mut state = self.state.write();
-            state.inboxes[input.index()].push(message);
         }
-        self.recv_notify(input); // wake up the receiving thread
-        Ok(())
     }
 
-    fn recv(&self, input: InputPortID) -> PortResult<Option<Bytes>> {
-        let state = self.state.read();
-        if state.inputs.get(input.index()).is_none() {
-            return Err(PortError::Invalid(PortID::Input(input)));
+    fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
+        let mut state = self.state.write();
+        if let Some(error) = state.failures.remove(&output.into()) {
+            return Err(error);
         }
+        let input = match state.port_state(output.into())? {
+            PortState::Closed => return Err(PortError::Closed),
+            PortState::Open => return Err(PortError::Disconnected),
+            PortState::Connected(PortID::Output(_))
+            | PortState::FannedOut(..)
+            | PortState::FannedIn(_) => unreachable!(),
+            PortState::Connected(PortID::Input(input)) => input,
+        };
+        state.sent[output.index()].push(message.clone());
+        state.inboxes[input.index()].push_back(Frame::Data(message));
         drop(state);
+        self.state_changed.notify_all();
+        Ok(())
+    }
 
-        loop {
-            let mut state = self.state.upgradable_read();
-            if !state.inboxes[input.index()].is_empty() {
-                return Ok(state.with_upgraded(|state| state.inboxes[input.index()].pop()));
-            }
-            if state.inputs[input.index()].is_closed() {
-                return Ok(None);
-            }
-            drop(state);
-            self.recv_wait(input); // sleep until something happens
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        _timeout: Duration,
+    ) -> PortResult<()> {
+        self.send(output, message) // never blocks
+    }
+
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()> {
+        if !frame.is_control() {
+            return Err(PortError::Invalid(output.into())); // TODO: better errors
         }
+        let mut state = self.state.write();
+        let input = match state.port_state(output.into())? {
+            PortState::Closed => return Err(PortError::Closed),
+            PortState::Open => return Err(PortError::Disconnected),
+            PortState::Connected(PortID::Output(_))
+            | PortState::FannedOut(..)
+            | PortState::FannedIn(_) => unreachable!(),
+            PortState::Connected(PortID::Input(input)) => input,
+        };
+        state.inboxes[input.index()].push_back(frame);
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(())
     }
 
-    fn try_recv(&self, _input: InputPortID) -> PortResult<Option<Bytes>> {
-        todo!() // TODO: implement try_recv()
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.recv_frame(input, None) // blocking
+    }
+
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>> {
+        self.recv_frame(input, Instant::now().checked_add(timeout))
+    }
+
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let mut state = self.state.write();
+        if state.port_state(input.into())?.is_closed() {
+            return Err(PortError::Closed);
+        }
+        let result = state.take(input);
+        drop(state);
+        self.state_changed.notify_all();
+        result.unwrap_or(Ok(None))
     }
 }
 
 impl MockTransport {
-    fn recv_wait(&self, _input: InputPortID) {
-        let (ref recv_lock, ref recv_cvar) = self.wakeup;
-        let mut recv_guard = recv_lock.lock().unwrap();
-        while !*recv_guard {
-            recv_guard = recv_cvar.wait(recv_guard).unwrap(); // blocks the current thread
+    fn recv_frame(
+        &self,
+        input: InputPortID,
+        deadline: Option<Instant>,
+    ) -> PortResult<Option<Frame>> {
+        loop {
+            let mut state = self.state.write();
+            if let Some(result) = state.take(input) {
+                drop(state);
+                self.state_changed.notify_all();
+                return result;
+            }
+            let mut state = RwLockWriteGuard::downgrade(state);
+            match deadline {
+                None => self.state_changed.wait(&mut state),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(PortError::Timeout);
+                    }
+                    self.state_changed.wait_for(&mut state, deadline - now);
+                }
+            }
         }
-        *recv_guard = false;
     }
+}
 
-    fn recv_notify(&self, _input: InputPortID) {
-        let (ref recv_lock, ref recv_cvar) = self.wakeup;
-        let mut recv_guard = recv_lock.lock().unwrap();
-        *recv_guard = true;
-        recv_cvar.notify_all();
+fn input_id(port: &dyn Port) -> PortResult<InputPortID> {
+    match port.id() {
+        Some(PortID::Input(input)) => Ok(input),
+        _ => Err(PortError::Other("not an input port".into())),
     }
 }
 
-impl MockTransportState {}
+fn output_id(port: &dyn Port) -> PortResult<OutputPortID> {
+    match port.id() {
+        Some(PortID::Output(output)) => Ok(output),
+        _ => Err(PortError::Other("not an output port".into())),
+    }
+}
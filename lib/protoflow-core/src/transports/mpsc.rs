@@ -3,35 +3,246 @@
 extern crate std;
 
 use crate::{
-    prelude::{Bytes, ToString, Vec},
+    prelude::{vec, Arc, Box, Bytes, Duration, ToString, Vec, VecDeque},
+    prelude::{AtomicUsize, Ordering},
     transport::Transport,
-    InputPortID, OutputPortID, PortError, PortID, PortResult, PortState,
+    utils::{self, Condvar, RwCondvar},
+    ConnectionOptions, FanOut, Frame, InputPortID, OutputPortID, OverflowPolicy, PortError, PortID,
+    PortResult, PortState, SharedMessage,
 };
-use parking_lot::RwLock;
-use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use core::{
+    future::poll_fn,
+    task::{Context, Poll},
+};
+use futures::future::BoxFuture;
+use parking_lot::{Mutex, RwLock};
+use std::time::Instant;
 
 pub(crate) const DEFAULT_INPUT_PORT_COUNT: usize = 16;
 pub(crate) const DEFAULT_OUTPUT_PORT_COUNT: usize = 16;
-pub(crate) const DEFAULT_CONNECTION_CAPACITY: usize = 1;
 
 #[derive(Debug, Default)]
 pub struct MpscTransport {
     pub state: RwLock<MpscTransportState>,
+    /// Notified whenever a port is connected or closed.
+    state_changed: RwCondvar,
 }
 
 #[derive(Debug, Default)]
 pub struct MpscTransportState {
     outputs: Vec<PortState>,
     inputs: Vec<PortState>,
-    channels: Vec<(SyncSender<Bytes>, Receiver<Bytes>)>,
+    channels: Vec<Arc<MpscChannel>>,
+    targets: Vec<MpscTargets>,
+    /// The output ports that each input port is connected to.
+    sources: Vec<Vec<OutputPortID>>,
+    /// Whether each input port has received the EOS of all of its output
+    /// ports, without getting connected again since.
+    drained: Vec<bool>,
+    /// Whether all ports were closed at once, on a shutdown.
+    is_terminated: bool,
+}
+
+/// The input ports that an output port is connected to.
+#[derive(Debug, Default)]
+struct MpscTargets {
+    inputs: Vec<InputPortID>,
+    fanout: FanOut,
+    /// The position of the next input port, for round-robin fan-out.
+    next: AtomicUsize,
+}
+
+/// The bounded message queue of an input port.
+#[derive(Debug, Default)]
+struct MpscChannel {
+    state: Mutex<MpscChannelState>,
+    /// Notified whenever a message is enqueued, or the channel is closed.
+    not_empty: Condvar,
+    /// Notified whenever a message is dequeued, the channel is reconfigured,
+    /// or the channel is closed.
+    not_full: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct MpscChannelState {
+    queue: VecDeque<Frame>,
+    options: ConnectionOptions,
+    is_closed: bool,
+    /// Whether the channel was closed on a shutdown, rather than by its
+    /// input port.
+    is_terminated: bool,
 }
 
-unsafe impl Sync for MpscTransportState {}
+impl MpscChannel {
+    /// Configures the channel for its first connection, or checks that a
+    /// further connection fanning into it agrees with its configuration.
+    ///
+    /// Returns `false` if the options conflict with the configuration.
+    fn configure(&self, options: ConnectionOptions, is_first: bool) -> bool {
+        let mut state = self.state.lock();
+        let capacity = options.capacity.max(1);
+        if !is_first {
+            return state.options.capacity == capacity
+                && state.options.overflow == options.overflow;
+        }
+        state.options = ConnectionOptions {
+            capacity,
+            ..options
+        };
+        self.not_full.notify_all();
+        true
+    }
+
+    fn close(&self) {
+        let mut state = self.state.lock();
+        state.is_closed = true;
+        state.queue.clear(); // nobody is left to receive them
+        self.not_empty.notify_all(); // wake up the receiving thread
+        self.not_full.notify_all(); // wake up any sending threads
+    }
+
+    /// Closes the channel on a shutdown, discarding any buffered messages,
+    /// and failing any blocked or later operations on it.
+    fn terminate(&self) {
+        self.state.lock().is_terminated = true;
+        self.close();
+    }
+
+    /// Enqueues a control frame, regardless of the channel capacity.
+    fn send_control(&self, frame: Frame) {
+        let mut state = self.state.lock();
+        if !state.is_closed {
+            state.queue.push_back(frame);
+            self.not_empty.notify_all();
+        }
+    }
+
+    fn send(&self, frame: Frame, deadline: Option<Instant>) -> PortResult<()> {
+        let mut state = self.state.lock();
+        let mut frame = frame;
+        loop {
+            frame = match self.enqueue(&mut state, frame) {
+                Ok(result) => return result,
+                Err(frame) => frame, // the channel is full
+            };
+            match deadline {
+                None => self.not_full.wait(&mut state),
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(PortError::Timeout);
+                    }
+                    self.not_full.wait_until(&mut state, deadline);
+                }
+            }
+        }
+    }
+
+    /// Attempts to send a frame without blocking, registering the current
+    /// asynchronous task to be woken up once the channel may have room.
+    ///
+    /// The frame is only taken once sent, so that a cancelled send doesn't
+    /// lose it.
+    fn poll_send(&self, frame: &mut Option<Frame>, cx: &mut Context<'_>) -> Poll<PortResult<()>> {
+        let mut state = self.state.lock();
+        let Some(taken) = frame.take() else {
+            return Poll::Ready(Ok(())); // already sent
+        };
+        match self.enqueue(&mut state, taken) {
+            Ok(result) => Poll::Ready(result),
+            Err(taken) => {
+                *frame = Some(taken);
+                self.not_full.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Enqueues a frame as per the overflow policy, returning it back if the
+    /// channel is full and the sender should block.
+    fn enqueue(&self, state: &mut MpscChannelState, frame: Frame) -> Result<PortResult<()>, Frame> {
+        if state.is_closed {
+            return Ok(Err(PortError::Closed));
+        }
+        if state.queue.len() >= state.options.capacity {
+            match state.options.overflow {
+                OverflowPolicy::Block => return Err(frame),
+                OverflowPolicy::DropNewest => return Ok(Ok(())),
+                OverflowPolicy::DropOldest => {
+                    // Control frames are never dropped:
+                    if let Some(position) = state.queue.iter().position(Frame::is_data) {
+                        state.queue.remove(position);
+                    }
+                }
+                OverflowPolicy::Error => return Ok(Err(PortError::Overflow)),
+            }
+        }
+        state.queue.push_back(frame);
+        self.not_empty.notify_all();
+        Ok(Ok(()))
+    }
+
+    fn recv(&self, deadline: Option<Instant>) -> PortResult<Option<Frame>> {
+        let mut state = self.state.lock();
+        loop {
+            if let Some(result) = self.dequeue(&mut state) {
+                return result;
+            }
+            match deadline {
+                None => self.not_empty.wait(&mut state),
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(PortError::Timeout);
+                    }
+                    self.not_empty.wait_until(&mut state, deadline);
+                }
+            }
+        }
+    }
+
+    /// Attempts to receive a frame without blocking, registering the current
+    /// asynchronous task to be woken up once the channel may have one.
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<PortResult<Option<Frame>>> {
+        let mut state = self.state.lock();
+        match self.dequeue(&mut state) {
+            Some(result) => Poll::Ready(result),
+            None => {
+                self.not_empty.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Dequeues the next frame, returning `None` if the receiver should block.
+    fn dequeue(&self, state: &mut MpscChannelState) -> Option<PortResult<Option<Frame>>> {
+        if state.is_terminated {
+            return Some(Err(PortError::Closed));
+        }
+        if state.is_closed {
+            return Some(Ok(None)); // EOS (port closed)
+        }
+        let frame = state.queue.pop_front()?;
+        self.not_full.notify_all();
+        Some(Ok(Some(frame)))
+    }
+
+    fn try_recv(&self) -> PortResult<Option<Frame>> {
+        let mut state = self.state.lock();
+        if state.is_closed {
+            return Err(PortError::Closed);
+        }
+        let Some(frame) = state.queue.pop_front() else {
+            return Ok(None);
+        };
+        self.not_full.notify_all();
+        Ok(Some(frame))
+    }
+}
 
 impl MpscTransport {
     pub fn new() -> Self {
         Self {
             state: RwLock::new(MpscTransportState::new()),
+            state_changed: RwCondvar::new(),
         }
     }
 }
@@ -43,8 +254,33 @@ impl MpscTransportState {
             outputs: Vec::with_capacity(DEFAULT_OUTPUT_PORT_COUNT),
             inputs: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
             channels: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+            targets: Vec::with_capacity(DEFAULT_OUTPUT_PORT_COUNT),
+            sources: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+            drained: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+            is_terminated: false,
         }
     }
+
+    /// Updates the state of a connected output port after its input ports
+    /// have changed.
+    fn update_output(&mut self, output: OutputPortID) {
+        let targets = &self.targets[output.index()];
+        self.outputs[output.index()] = match targets.inputs.as_slice() {
+            [] => PortState::Open,
+            [input] => PortState::Connected(PortID::Input(*input)),
+            inputs => PortState::FannedOut(targets.fanout, inputs.len()),
+        };
+    }
+
+    /// Updates the state of a connected input port after its output ports
+    /// have changed.
+    fn update_input(&mut self, input: InputPortID) {
+        self.inputs[input.index()] = match self.sources[input.index()].as_slice() {
+            [] => PortState::Open,
+            [output] => PortState::Connected(PortID::Output(*output)),
+            outputs => PortState::FannedIn(outputs.len()),
+        };
+    }
 }
 
 impl Transport for MpscTransport {
@@ -65,9 +301,9 @@ impl Transport for MpscTransport {
     fn open_input(&self) -> PortResult<InputPortID> {
         let mut state = self.state.write();
         state.inputs.push(PortState::Open);
-        state
-            .channels
-            .push(sync_channel(DEFAULT_CONNECTION_CAPACITY));
+        state.channels.push(Arc::new(MpscChannel::default()));
+        state.sources.push(Vec::new());
+        state.drained.push(false);
 
         InputPortID::try_from(-(state.inputs.len() as isize))
             .map_err(|s| PortError::Other(s.to_string()))
@@ -76,6 +312,7 @@ impl Transport for MpscTransport {
     fn open_output(&self) -> PortResult<OutputPortID> {
         let mut state = self.state.write();
         state.outputs.push(PortState::Open);
+        state.targets.push(MpscTargets::default());
 
         OutputPortID::try_from(state.outputs.len() as isize)
             .map_err(|s| PortError::Other(s.to_string()))
@@ -84,7 +321,7 @@ impl Transport for MpscTransport {
     fn close_input(&self, input: InputPortID) -> PortResult<bool> {
         let input_index = input.index();
         let mut state = self.state.upgradable_read();
-        Ok(match state.inputs.get(input_index) {
+        let closed = match state.inputs.get(input_index) {
             None => return Err(PortError::Invalid(input.into())),
             Some(input_state) => match input_state {
                 PortState::Closed => false, // already closed
@@ -94,30 +331,37 @@ impl Transport for MpscTransport {
                     });
                     true
                 }
-                PortState::Connected(PortID::Output(output)) => {
-                    let output_index = output.index();
-                    debug_assert!(matches!(
-                        state.outputs[output_index],
-                        PortState::Connected(PortID::Input(_))
-                    ));
-                    let sender = state.channels[input_index].0.clone();
+                PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_) => {
+                    let channel = state.channels[input_index].clone();
                     state.with_upgraded(|state| {
-                        state.outputs[output_index] = PortState::Open;
+                        for output in core::mem::take(&mut state.sources[input_index]) {
+                            // The output may already have been closed, with
+                            // its EOS not yet received:
+                            let targets = &mut state.targets[output.index()].inputs;
+                            if let Some(position) = targets.iter().position(|&t| t == input) {
+                                targets.remove(position);
+                                state.update_output(output);
+                            }
+                        }
                         state.inputs[input_index] = PortState::Closed;
                     });
                     drop(state);
-                    sender.send(Bytes::new()).unwrap(); // blocking
+                    channel.close();
                     true
                 }
-                PortState::Connected(PortID::Input(_)) => unreachable!(),
+                PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..) => {
+                    unreachable!()
+                }
             },
-        })
+        };
+        self.state_changed.notify_all();
+        Ok(closed)
     }
 
     fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
         let output_index = output.index();
         let mut state = self.state.upgradable_read();
-        Ok(match state.outputs.get(output_index) {
+        let closed = match state.outputs.get(output_index) {
             None => return Err(PortError::Invalid(output.into())),
             Some(output_state) => match output_state {
                 PortState::Closed => false, // already closed
@@ -127,74 +371,383 @@ impl Transport for MpscTransport {
                     });
                     true
                 }
-                PortState::Connected(PortID::Input(input)) => {
-                    let input = input.clone();
-                    let input_index = input.index();
-                    debug_assert!(matches!(
-                        state.inputs[input_index],
-                        PortState::Connected(PortID::Output(_))
-                    ));
-                    let sender = state.channels[input_index].0.clone();
+                PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..) => {
+                    let mut channels = Vec::new();
                     state.with_upgraded(|state| {
+                        for input in core::mem::take(&mut state.targets[output_index].inputs) {
+                            let sources = &mut state.sources[input.index()];
+                            if sources.len() == 1 {
+                                // The input stays connected until it receives the EOS:
+                                channels.push(state.channels[input.index()].clone());
+                            } else {
+                                // The input only gets an EOS from its last output:
+                                sources.retain(|&source| source != output);
+                                state.update_input(input);
+                            }
+                        }
                         state.outputs[output_index] = PortState::Closed;
-                        state.inputs[input_index] = PortState::Open;
                     });
                     drop(state);
-                    sender.send(Bytes::new()).unwrap(); // blocking
+                    for channel in channels {
+                        channel.send_control(Frame::Eos);
+                    }
                     true
                 }
-                PortState::Connected(PortID::Output(_)) => unreachable!(),
+                PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_) => unreachable!(),
             },
-        })
+        };
+        self.state_changed.notify_all();
+        Ok(closed)
     }
 
-    fn connect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
         let mut state = self.state.write();
         match (
             state.outputs.get(source.index()),
             state.inputs.get(target.index()),
         ) {
-            (Some(PortState::Open), Some(PortState::Open)) => {
-                state.outputs[source.index()] = PortState::Connected(PortID::Input(target));
-                state.inputs[target.index()] = PortState::Connected(PortID::Output(source));
+            (None | Some(PortState::Closed), _) => {
+                return Err(PortError::Invalid(PortID::Output(source))); // TODO: better errors
+            }
+            (_, None | Some(PortState::Closed)) => {
+                return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
             }
-            _ => return Err(PortError::Invalid(PortID::Output(source))), // TODO: better errors
+            _ => {}
+        };
+        // The input's outputs must be distinct, and none of them closed with
+        // its EOS not yet received:
+        let sources = &state.sources[target.index()];
+        if sources.iter().any(|&output| {
+            output == source || !state.targets[output.index()].inputs.contains(&target)
+        }) {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        // The input's outputs share its channel, so must agree on its options:
+        let is_first = sources.is_empty();
+        if !state.channels[target.index()].configure(options, is_first) {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        let targets = &mut state.targets[source.index()];
+        targets.inputs.push(target);
+        targets.fanout = options.fanout;
+        state.update_output(source);
+        state.sources[target.index()].push(source);
+        state.update_input(target);
+        state.drained[target.index()] = false;
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+
+    fn disconnect(&self, source: OutputPortID, target: InputPortID) -> PortResult<bool> {
+        let mut state = self.state.write();
+        if state.outputs.get(source.index()).is_none() {
+            return Err(PortError::Invalid(PortID::Output(source)));
+        }
+        if state.inputs.get(target.index()).is_none() {
+            return Err(PortError::Invalid(PortID::Input(target)));
+        }
+        let targets = &mut state.targets[source.index()].inputs;
+        let Some(position) = targets.iter().position(|&input| input == target) else {
+            return Ok(false); // not connected
         };
+        targets.remove(position);
+        state.update_output(source);
+        let sources = &mut state.sources[target.index()];
+        if sources.len() == 1 {
+            // The input stays connected until it receives the EOS:
+            let channel = state.channels[target.index()].clone();
+            drop(state);
+            channel.send_control(Frame::Eos);
+        } else {
+            sources.retain(|&output| output != source);
+            state.update_input(target);
+        }
+        self.state_changed.notify_all();
         Ok(true)
     }
 
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        let deadline = timeout.and_then(|timeout| std::time::Instant::now().checked_add(timeout));
+        let mut state = self.state.read();
+        loop {
+            let port_state = match port {
+                PortID::Input(input) => state.inputs.get(input.index()),
+                PortID::Output(output) => state.outputs.get(output.index()),
+            };
+            match port_state {
+                None => return Err(PortError::Invalid(port)),
+                Some(PortState::Closed) => return Err(PortError::Closed),
+                Some(
+                    PortState::Connected(_) | PortState::FannedOut(..) | PortState::FannedIn(_),
+                ) => return Ok(true),
+                Some(PortState::Open) => {}
+            }
+            match deadline {
+                None => self.state_changed.wait(&mut state),
+                Some(deadline) => {
+                    let now = std::time::Instant::now();
+                    if now >= deadline {
+                        return Ok(false); // timed out
+                    }
+                    self.state_changed.wait_for(&mut state, deadline - now);
+                }
+            }
+        }
+    }
+
     fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
+        self.send_frame(output, Frame::Data(message), None) // blocking
+    }
+
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        timeout: Duration,
+    ) -> PortResult<()> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.send_frame(output, Frame::Data(message), deadline)
+    }
+
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()> {
+        if !frame.is_control() {
+            return Err(PortError::Invalid(PortID::Output(output))); // TODO: better errors
+        }
         let state = self.state.read();
-        let input = {
-            match state.outputs.get(output.index()) {
-                None => return Err(PortError::Invalid(PortID::Output(output))),
-                Some(PortState::Closed) => return Err(PortError::Closed),
-                Some(PortState::Open) => return Err(PortError::Disconnected),
-                Some(PortState::Connected(PortID::Output(_))) => unreachable!(),
-                Some(PortState::Connected(PortID::Input(input))) => *input,
+        match state.outputs.get(output.index()) {
+            None => return Err(PortError::Invalid(PortID::Output(output))),
+            Some(PortState::Closed) => return Err(PortError::Closed),
+            Some(PortState::Open) => return Err(PortError::Disconnected),
+            Some(_) => {}
+        }
+        // Control frames go to all the inputs, regardless of the fan-out:
+        let channels: Vec<_> = state.targets[output.index()]
+            .inputs
+            .iter()
+            .map(|input| state.channels[input.index()].clone())
+            .collect();
+        drop(state);
+        for channel in channels {
+            channel.send_control(frame.clone());
+        }
+        Ok(())
+    }
+
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let Some(channel) = self.input_channel(input)? else {
+            return Ok(None); // EOS
+        };
+        let frame = channel.recv(None)?; // blocking
+        self.received(input, frame)
+    }
+
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>> {
+        let Some(channel) = self.input_channel(input)? else {
+            return Ok(None); // EOS
+        };
+        let frame = channel.recv(Instant::now().checked_add(timeout))?;
+        self.received(input, frame)
+    }
+
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let state = self.state.read();
+        let channel = match state.inputs.get(input.index()) {
+            None => return Err(PortError::Invalid(PortID::Input(input))),
+            Some(PortState::Closed) => return Err(PortError::Closed),
+            Some(PortState::Open) => return Err(PortError::Disconnected),
+            Some(PortState::Connected(_) | PortState::FannedIn(_)) => {
+                state.channels[input.index()].clone()
             }
+            Some(PortState::FannedOut(..)) => unreachable!(),
         };
-        let sender = state.channels[input.index()].0.clone();
         drop(state);
-        Ok(sender.send(message).unwrap()) // blocking (TODO: error handling)
+        let frame = channel.try_recv()?;
+        self.received(input, frame)
+    }
+
+    fn is_zero_copy(&self) -> bool {
+        true
+    }
+
+    fn send_shared(
+        &self,
+        output: OutputPortID,
+        message: SharedMessage,
+        timeout: Option<Duration>,
+    ) -> PortResult<()> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        self.send_frame(output, Frame::Shared(message), deadline)
+    }
+
+    /// Receives a frame from whichever of the given input ports has one
+    /// available first, waiting on all of them at once.
+    fn recv_any(&self, inputs: &[InputPortID]) -> PortResult<Option<(usize, Option<Frame>)>> {
+        let channels = {
+            let state = self.state.read();
+            let mut channels = Vec::with_capacity(inputs.len());
+            for (index, input) in inputs.iter().enumerate() {
+                match state.inputs.get(input.index()) {
+                    None => return Err(PortError::Invalid(PortID::Input(*input))),
+                    Some(PortState::Closed) if state.is_terminated => {
+                        return Err(PortError::Closed)
+                    }
+                    Some(PortState::Connected(_) | PortState::FannedIn(_)) => {
+                        channels.push((index, state.channels[input.index()].clone()));
+                    }
+                    Some(_) => {} // not connected
+                }
+            }
+            channels
+        };
+        if channels.is_empty() {
+            return Ok(None);
+        }
+        // Register with all the channels, and wake up once any has a frame:
+        let (index, frame) = utils::block_on(poll_fn(|cx| {
+            for (index, channel) in &channels {
+                if let Poll::Ready(result) = channel.poll_recv(cx) {
+                    return Poll::Ready(result.map(|frame| (*index, frame)));
+                }
+            }
+            Poll::Pending
+        }))?;
+        Ok(Some((index, self.received(inputs[index], frame)?)))
+    }
+
+    /// Sends a message without blocking the current task.
+    ///
+    /// Cancelling the send before it completes doesn't send the message,
+    /// except to those inputs of a broadcast fan-out already sent to.
+    fn send_async(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+    ) -> Option<BoxFuture<'_, PortResult<()>>> {
+        Some(Box::pin(async move {
+            let frame = Frame::Data(message);
+            let mut result = Ok(());
+            for channel in self.output_channels(output)? {
+                let mut frame = Some(frame.clone());
+                let sent = poll_fn(|cx| channel.poll_send(&mut frame, cx)).await;
+                result = result.and(sent);
+            }
+            result
+        }))
+    }
+
+    /// Receives a frame without blocking the current task.
+    ///
+    /// Cancelling the receive before it completes doesn't lose any frame.
+    fn recv_async(&self, input: InputPortID) -> Option<BoxFuture<'_, PortResult<Option<Frame>>>> {
+        Some(Box::pin(async move {
+            let Some(channel) = self.input_channel(input)? else {
+                return Ok(None); // EOS
+            };
+            let frame = poll_fn(|cx| channel.poll_recv(cx)).await?;
+            self.received(input, frame)
+        }))
+    }
+
+    fn close_all(&self) -> PortResult<()> {
+        let channels = {
+            let mut state = self.state.write();
+            state.outputs.fill(PortState::Closed);
+            state.inputs.fill(PortState::Closed);
+            state.targets.iter_mut().for_each(|t| t.inputs.clear());
+            state.sources.iter_mut().for_each(Vec::clear);
+            state.is_terminated = true;
+            state.channels.clone()
+        };
+        self.state_changed.notify_all();
+        for channel in channels {
+            channel.terminate();
+        }
+        Ok(())
+    }
+}
+
+impl MpscTransport {
+    fn send_frame(
+        &self,
+        output: OutputPortID,
+        frame: Frame,
+        deadline: Option<Instant>,
+    ) -> PortResult<()> {
+        let channels = self.output_channels(output)?;
+        if let [channel] = channels.as_slice() {
+            return channel.send(frame, deadline);
+        }
+        // Send to all the inputs, even if some fail, returning the first error:
+        let mut result = Ok(());
+        for channel in channels {
+            let sent = channel.send(frame.clone(), deadline);
+            result = result.and(sent);
+        }
+        result
     }
 
-    fn recv(&self, input: InputPortID) -> PortResult<Option<Bytes>> {
+    /// Returns the channels that the next message sent on the output port
+    /// goes to, as per its fan-out.
+    fn output_channels(&self, output: OutputPortID) -> PortResult<Vec<Arc<MpscChannel>>> {
         let state = self.state.read();
-        if state.inputs.get(input.index()).is_none() {
-            return Err(PortError::Invalid(PortID::Input(input)));
+        match state.outputs.get(output.index()) {
+            None => Err(PortError::Invalid(PortID::Output(output))),
+            Some(PortState::Closed) => Err(PortError::Closed),
+            Some(PortState::Open) => Err(PortError::Disconnected),
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                unreachable!()
+            }
+            Some(PortState::Connected(PortID::Input(input))) => {
+                Ok(vec![state.channels[input.index()].clone()])
+            }
+            Some(PortState::FannedOut(FanOut::RoundRobin, count)) => {
+                let targets = &state.targets[output.index()];
+                let next = targets.next.fetch_add(1, Ordering::Relaxed) % count;
+                Ok(vec![state.channels[targets.inputs[next].index()].clone()])
+            }
+            Some(PortState::FannedOut(FanOut::Broadcast, _)) => Ok(state.targets[output.index()]
+                .inputs
+                .iter()
+                .map(|input| state.channels[input.index()].clone())
+                .collect()),
         }
-        if state.inputs[input.index()].is_closed() {
-            return Ok(None); // EOS
+    }
+
+    fn input_channel(&self, input: InputPortID) -> PortResult<Option<Arc<MpscChannel>>> {
+        let state = self.state.read();
+        match state.inputs.get(input.index()) {
+            None => Err(PortError::Invalid(PortID::Input(input))),
+            Some(PortState::Closed) if state.is_terminated => Err(PortError::Closed),
+            Some(PortState::Closed) => Ok(None),
+            Some(PortState::Open) if state.drained[input.index()] => Ok(None),
+            Some(_) => Ok(Some(state.channels[input.index()].clone())),
+        }
+    }
+
+    fn received(&self, input: InputPortID, frame: Option<Frame>) -> PortResult<Option<Frame>> {
+        if frame.as_ref().is_some_and(Frame::is_eos) {
+            self.disconnect_input(input);
         }
-        let receiver = &state.channels[input.index()].1;
-        let bytes = receiver
-            .recv() // blocking
-            .map_err(|_| PortError::Disconnected)?;
-        Ok(Some(bytes))
+        Ok(frame)
     }
 
-    fn try_recv(&self, _input: InputPortID) -> PortResult<Option<Bytes>> {
-        todo!() // TODO: implement try_recv()
+    fn disconnect_input(&self, input: InputPortID) {
+        let input_index = input.index();
+        let mut state = self.state.write();
+        if !state.inputs[input_index].is_closed() {
+            let mut sources = core::mem::take(&mut state.sources[input_index]);
+            sources.retain(|output| state.targets[output.index()].inputs.contains(&input));
+            state.drained[input_index] = sources.is_empty();
+            state.sources[input_index] = sources;
+            state.update_input(input);
+        }
+        drop(state);
+        self.state_changed.notify_all();
     }
 }
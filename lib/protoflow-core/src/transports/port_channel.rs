@@ -0,0 +1,240 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use super::{FrameStash, NumberedFrame};
+use crate::{
+    prelude::{vec, Arc, Vec},
+    runtimes::block_in_place,
+    ConnectionOptions, Frame, OutputPortID, OverflowPolicy, PortError, PortResult,
+};
+use core::fmt::Debug;
+use std::time::Instant;
+
+/// A library of bounded channels, such as Crossbeam's or Flume's, that a
+/// [`PortTable`](super::PortTable) connects ports through.
+///
+/// Besides carrying frames, a channel that nothing is ever sent over
+/// signals that an input port was closed, by getting disconnected.
+pub trait BoundedChannel {
+    /// The sending end of a channel.
+    type Sender: Clone + Debug + Send + Sync;
+
+    /// The receiving end of a channel.
+    type Receiver: Clone + Debug + Send + Sync;
+
+    /// Creates a channel with room for `capacity` frames, or a rendezvous
+    /// channel if `capacity` is zero.
+    fn bounded(capacity: usize) -> (Self::Sender, Self::Receiver);
+
+    /// Sends a frame if the channel has room for it, or returns it back.
+    ///
+    /// Returns `Err(PortError::Closed)` if the channel is disconnected.
+    fn try_send(sender: &Self::Sender, frame: NumberedFrame) -> PortResult<Option<NumberedFrame>>;
+
+    /// Receives a frame if the channel has one.
+    ///
+    /// Returns `Err(PortError::Disconnected)` if the channel is empty and
+    /// disconnected.
+    fn try_recv(receiver: &Self::Receiver) -> PortResult<Option<NumberedFrame>>;
+
+    /// Blocks until the frame is sent, or until `closed` gets disconnected.
+    ///
+    /// Returns `Err(PortError::Closed)` if either channel is disconnected.
+    /// Returns `Err(PortError::Timeout)` if the deadline passes first.
+    fn send_until(
+        sender: &Self::Sender,
+        frame: NumberedFrame,
+        closed: &Self::Receiver,
+        deadline: Option<Instant>,
+    ) -> PortResult<()>;
+
+    /// Blocks until one of the given pairs of channels and `closed` channels
+    /// has a frame, or gets disconnected.
+    ///
+    /// Returns the position of the pair in `channels`, along with the frame
+    /// received, or `None` if either channel of the pair is disconnected.
+    /// Returns `Err(PortError::Timeout)` if the deadline passes first.
+    fn select_recv(
+        channels: &[(&Self::Receiver, &Self::Receiver)],
+        deadline: Option<Instant>,
+    ) -> PortResult<(usize, Option<NumberedFrame>)>;
+}
+
+/// The channel of an input port of a [`PortTable`](super::PortTable),
+/// shared by all the output ports connected to the input port.
+///
+/// Dropping the channel closes it, waking up any operations blocked on
+/// either of its ends.
+#[derive(Debug)]
+pub struct PortChannel<C: BoundedChannel> {
+    /// The sending end of each output port, which is dropped when the output
+    /// port is closed, so that the channel signals the EOS once all of them
+    /// were dropped and all buffered messages have been received.
+    senders: Vec<(OutputPortID, C::Sender)>,
+    receiver: C::Receiver,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    /// The control frames that were evicted from, or didn't fit in, the
+    /// channel.
+    stash: Arc<FrameStash>,
+    /// Dropped when the input port is closed, waking up any blocked
+    /// operations selecting on `closed`.
+    _closer: C::Sender,
+    closed: C::Receiver,
+}
+
+/// The ends of a channel needed to send a frame.
+#[derive(Debug)]
+pub struct Sending<C: BoundedChannel> {
+    sender: C::Sender,
+    receiver: C::Receiver,
+    overflow: OverflowPolicy,
+    stash: Arc<FrameStash>,
+    closed: C::Receiver,
+}
+
+/// The ends of a channel needed to receive a frame.
+#[derive(Debug)]
+pub struct Receiving<C: BoundedChannel> {
+    receiver: C::Receiver,
+    stash: Arc<FrameStash>,
+    closed: C::Receiver,
+}
+
+impl<C: BoundedChannel> PortChannel<C> {
+    /// Creates a channel from an output port, buffering frames as per the
+    /// given options.
+    pub fn new(output: OutputPortID, options: ConnectionOptions) -> Self {
+        let capacity = options.capacity.max(1);
+        let (sender, receiver) = C::bounded(capacity);
+        let (closer, closed) = C::bounded(0);
+        Self {
+            senders: vec![(output, sender)],
+            receiver,
+            capacity,
+            overflow: options.overflow,
+            stash: Arc::new(FrameStash::new()),
+            _closer: closer,
+            closed,
+        }
+    }
+
+    /// Checks whether another output port connecting to the channel agrees
+    /// with its configuration, as the output ports share its buffer.
+    pub fn agrees_with(&self, options: ConnectionOptions) -> bool {
+        self.capacity == options.capacity.max(1) && self.overflow == options.overflow
+    }
+
+    /// Opens a sending end of the channel for another output port fanning
+    /// into its input port.
+    ///
+    /// Returns `false` if the sending ends of all output ports were closed.
+    pub fn open_sending(&mut self, output: OutputPortID) -> bool {
+        let Some((_, sender)) = self.senders.first() else {
+            return false;
+        };
+        let sender = sender.clone();
+        self.senders.push((output, sender));
+        true
+    }
+
+    /// Returns the ends of the channel needed to send a frame from an output
+    /// port, or `None` if its sending end was closed.
+    pub fn sending(&self, output: OutputPortID) -> Option<Sending<C>> {
+        let (_, sender) = self.senders.iter().find(|(o, _)| *o == output)?;
+        Some(Sending {
+            sender: sender.clone(),
+            receiver: self.receiver.clone(),
+            overflow: self.overflow,
+            stash: self.stash.clone(),
+            closed: self.closed.clone(),
+        })
+    }
+
+    /// Returns the ends of the channel needed to receive a frame.
+    pub fn receiving(&self) -> Receiving<C> {
+        Receiving {
+            receiver: self.receiver.clone(),
+            stash: self.stash.clone(),
+            closed: self.closed.clone(),
+        }
+    }
+
+    /// Closes the sending end of an output port, so that the input port
+    /// receives the end of the stream once the sending ends of all output
+    /// ports were closed, and it has received all buffered frames.
+    pub fn close_sending(&mut self, output: OutputPortID) {
+        self.senders.retain(|(o, _)| *o != output);
+    }
+}
+
+impl<C: BoundedChannel> Sending<C> {
+    /// The sending end of the channel.
+    pub fn sender(&self) -> &C::Sender {
+        &self.sender
+    }
+
+    /// The channel that gets disconnected once the input port is closed.
+    pub fn closed(&self) -> &C::Receiver {
+        &self.closed
+    }
+
+    /// Sends a frame, blocking until the channel has room for it if the
+    /// overflow policy says so, or until the deadline passes.
+    pub fn send(&self, frame: Frame, deadline: Option<Instant>) -> PortResult<()> {
+        let Some(frame) = self.try_send(frame)? else {
+            return Ok(());
+        };
+        block_in_place(|| C::send_until(&self.sender, frame, &self.closed, deadline))
+    }
+
+    /// Sends a frame if the channel has room for it, or if the overflow
+    /// policy doesn't block. Otherwise, returns the frame back.
+    pub fn try_send(&self, frame: Frame) -> PortResult<Option<NumberedFrame>> {
+        let mut frame = self.stash.number(frame);
+        loop {
+            frame = match C::try_send(&self.sender, frame)? {
+                None => return Ok(None),
+                Some(unsent_frame) => unsent_frame,
+            };
+            // Control frames are never dropped, regardless of the capacity:
+            if frame.1.is_control() {
+                self.stash.stash(frame);
+                return Ok(None);
+            }
+            match self.overflow {
+                OverflowPolicy::Block => return Ok(Some(frame)),
+                OverflowPolicy::DropNewest => return Ok(None),
+                OverflowPolicy::DropOldest => self
+                    .stash
+                    .evict(|| C::try_recv(&self.receiver).ok().flatten()),
+                OverflowPolicy::Error => return Err(PortError::Overflow),
+            }
+        }
+    }
+}
+
+impl<C: BoundedChannel> Receiving<C> {
+    /// The receiving end of the channel.
+    pub fn receiver(&self) -> &C::Receiver {
+        &self.receiver
+    }
+
+    /// The channel that gets disconnected once the input port is closed.
+    pub fn closed(&self) -> &C::Receiver {
+        &self.closed
+    }
+
+    /// The control frames that were evicted from, or didn't fit in, the
+    /// channel.
+    pub fn stash(&self) -> &FrameStash {
+        &self.stash
+    }
+
+    /// Returns the next frame to receive, if the channel or its stash has
+    /// one available.
+    pub fn try_next(&self) -> Option<Frame> {
+        self.stash.next(C::try_recv(&self.receiver).ok().flatten())
+    }
+}
@@ -0,0 +1,528 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use super::{BoundedChannel, FrameStash, NumberedFrame, PortChannel, Receiving, Sending};
+use crate::{
+    prelude::{AtomicUsize, Duration, Ordering, ToString, Vec},
+    runtimes::block_in_place,
+    utils::Condvar,
+    ConnectionOptions, FanOut, Frame, InputPortID, OutputPortID, PortError, PortID, PortResult,
+    PortState,
+};
+use parking_lot::Mutex;
+use std::time::Instant;
+
+const DEFAULT_INPUT_PORT_COUNT: usize = 16;
+const DEFAULT_OUTPUT_PORT_COUNT: usize = 16;
+
+/// The ports of a transport that connects them through bounded channels,
+/// one for each connected input port.
+///
+/// An output port fans out to several input ports by sending over each of
+/// their channels, and several output ports fan in to an input port by
+/// sharing its channel, so that they must agree on its capacity and
+/// overflow policy.
+///
+/// This keeps track of the state of the ports and of their channels, and
+/// sends and receives frames over the channels, leaving it to the transport
+/// to pick the library of channels.
+#[derive(Debug)]
+pub struct PortTable<C: BoundedChannel> {
+    state: Mutex<PortTableState<C>>,
+    /// Notified whenever a port is connected or closed.
+    state_changed: Condvar,
+}
+
+#[derive(Debug)]
+struct PortTableState<C: BoundedChannel> {
+    outputs: Vec<PortState>,
+    inputs: Vec<PortState>,
+    targets: Vec<PortTargets>,
+    /// The output ports that each input port is connected to, including a
+    /// closed one whose EOS it has yet to receive.
+    sources: Vec<Vec<OutputPortID>>,
+    /// The channel of each input port, created when the port is connected,
+    /// and dropped once the port is closed or has received the EOS.
+    channels: Vec<Option<PortChannel<C>>>,
+    /// Whether each input port has received the EOS of all of its output
+    /// ports, without getting connected again since.
+    drained: Vec<bool>,
+    /// Whether all ports were closed at once, on a shutdown.
+    is_terminated: bool,
+}
+
+/// The input ports that an output port is connected to.
+#[derive(Debug, Default)]
+struct PortTargets {
+    inputs: Vec<InputPortID>,
+    fanout: FanOut,
+    /// The position of the next input port, for round-robin fan-out.
+    next: AtomicUsize,
+}
+
+impl<C: BoundedChannel> Default for PortTable<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: BoundedChannel> PortTable<C> {
+    pub fn new() -> Self {
+        // Avoid reallocations by pre-allocating an ample default capacity.
+        Self {
+            state: Mutex::new(PortTableState {
+                outputs: Vec::with_capacity(DEFAULT_OUTPUT_PORT_COUNT),
+                inputs: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+                targets: Vec::with_capacity(DEFAULT_OUTPUT_PORT_COUNT),
+                sources: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+                channels: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+                drained: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+                is_terminated: false,
+            }),
+            state_changed: Condvar::new(),
+        }
+    }
+
+    pub fn state(&self, port: PortID) -> PortResult<PortState> {
+        let state = self.state.lock();
+        match port {
+            PortID::Input(input) => match state.inputs.get(input.index()) {
+                None => Err(PortError::Invalid(port)),
+                Some(state) => Ok(*state),
+            },
+            PortID::Output(output) => match state.outputs.get(output.index()) {
+                None => Err(PortError::Invalid(port)),
+                Some(state) => Ok(*state),
+            },
+        }
+    }
+
+    pub fn open_input(&self) -> PortResult<InputPortID> {
+        let mut state = self.state.lock();
+        state.inputs.push(PortState::Open);
+        state.sources.push(Vec::new());
+        state.channels.push(None);
+        state.drained.push(false);
+
+        InputPortID::try_from(-(state.inputs.len() as isize))
+            .map_err(|s| PortError::Other(s.to_string()))
+    }
+
+    pub fn open_output(&self) -> PortResult<OutputPortID> {
+        let mut state = self.state.lock();
+        state.outputs.push(PortState::Open);
+        state.targets.push(PortTargets::default());
+
+        OutputPortID::try_from(state.outputs.len() as isize)
+            .map_err(|s| PortError::Other(s.to_string()))
+    }
+
+    /// Closes an input port, dropping its channel.
+    pub fn close_input(&self, input: InputPortID) -> PortResult<bool> {
+        let input_index = input.index();
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        match state.inputs.get(input_index) {
+            None => return Err(PortError::Invalid(input.into())),
+            Some(PortState::Closed) => return Ok(false), // already closed
+            Some(PortState::Open) => {}
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                for output in core::mem::take(&mut state.sources[input_index]) {
+                    // The output may already have been closed, with its EOS
+                    // not yet received:
+                    let targets = &mut state.targets[output.index()].inputs;
+                    if let Some(position) = targets.iter().position(|&t| t == input) {
+                        targets.remove(position);
+                        state.update_output(output);
+                    }
+                }
+            }
+            Some(PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..)) => {
+                unreachable!()
+            }
+        };
+        state.inputs[input_index] = PortState::Closed;
+        state.channels[input_index] = None; // wake up any blocked operations
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+
+    /// Closes an output port, closing its sending ends of the channels of
+    /// its input ports.
+    pub fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
+        let output_index = output.index();
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        match state.outputs.get(output_index) {
+            None => return Err(PortError::Invalid(output.into())),
+            Some(PortState::Closed) => return Ok(false), // already closed
+            Some(PortState::Open) => {}
+            Some(PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..)) => {
+                for input in core::mem::take(&mut state.targets[output_index].inputs) {
+                    let input_index = input.index();
+                    if let Some(channel) = state.channels[input_index].as_mut() {
+                        channel.close_sending(output);
+                    }
+                    // The input stays connected until it receives the EOS,
+                    // which it only gets from its last output:
+                    let sources = &mut state.sources[input_index];
+                    if sources.len() > 1 {
+                        sources.retain(|&source| source != output);
+                        state.update_input(input);
+                    }
+                }
+            }
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                unreachable!()
+            }
+        };
+        state.outputs[output_index] = PortState::Closed;
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+
+    /// Connects two open ports, through the channel of the input port.
+    pub fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        match (
+            state.outputs.get(source.index()),
+            state.inputs.get(target.index()),
+        ) {
+            (None | Some(PortState::Closed), _) => {
+                return Err(PortError::Invalid(PortID::Output(source))); // TODO: better errors
+            }
+            (_, None | Some(PortState::Closed)) => {
+                return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+            }
+            _ => {}
+        };
+        // The input's outputs must be distinct, and none of them closed with
+        // its EOS not yet received:
+        let sources = &state.sources[target.index()];
+        if sources.iter().any(|&output| {
+            output == source || !state.targets[output.index()].inputs.contains(&target)
+        }) {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        // The input's outputs share its channel, so must agree on its options:
+        match state.channels[target.index()].as_mut() {
+            None => state.channels[target.index()] = Some(PortChannel::new(source, options)),
+            Some(channel) => {
+                if !channel.agrees_with(options) || !channel.open_sending(source) {
+                    return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+                }
+            }
+        }
+        let targets = &mut state.targets[source.index()];
+        targets.inputs.push(target);
+        targets.fanout = options.fanout;
+        state.update_output(source);
+        state.sources[target.index()].push(source);
+        state.update_input(target);
+        state.drained[target.index()] = false;
+        self.state_changed.notify_all();
+        Ok(true)
+    }
+
+    pub fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.state.lock();
+        loop {
+            let port_state = match port {
+                PortID::Input(input) => state.inputs.get(input.index()),
+                PortID::Output(output) => state.outputs.get(output.index()),
+            };
+            match port_state {
+                None => return Err(PortError::Invalid(port)),
+                Some(PortState::Closed) => return Err(PortError::Closed),
+                Some(
+                    PortState::Connected(_) | PortState::FannedOut(..) | PortState::FannedIn(_),
+                ) => return Ok(true),
+                Some(PortState::Open) => {}
+            }
+            match deadline {
+                None => self.state_changed.wait(&mut state),
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Ok(false); // timed out
+                    }
+                    self.state_changed.wait_until(&mut state, deadline);
+                }
+            }
+        }
+    }
+
+    /// Closes all ports on a shutdown, dropping their channels.
+    pub fn close_all(&self) {
+        let channels: Vec<_> = {
+            let mut state = self.state.lock();
+            state.outputs.fill(PortState::Closed);
+            state.inputs.fill(PortState::Closed);
+            state.targets.iter_mut().for_each(|t| t.inputs.clear());
+            state.sources.iter_mut().for_each(Vec::clear);
+            state.is_terminated = true;
+            state.channels.iter_mut().map(Option::take).collect()
+        };
+        drop(channels); // wake up any blocked operations
+        self.state_changed.notify_all();
+    }
+
+    /// Sends a frame over the channels of a connected output port, as per
+    /// its fan-out, blocking until each channel has room for it if the
+    /// overflow policy says so, or until the deadline passes.
+    pub fn send(
+        &self,
+        output: OutputPortID,
+        frame: Frame,
+        deadline: Option<Instant>,
+    ) -> PortResult<()> {
+        let sendings = self.sending(output, &frame)?;
+        if let [sending] = sendings.as_slice() {
+            return sending.send(frame, deadline);
+        }
+        // Send to all the inputs, even if some fail, returning the first error:
+        let mut result = Ok(());
+        for sending in sendings {
+            let sent = sending.send(frame.clone(), deadline);
+            result = result.and(sent);
+        }
+        result
+    }
+
+    /// Receives a frame over the channel of an input port, blocking until
+    /// one is available, or until the deadline passes.
+    ///
+    /// Returns `Ok(None)` if the port is closed.
+    pub fn recv(&self, input: InputPortID, deadline: Option<Instant>) -> PortResult<Option<Frame>> {
+        let Some(receiving) = self.receiving(input, deadline)? else {
+            return Ok(None); // EOS (port closed)
+        };
+        if let Some(frame) = receiving.try_next() {
+            return Ok(Some(frame));
+        }
+        let channels = [(receiving.receiver(), receiving.closed())];
+        let (_, frame) = block_in_place(|| C::select_recv(&channels, deadline))?;
+        self.received(input, receiving.stash(), frame)
+    }
+
+    /// Receives a frame over the channel of a connected input port, if one
+    /// is available, without blocking.
+    pub fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let receiving = self.try_receiving(input)?;
+        match C::try_recv(receiving.receiver()) {
+            Ok(frame) => Ok(receiving.stash().next(frame)),
+            Err(_) => match self.received(input, receiving.stash(), None)? {
+                None => Err(PortError::Closed),
+                frame => Ok(frame),
+            },
+        }
+    }
+
+    /// Receives a frame from whichever of the given input ports has one
+    /// available first, selecting over all of their channels at once.
+    pub fn recv_any(&self, inputs: &[InputPortID]) -> PortResult<Option<(usize, Option<Frame>)>> {
+        let candidates = self.receiving_any(inputs)?;
+        if candidates.is_empty() {
+            return Ok(None);
+        }
+
+        // Prefer the ports in order when several have a frame available:
+        for (index, receiving) in &candidates {
+            let frame = receiving.try_next();
+            if frame.is_some() {
+                return Ok(Some((*index, frame)));
+            }
+        }
+        let channels: Vec<_> = candidates
+            .iter()
+            .map(|(_, receiving)| (receiving.receiver(), receiving.closed()))
+            .collect();
+        let (position, frame) = block_in_place(|| C::select_recv(&channels, None))?; // blocking
+        let (index, receiving) = &candidates[position];
+        let frame = self.received(inputs[*index], receiving.stash(), frame)?;
+        Ok(Some((*index, frame)))
+    }
+
+    /// Returns the ends of the channels of a connected output port needed to
+    /// send a frame, as per its fan-out: control frames go to all the input
+    /// ports, regardless of the fan-out.
+    pub fn sending(&self, output: OutputPortID, frame: &Frame) -> PortResult<Vec<Sending<C>>> {
+        let state = self.state.lock();
+        let targets = &state.targets[match state.outputs.get(output.index()) {
+            None => return Err(PortError::Invalid(PortID::Output(output))),
+            Some(PortState::Closed) => return Err(PortError::Closed),
+            Some(PortState::Open) => return Err(PortError::Disconnected),
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                unreachable!()
+            }
+            Some(PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..)) => {
+                output.index()
+            }
+        }];
+        let inputs = match targets.fanout {
+            FanOut::RoundRobin if !frame.is_control() => {
+                let next = targets.next.fetch_add(1, Ordering::Relaxed) % targets.inputs.len();
+                &targets.inputs[next..=next]
+            }
+            _ => targets.inputs.as_slice(),
+        };
+        inputs
+            .iter()
+            .map(|input| {
+                let channel = state.channels[input.index()].as_ref().unwrap();
+                channel.sending(output).ok_or(PortError::Closed)
+            })
+            .collect()
+    }
+
+    /// Returns the ends of the channel of an input port needed to receive a
+    /// frame, waiting for the port to get connected if need be.
+    ///
+    /// Returns `Ok(None)` if the port is closed, or if it has received the
+    /// EOS of all of its output ports.
+    pub fn receiving(
+        &self,
+        input: InputPortID,
+        deadline: Option<Instant>,
+    ) -> PortResult<Option<Receiving<C>>> {
+        let mut state = self.state.lock();
+        loop {
+            match state.inputs.get(input.index()) {
+                None => return Err(PortError::Invalid(PortID::Input(input))),
+                Some(PortState::Closed) if state.is_terminated => return Err(PortError::Closed),
+                Some(PortState::Closed) => return Ok(None),
+                Some(PortState::Open) if state.drained[input.index()] => return Ok(None),
+                Some(PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..)) => {
+                    unreachable!()
+                }
+                Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                    let channel = state.channels[input.index()].as_ref().unwrap();
+                    return Ok(Some(channel.receiving()));
+                }
+                Some(PortState::Open) => {}
+            }
+            match deadline {
+                None => self.state_changed.wait(&mut state),
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Err(PortError::Timeout);
+                    }
+                    self.state_changed.wait_until(&mut state, deadline);
+                }
+            }
+        }
+    }
+
+    /// Returns the ends of the channel of a connected input port needed to
+    /// receive a frame, without waiting.
+    fn try_receiving(&self, input: InputPortID) -> PortResult<Receiving<C>> {
+        let state = self.state.lock();
+        match state.inputs.get(input.index()) {
+            None => Err(PortError::Invalid(PortID::Input(input))),
+            Some(PortState::Closed) => Err(PortError::Closed),
+            Some(PortState::Open) => Err(PortError::Disconnected),
+            Some(PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..)) => {
+                unreachable!()
+            }
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                Ok(state.channels[input.index()].as_ref().unwrap().receiving())
+            }
+        }
+    }
+
+    /// Returns the ends of the channels of those of the given input ports
+    /// that are connected, along with their positions in `inputs`.
+    fn receiving_any(&self, inputs: &[InputPortID]) -> PortResult<Vec<(usize, Receiving<C>)>> {
+        let state = self.state.lock();
+        let mut candidates = Vec::with_capacity(inputs.len());
+        for (index, input) in inputs.iter().enumerate() {
+            match state.inputs.get(input.index()) {
+                None => return Err(PortError::Invalid(PortID::Input(*input))),
+                Some(PortState::Closed) if state.is_terminated => return Err(PortError::Closed),
+                Some(PortState::Connected(_) | PortState::FannedIn(_)) => {
+                    let channel = state.channels[input.index()].as_ref().unwrap();
+                    candidates.push((index, channel.receiving()));
+                }
+                Some(_) => {} // not connected
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Handles the disconnection of the channel of an input port, either by
+    /// closing all of its output ports, or by closing the input port itself.
+    ///
+    /// Returns the end of the stream in the former case, and `Ok(None)` in
+    /// the latter case, or `Err(PortError::Closed)` on a shutdown.
+    pub fn disconnected(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let input_index = input.index();
+        let mut state = self.state.lock();
+        match state.inputs.get(input_index) {
+            None => return Err(PortError::Invalid(PortID::Input(input))),
+            Some(PortState::Closed) if state.is_terminated => return Err(PortError::Closed),
+            Some(PortState::Closed) => return Ok(None), // EOS (port closed)
+            Some(PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_)) => {
+                let state = &mut *state;
+                let sources = &state.sources[input_index];
+                if sources
+                    .iter()
+                    .all(|output| !state.targets[output.index()].inputs.contains(&input))
+                {
+                    state.inputs[input_index] = PortState::Open;
+                    state.sources[input_index].clear();
+                    state.channels[input_index] = None;
+                    state.drained[input_index] = true;
+                }
+            }
+            Some(_) => {}
+        }
+        drop(state);
+        self.state_changed.notify_all();
+        Ok(Some(Frame::Eos))
+    }
+
+    /// Handles a frame taken out of the channel of an input port, where
+    /// `None` means that the channel got disconnected, returning the next
+    /// frame to receive, given the frames stashed for the channel.
+    pub fn received(
+        &self,
+        input: InputPortID,
+        stash: &FrameStash,
+        frame: Option<NumberedFrame>,
+    ) -> PortResult<Option<Frame>> {
+        match stash.next(frame) {
+            Some(frame) => Ok(Some(frame)),
+            None => self.disconnected(input),
+        }
+    }
+}
+
+impl<C: BoundedChannel> PortTableState<C> {
+    /// Updates the state of a connected output port after its input ports
+    /// have changed.
+    fn update_output(&mut self, output: OutputPortID) {
+        let targets = &self.targets[output.index()];
+        self.outputs[output.index()] = match targets.inputs.as_slice() {
+            [] => PortState::Open,
+            [input] => PortState::Connected(PortID::Input(*input)),
+            inputs => PortState::FannedOut(targets.fanout, inputs.len()),
+        };
+    }
+
+    /// Updates the state of a connected input port after its output ports
+    /// have changed.
+    fn update_input(&mut self, input: InputPortID) {
+        self.inputs[input.index()] = match self.sources[input.index()].as_slice() {
+            [] => PortState::Open,
+            [output] => PortState::Connected(PortID::Output(*output)),
+            outputs => PortState::FannedIn(outputs.len()),
+        };
+    }
+}
@@ -0,0 +1,58 @@
+// This is free and unencumbered software released into the public domain.
+
+#![allow(unused)]
+
+extern crate std;
+
+use super::Condvar;
+use crate::prelude::Arc;
+use core::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+use parking_lot::Mutex;
+use std::task::Wake;
+
+/// Drives a future to completion on the current thread, or suspends the
+/// current `PoolRuntime` task while the future is pending.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let signal = Arc::new(Signal::default());
+    let waker = Waker::from(signal.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        signal.wait();
+    }
+}
+
+/// Wakes up a thread or task blocked in `block_on`.
+#[derive(Default)]
+struct Signal {
+    is_woken: Mutex<bool>,
+    woken: Condvar,
+}
+
+impl Signal {
+    fn wait(&self) {
+        let mut is_woken = self.is_woken.lock();
+        while !*is_woken {
+            self.woken.wait(&mut is_woken);
+        }
+        *is_woken = false;
+    }
+}
+
+impl Wake for Signal {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        *self.is_woken.lock() = true;
+        self.woken.notify_all();
+    }
+}
@@ -0,0 +1,131 @@
+// This is free and unencumbered software released into the public domain.
+
+#![allow(unused)]
+
+extern crate std;
+
+use crate::prelude::{fmt, Vec};
+#[cfg(feature = "pool")]
+use crate::runtimes::{Task, TaskWaker};
+use core::task::Waker;
+use parking_lot::{Mutex, MutexGuard};
+use std::time::Instant;
+
+/// A condition variable that suspends the waiting block, rather than its
+/// thread, when waited on from a `PoolRuntime` task, and that can also wake
+/// up asynchronous tasks.
+#[derive(Default)]
+pub struct Condvar {
+    condvar: parking_lot::Condvar,
+    /// The tasks currently waiting on this condvar.
+    #[cfg(feature = "pool")]
+    tasks: Mutex<Vec<TaskWaker>>,
+    /// The asynchronous tasks registered with this condvar.
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl Condvar {
+    /// Creates a new condition variable which is ready to be waited on and
+    /// notified.
+    pub const fn new() -> Self {
+        Self {
+            condvar: parking_lot::Condvar::new(),
+            #[cfg(feature = "pool")]
+            tasks: Mutex::new(Vec::new()),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers an asynchronous task to be woken up by the next
+    /// notification.
+    ///
+    /// The caller must hold the lock that notifiers hold, so that a
+    /// notification can't slip in between its check and its registration.
+    pub fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock();
+        if !wakers.iter().any(|other| other.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+    }
+
+    /// Wakes up one blocked task or thread on this condvar, as well as all
+    /// registered asynchronous tasks, any of which may have been cancelled.
+    pub fn notify_one(&self) {
+        self.wake_registered();
+        #[cfg(feature = "pool")]
+        {
+            let mut tasks = self.tasks.lock();
+            if !tasks.is_empty() {
+                let task = tasks.remove(0);
+                drop(tasks);
+                return task.wake();
+            }
+        }
+        self.condvar.notify_one();
+    }
+
+    /// Wakes up all blocked tasks and threads on this condvar.
+    pub fn notify_all(&self) {
+        self.wake_registered();
+        #[cfg(feature = "pool")]
+        for task in core::mem::take(&mut *self.tasks.lock()) {
+            task.wake();
+        }
+        self.condvar.notify_all();
+    }
+
+    fn wake_registered(&self) {
+        let wakers = core::mem::take(&mut *self.wakers.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Blocks the current task or thread until this condition variable
+    /// receives a notification.
+    pub fn wait<T: ?Sized>(&self, mutex_guard: &mut MutexGuard<'_, T>) {
+        self.park(mutex_guard, None);
+    }
+
+    /// Blocks the current task or thread until this condition variable
+    /// receives a notification, or until the deadline passes.
+    ///
+    /// Returns `true` if the deadline passed.
+    pub fn wait_until<T: ?Sized>(
+        &self,
+        mutex_guard: &mut MutexGuard<'_, T>,
+        deadline: Instant,
+    ) -> bool {
+        self.park(mutex_guard, Some(deadline))
+    }
+
+    fn park<T: ?Sized>(
+        &self,
+        mutex_guard: &mut MutexGuard<'_, T>,
+        deadline: Option<Instant>,
+    ) -> bool {
+        #[cfg(feature = "pool")]
+        if let Some(task) = Task::current() {
+            // Register while still holding the caller's lock, so that a
+            // notification can't slip in between its unlock and the suspension:
+            let waker = task.waker();
+            self.tasks.lock().push(waker.clone());
+            MutexGuard::unlocked(mutex_guard, || task.suspend(deadline));
+            self.tasks.lock().retain(|other| !other.is(&waker));
+            return deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        match deadline {
+            None => {
+                self.condvar.wait(mutex_guard);
+                false
+            }
+            Some(deadline) => self.condvar.wait_until(mutex_guard, deadline).timed_out(),
+        }
+    }
+}
+
+impl fmt::Debug for Condvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("Condvar { .. }")
+    }
+}
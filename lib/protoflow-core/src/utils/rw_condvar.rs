@@ -2,8 +2,16 @@
 
 #![allow(unused)]
 
-use crate::prelude::fmt;
-use parking_lot::{Condvar, Mutex, RwLockReadGuard};
+#[cfg(feature = "std")]
+extern crate std;
+
+use crate::prelude::{fmt, Duration};
+use parking_lot::{Mutex, RwLockReadGuard};
+
+#[cfg(feature = "std")]
+use super::Condvar;
+#[cfg(not(feature = "std"))]
+use parking_lot::Condvar;
 
 // See: https://github.com/Amanieu/parking_lot/issues/165
 #[derive(Default)]
@@ -24,14 +32,18 @@ impl RwCondvar {
 
     /// Wakes up one blocked thread on this condvar.
     #[inline]
-    pub fn notify_one(&self) -> bool {
-        self.condvar.notify_one()
+    pub fn notify_one(&self) {
+        // Synchronize with any thread that is about to wait, so that the
+        // notification can't slip in between its unlock and its wait:
+        let _mutex_guard = self.mutex.lock();
+        self.condvar.notify_one();
     }
 
     /// Wakes up all blocked threads on this condvar.
     #[inline]
-    pub fn notify_all(&self) -> usize {
-        self.condvar.notify_all()
+    pub fn notify_all(&self) {
+        let _mutex_guard = self.mutex.lock();
+        self.condvar.notify_all();
     }
 
     /// Blocks the current thread until this condition variable receives a
@@ -44,6 +56,27 @@ impl RwCondvar {
             self.condvar.wait(&mut mutex_guard);
         });
     }
+
+    /// Blocks the current thread until this condition variable receives a
+    /// notification, or until the timeout elapses.
+    ///
+    /// Returns `true` if the timeout elapsed.
+    pub fn wait_for<T: ?Sized>(
+        &self,
+        rwlock_read_guard: &mut RwLockReadGuard<'_, T>,
+        timeout: Duration,
+    ) -> bool {
+        #[cfg(feature = "std")]
+        let deadline = std::time::Instant::now() + timeout;
+        let mutex_guard = self.mutex.lock();
+        RwLockReadGuard::unlocked(rwlock_read_guard, || {
+            let mut mutex_guard = mutex_guard;
+            #[cfg(feature = "std")]
+            return self.condvar.wait_until(&mut mutex_guard, deadline);
+            #[cfg(not(feature = "std"))]
+            return self.condvar.wait_for(&mut mutex_guard, timeout).timed_out();
+        })
+    }
 }
 
 impl fmt::Debug for RwCondvar {
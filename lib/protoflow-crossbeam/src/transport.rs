@@ -0,0 +1,188 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use crossbeam::channel::{bounded, Receiver, Select, Sender, TryRecvError, TrySendError};
+use protoflow_core::{
+    prelude::{Bytes, Duration},
+    transports::{BoundedChannel, NumberedFrame, PortTable},
+    ConnectionOptions, Frame, InputPortID, OutputPortID, PortError, PortID, PortResult, PortState,
+    Transport,
+};
+use std::time::Instant;
+
+/// A transport that connects ports using bounded Crossbeam channels.
+///
+/// An output port fans out to several input ports by sending over each of
+/// their channels, and several output ports fan in to an input port by
+/// sharing its channel.
+#[derive(Debug, Default)]
+pub struct CrossbeamTransport {
+    ports: PortTable<CrossbeamChannel>,
+}
+
+/// The bounded Crossbeam channels that a [`CrossbeamTransport`] connects
+/// ports through.
+#[derive(Debug)]
+struct CrossbeamChannel;
+
+impl BoundedChannel for CrossbeamChannel {
+    type Sender = Sender<NumberedFrame>;
+    type Receiver = Receiver<NumberedFrame>;
+
+    fn bounded(capacity: usize) -> (Self::Sender, Self::Receiver) {
+        bounded(capacity)
+    }
+
+    fn try_send(sender: &Self::Sender, frame: NumberedFrame) -> PortResult<Option<NumberedFrame>> {
+        match sender.try_send(frame) {
+            Ok(()) => Ok(None),
+            Err(TrySendError::Full(frame)) => Ok(Some(frame)),
+            Err(TrySendError::Disconnected(_)) => Err(PortError::Closed),
+        }
+    }
+
+    fn try_recv(receiver: &Self::Receiver) -> PortResult<Option<NumberedFrame>> {
+        match receiver.try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(PortError::Disconnected),
+        }
+    }
+
+    fn send_until(
+        sender: &Self::Sender,
+        frame: NumberedFrame,
+        closed: &Self::Receiver,
+        deadline: Option<Instant>,
+    ) -> PortResult<()> {
+        let mut select = Select::new();
+        let send_index = select.send(sender);
+        select.recv(closed);
+        let operation = match deadline {
+            None => select.select(),
+            Some(deadline) => select
+                .select_deadline(deadline)
+                .map_err(|_| PortError::Timeout)?,
+        };
+        if operation.index() == send_index {
+            operation.send(sender, frame).map_err(|_| PortError::Closed)
+        } else {
+            let _ = operation.recv(closed);
+            Err(PortError::Closed)
+        }
+    }
+
+    fn select_recv(
+        channels: &[(&Self::Receiver, &Self::Receiver)],
+        deadline: Option<Instant>,
+    ) -> PortResult<(usize, Option<NumberedFrame>)> {
+        let mut select = Select::new();
+        for (receiver, closed) in channels {
+            select.recv(receiver);
+            select.recv(closed);
+        }
+        let operation = match deadline {
+            None => select.select(),
+            Some(deadline) => select
+                .select_deadline(deadline)
+                .map_err(|_| PortError::Timeout)?,
+        };
+        let position = operation.index() / 2;
+        let (receiver, closed) = channels[position];
+        let frame = if operation.index() % 2 == 0 {
+            operation.recv(receiver).ok()
+        } else {
+            let _ = operation.recv(closed);
+            None
+        };
+        Ok((position, frame))
+    }
+}
+
+impl CrossbeamTransport {
+    pub fn new() -> Self {
+        Self {
+            ports: PortTable::new(),
+        }
+    }
+}
+
+impl Transport for CrossbeamTransport {
+    fn state(&self, port: PortID) -> PortResult<PortState> {
+        self.ports.state(port)
+    }
+
+    fn open_input(&self) -> PortResult<InputPortID> {
+        self.ports.open_input()
+    }
+
+    fn open_output(&self) -> PortResult<OutputPortID> {
+        self.ports.open_output()
+    }
+
+    fn close_input(&self, input: InputPortID) -> PortResult<bool> {
+        self.ports.close_input(input)
+    }
+
+    fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
+        self.ports.close_output(output)
+    }
+
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        self.ports.connect_with(source, target, options)
+    }
+
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        self.ports.wait_for(port, timeout)
+    }
+
+    fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
+        self.ports.send(output, Frame::Data(message), None) // blocking
+    }
+
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        timeout: Duration,
+    ) -> PortResult<()> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.ports.send(output, Frame::Data(message), deadline)
+    }
+
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()> {
+        if !frame.is_control() {
+            return Err(PortError::Invalid(PortID::Output(output))); // TODO: better errors
+        }
+        self.ports.send(output, frame, None)
+    }
+
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.ports.recv(input, None) // blocking
+    }
+
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>> {
+        self.ports.recv(input, Instant::now().checked_add(timeout))
+    }
+
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.ports.try_recv(input)
+    }
+
+    /// Receives a frame from whichever of the given input ports has one
+    /// available first, selecting over all of their channels at once.
+    fn recv_any(&self, inputs: &[InputPortID]) -> PortResult<Option<(usize, Option<Frame>)>> {
+        self.ports.recv_any(inputs)
+    }
+
+    fn close_all(&self) -> PortResult<()> {
+        self.ports.close_all();
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow_core::{
+    runtimes::StdRuntime, ConnectionOptions, FanOut, Frame, OverflowPolicy, PortError, PortState,
+    System, Transport,
+};
+use protoflow_crossbeam::CrossbeamTransport;
+use std::{thread, time::Duration};
+
+#[test]
+fn send_and_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(CrossbeamTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect(&output, &input));
+    let sender = thread::spawn(move || {
+        for message in 0..100 {
+            output.send(&message).unwrap();
+        }
+        output.close().unwrap();
+    });
+    for message in 0..100 {
+        assert_eq!(input.recv(), Ok(Some(message)));
+    }
+    assert_eq!(input.recv(), Ok(None));
+    sender.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn try_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(CrossbeamTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    system.connect(&output, &input);
+    assert_eq!(input.try_recv(), Ok(None));
+    output.send(&42).unwrap();
+    assert_eq!(input.try_recv(), Ok(Some(42)));
+    output.close().unwrap();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    Ok(())
+}
+
+#[test]
+fn timeouts() -> Result<(), ()> {
+    let runtime = StdRuntime::new(CrossbeamTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let timeout = Duration::from_millis(10);
+    assert_eq!(input.recv_timeout(timeout), Err(PortError::Timeout));
+    assert_eq!(output.send_timeout(&1, timeout), Ok(()));
+    assert_eq!(output.send_timeout(&2, timeout), Err(PortError::Timeout));
+    Ok(())
+}
+
+#[test]
+fn overflow() -> Result<(), ()> {
+    let runtime = StdRuntime::new(CrossbeamTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    let options = ConnectionOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropOldest);
+    system.connect_with(&output, &input, options);
+    for message in 1..=4 {
+        assert_eq!(output.send(&message), Ok(()));
+    }
+    assert_eq!(input.try_recv(), Ok(Some(3)));
+    assert_eq!(input.try_recv(), Ok(Some(4)));
+    Ok(())
+}
+
+#[test]
+fn overflow_keeps_control_frames() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new()
+        .with_capacity(1)
+        .with_overflow(OverflowPolicy::DropOldest);
+    transport.connect_with(output, input, options).unwrap();
+    let error = Frame::Error("failed".into());
+    // Evicted by the next message:
+    transport.send_control(output, error.clone()).unwrap();
+    transport.send(output, "1".into()).unwrap();
+    // Sent while the channel is full:
+    transport.send_control(output, Frame::Flush).unwrap();
+    transport.send(output, "2".into()).unwrap();
+    assert_eq!(transport.try_recv(input), Ok(Some(error)));
+    assert_eq!(transport.try_recv(input), Ok(Some(Frame::Flush)));
+    assert_eq!(transport.try_recv(input), Ok(Some(Frame::Data("2".into()))));
+    assert_eq!(transport.try_recv(input), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn close_input_wakes_up_recv() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    transport.connect(output, input).unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            transport.close_input(input).unwrap();
+        });
+        assert_eq!(transport.recv(input), Ok(None));
+    });
+    Ok(())
+}
+
+#[test]
+fn recv_any() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let (output1, input1) = (
+        transport.open_output().unwrap(),
+        transport.open_input().unwrap(),
+    );
+    let (output2, input2) = (
+        transport.open_output().unwrap(),
+        transport.open_input().unwrap(),
+    );
+    transport.connect(output1, input1).unwrap();
+    transport.connect(output2, input2).unwrap();
+    transport.send(output2, "hello".into()).unwrap();
+    assert_eq!(
+        transport.recv_any(&[input1, input2]),
+        Ok(Some((1, Some(Frame::Data("hello".into())))))
+    );
+    transport.close_output(output1).unwrap();
+    assert_eq!(
+        transport.recv_any(&[input1, input2]),
+        Ok(Some((0, Some(Frame::Eos))))
+    );
+    assert_eq!(transport.state(input1.into()), Ok(PortState::Open));
+    Ok(())
+}
+
+#[test]
+fn close_all() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    transport.connect(output, input).unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            transport.close_all().unwrap();
+        });
+        assert_eq!(transport.recv(input), Err(PortError::Closed));
+    });
+    assert_eq!(transport.recv(input), Err(PortError::Closed));
+    // Ports can still be opened and connected afterwards:
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    assert_eq!(transport.connect(output, input), Ok(true));
+    transport.send(output, "hello".into()).unwrap();
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data("hello".into()))));
+    Ok(())
+}
+
+#[test]
+fn fan_out() -> Result<(), ()> {
+    for fanout in [FanOut::Broadcast, FanOut::RoundRobin] {
+        let transport = CrossbeamTransport::new();
+        let output = transport.open_output().unwrap();
+        let inputs = [
+            transport.open_input().unwrap(),
+            transport.open_input().unwrap(),
+        ];
+        let options = ConnectionOptions::new()
+            .with_capacity(2)
+            .with_fanout(fanout);
+        for input in inputs {
+            assert_eq!(transport.connect_with(output, input, options), Ok(true));
+        }
+        assert_eq!(
+            transport.state(output.into()),
+            Ok(PortState::FannedOut(fanout, 2))
+        );
+        transport.send(output, "1".into()).unwrap();
+        transport.send(output, "2".into()).unwrap();
+        transport.close_output(output).unwrap();
+        let received: Vec<_> = inputs
+            .iter()
+            .map(|&input| {
+                let mut frames = Vec::new();
+                while let Some(frame) = transport.recv(input).unwrap() {
+                    if frame == Frame::Eos {
+                        break;
+                    }
+                    frames.push(frame);
+                }
+                frames
+            })
+            .collect();
+        let (one, two) = (Frame::Data("1".into()), Frame::Data("2".into()));
+        match fanout {
+            FanOut::Broadcast => assert_eq!(received, [[one.clone(), two.clone()], [one, two]]),
+            FanOut::RoundRobin => assert_eq!(received, [[one], [two]]),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn fan_in() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(2);
+    for output in outputs {
+        assert_eq!(transport.connect_with(output, input, options), Ok(true));
+    }
+    assert_eq!(transport.state(input.into()), Ok(PortState::FannedIn(2)));
+    transport.send(outputs[0], "1".into()).unwrap();
+    transport.close_output(outputs[0]).unwrap();
+    assert_eq!(
+        transport.state(input.into()),
+        Ok(PortState::Connected(outputs[1].into()))
+    );
+    transport.send(outputs[1], "2".into()).unwrap();
+    transport.close_output(outputs[1]).unwrap();
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data("1".into()))));
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data("2".into()))));
+    // The input only receives the EOS once all of its outputs are closed:
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Eos)));
+    assert_eq!(transport.state(input.into()), Ok(PortState::Open));
+    assert_eq!(
+        transport.recv_timeout(input, Duration::from_secs(10)),
+        Ok(None)
+    );
+    Ok(())
+}
+
+#[test]
+fn fan_in_with_conflicting_options() -> Result<(), ()> {
+    let transport = CrossbeamTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(4);
+    assert_eq!(transport.connect_with(outputs[0], input, options), Ok(true));
+    assert!(transport
+        .connect_with(outputs[1], input, options.with_capacity(8))
+        .is_err());
+    assert_eq!(transport.state(outputs[1].into()), Ok(PortState::Open));
+    Ok(())
+}
@@ -14,4 +14,7 @@ fn define_function_block() {
             Ok(input)
         }
     }
+
+    // Check that the block is constructible:
+    let _: fn(InputPort<i64>, OutputPort<i64>) -> Echo = Echo;
 }
@@ -0,0 +1,242 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use core::pin::pin;
+use flume::{bounded, Receiver, Selector, Sender, TryRecvError, TrySendError};
+use protoflow_core::{
+    prelude::{
+        futures::future::{ready, select, BoxFuture, Either},
+        Box, Bytes, Duration,
+    },
+    transports::{BoundedChannel, NumberedFrame, PortTable, Sending},
+    ConnectionOptions, Frame, InputPortID, OutputPortID, PortError, PortID, PortResult, PortState,
+    Transport,
+};
+use std::time::Instant;
+
+/// A transport that connects ports using bounded Flume channels.
+///
+/// The channels can be used both synchronously and asynchronously, so that
+/// blocks executed by a `StdRuntime` can share the transport with
+/// asynchronous blocks executed by a `TokioRuntime`.
+///
+/// An output port fans out to several input ports by sending over each of
+/// their channels, and several output ports fan in to an input port by
+/// sharing its channel.
+#[derive(Debug, Default)]
+pub struct FlumeTransport {
+    ports: PortTable<FlumeChannel>,
+}
+
+/// The bounded Flume channels that a [`FlumeTransport`] connects ports
+/// through.
+#[derive(Debug)]
+struct FlumeChannel;
+
+impl BoundedChannel for FlumeChannel {
+    type Sender = Sender<NumberedFrame>;
+    type Receiver = Receiver<NumberedFrame>;
+
+    fn bounded(capacity: usize) -> (Self::Sender, Self::Receiver) {
+        bounded(capacity)
+    }
+
+    fn try_send(sender: &Self::Sender, frame: NumberedFrame) -> PortResult<Option<NumberedFrame>> {
+        match sender.try_send(frame) {
+            Ok(()) => Ok(None),
+            Err(TrySendError::Full(frame)) => Ok(Some(frame)),
+            Err(TrySendError::Disconnected(_)) => Err(PortError::Closed),
+        }
+    }
+
+    fn try_recv(receiver: &Self::Receiver) -> PortResult<Option<NumberedFrame>> {
+        match receiver.try_recv() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(PortError::Disconnected),
+        }
+    }
+
+    fn send_until(
+        sender: &Self::Sender,
+        frame: NumberedFrame,
+        closed: &Self::Receiver,
+        deadline: Option<Instant>,
+    ) -> PortResult<()> {
+        let selector = Selector::new()
+            .send(sender, frame, |result| {
+                result.map_err(|_| PortError::Closed)
+            })
+            .recv(closed, |_| Err(PortError::Closed));
+        match deadline {
+            None => selector.wait(),
+            Some(deadline) => selector
+                .wait_deadline(deadline)
+                .unwrap_or(Err(PortError::Timeout)),
+        }
+    }
+
+    fn select_recv(
+        channels: &[(&Self::Receiver, &Self::Receiver)],
+        deadline: Option<Instant>,
+    ) -> PortResult<(usize, Option<NumberedFrame>)> {
+        let mut selector = Selector::new();
+        for (position, (receiver, closed)) in channels.iter().enumerate() {
+            selector = selector
+                .recv(receiver, move |frame| (position, frame.ok()))
+                .recv(closed, move |_| (position, None));
+        }
+        match deadline {
+            None => Ok(selector.wait()),
+            Some(deadline) => selector
+                .wait_deadline(deadline)
+                .map_err(|_| PortError::Timeout),
+        }
+    }
+}
+
+impl FlumeTransport {
+    pub fn new() -> Self {
+        Self {
+            ports: PortTable::new(),
+        }
+    }
+}
+
+impl Transport for FlumeTransport {
+    fn state(&self, port: PortID) -> PortResult<PortState> {
+        self.ports.state(port)
+    }
+
+    fn open_input(&self) -> PortResult<InputPortID> {
+        self.ports.open_input()
+    }
+
+    fn open_output(&self) -> PortResult<OutputPortID> {
+        self.ports.open_output()
+    }
+
+    fn close_input(&self, input: InputPortID) -> PortResult<bool> {
+        self.ports.close_input(input)
+    }
+
+    fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
+        self.ports.close_output(output)
+    }
+
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        self.ports.connect_with(source, target, options)
+    }
+
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        self.ports.wait_for(port, timeout)
+    }
+
+    fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
+        self.ports.send(output, Frame::Data(message), None) // blocking
+    }
+
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        timeout: Duration,
+    ) -> PortResult<()> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.ports.send(output, Frame::Data(message), deadline)
+    }
+
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()> {
+        if !frame.is_control() {
+            return Err(PortError::Invalid(PortID::Output(output))); // TODO: better errors
+        }
+        self.ports.send(output, frame, None)
+    }
+
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.ports.recv(input, None) // blocking
+    }
+
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>> {
+        self.ports.recv(input, Instant::now().checked_add(timeout))
+    }
+
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.ports.try_recv(input)
+    }
+
+    /// Receives a frame from whichever of the given input ports has one
+    /// available first, selecting over all of their channels at once.
+    fn recv_any(&self, inputs: &[InputPortID]) -> PortResult<Option<(usize, Option<Frame>)>> {
+        self.ports.recv_any(inputs)
+    }
+
+    fn close_all(&self) -> PortResult<()> {
+        self.ports.close_all();
+        Ok(())
+    }
+
+    /// Sends a message without blocking the current task.
+    ///
+    /// Cancelling the send before it completes doesn't send the message,
+    /// except to those inputs of a broadcast fan-out already sent to.
+    fn send_async(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+    ) -> Option<BoxFuture<'_, PortResult<()>>> {
+        let frame = Frame::Data(message);
+        let sendings = match self.ports.sending(output, &frame) {
+            Ok(sendings) => sendings,
+            Err(error) => return Some(Box::pin(ready(Err(error)))),
+        };
+        Some(Box::pin(async move {
+            let mut result = Ok(());
+            for sending in sendings {
+                let sent = send_async(&sending, frame.clone()).await;
+                result = result.and(sent);
+            }
+            result
+        }))
+    }
+
+    fn recv_async(&self, input: InputPortID) -> Option<BoxFuture<'_, PortResult<Option<Frame>>>> {
+        let receiving = match self.ports.receiving(input, Some(Instant::now())) {
+            Ok(Some(receiving)) => receiving,
+            Ok(None) => return Some(Box::pin(ready(Ok(None)))), // EOS (port closed)
+            Err(PortError::Timeout) => return None, // wait for the port to get connected
+            Err(error) => return Some(Box::pin(ready(Err(error)))),
+        };
+        Some(Box::pin(async move {
+            if let Some(frame) = receiving.try_next() {
+                return Ok(Some(frame));
+            }
+            let recv = pin!(receiving.receiver().recv_async());
+            let closed = pin!(receiving.closed().recv_async());
+            let frame = match select(recv, closed).await {
+                Either::Left((frame, _)) => frame.ok(),
+                Either::Right(_) => None,
+            };
+            self.ports.received(input, receiving.stash(), frame)
+        }))
+    }
+}
+
+/// Sends a frame over a channel without blocking the current task.
+async fn send_async(sending: &Sending<FlumeChannel>, frame: Frame) -> PortResult<()> {
+    let Some(frame) = sending.try_send(frame)? else {
+        return Ok(());
+    };
+    let send = pin!(sending.sender().send_async(frame));
+    let closed = pin!(sending.closed().recv_async());
+    match select(send, closed).await {
+        Either::Left((result, _)) => result.map_err(|_| PortError::Closed),
+        Either::Right(_) => Err(PortError::Closed),
+    }
+}
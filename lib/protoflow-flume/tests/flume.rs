@@ -0,0 +1,276 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow_core::{
+    runtimes::StdRuntime, ConnectionOptions, FanOut, Frame, OverflowPolicy, PortError, PortState,
+    System, Transport,
+};
+use protoflow_flume::FlumeTransport;
+use std::{thread, time::Duration};
+
+#[test]
+fn send_and_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect(&output, &input));
+    let sender = thread::spawn(move || {
+        for message in 0..100 {
+            output.send(&message).unwrap();
+        }
+        output.close().unwrap();
+    });
+    for message in 0..100 {
+        assert_eq!(input.recv(), Ok(Some(message)));
+    }
+    assert_eq!(input.recv(), Ok(None));
+    sender.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn send_and_recv_async() -> Result<(), ()> {
+    let tokio = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (mut sync_output, async_input) = (system.output::<i32>(), system.input::<i32>());
+    let (async_output, sync_input) = (system.output::<i32>(), system.input::<i32>());
+    system.connect(&sync_output, &async_input);
+    system.connect(&async_output, &sync_input);
+    let echo = tokio.spawn(async move {
+        while let Some(message) = async_input.recv_async().await? {
+            async_output.send_async(&message).await?;
+        }
+        Ok::<_, PortError>(())
+    });
+    for message in 0..10 {
+        sync_output.send(&message).unwrap();
+        assert_eq!(sync_input.recv(), Ok(Some(message)));
+    }
+    sync_output.close().unwrap();
+    assert_eq!(tokio.block_on(echo).unwrap(), Ok(()));
+    Ok(())
+}
+
+#[test]
+fn try_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    system.connect(&output, &input);
+    assert_eq!(input.try_recv(), Ok(None));
+    output.send(&42).unwrap();
+    assert_eq!(input.try_recv(), Ok(Some(42)));
+    output.close().unwrap();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    Ok(())
+}
+
+#[test]
+fn timeouts() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let timeout = Duration::from_millis(10);
+    assert_eq!(input.recv_timeout(timeout), Err(PortError::Timeout));
+    assert_eq!(output.send_timeout(&1, timeout), Ok(()));
+    assert_eq!(output.send_timeout(&2, timeout), Err(PortError::Timeout));
+    Ok(())
+}
+
+#[test]
+fn overflow() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    let options = ConnectionOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropNewest);
+    system.connect_with(&output, &input, options);
+    for message in 1..=4 {
+        assert_eq!(output.send(&message), Ok(()));
+    }
+    assert_eq!(input.try_recv(), Ok(Some(1)));
+    assert_eq!(input.try_recv(), Ok(Some(2)));
+    assert_eq!(input.try_recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn shutdown_wakes_up_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let receiver = thread::spawn(move || input.recv());
+    thread::sleep(Duration::from_millis(10));
+    runtime.shutdown().unwrap();
+    assert_eq!(receiver.join().unwrap(), Err(PortError::Closed));
+    Ok(())
+}
+
+#[test]
+fn shutdown_wakes_up_send() -> Result<(), ()> {
+    let runtime = StdRuntime::new(FlumeTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect_with(&output, &input, ConnectionOptions::new().with_capacity(1));
+    output.send(&1).unwrap();
+    let sender = thread::spawn(move || output.send(&2)); // blocks while full
+    thread::sleep(Duration::from_millis(10));
+    runtime.shutdown().unwrap();
+    assert_eq!(sender.join().unwrap(), Err(PortError::Closed));
+    assert_eq!(input.recv(), Err(PortError::Closed));
+    Ok(())
+}
+
+#[test]
+fn close_input_wakes_up_send() -> Result<(), ()> {
+    let transport = FlumeTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(1);
+    transport.connect_with(output, input, options).unwrap();
+    transport.send(output, "1".into()).unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            transport.close_input(input).unwrap();
+        });
+        assert_eq!(transport.send(output, "2".into()), Err(PortError::Closed));
+    });
+    Ok(())
+}
+
+#[test]
+fn recv_any() -> Result<(), ()> {
+    let transport = FlumeTransport::new();
+    let (output1, input1) = (
+        transport.open_output().unwrap(),
+        transport.open_input().unwrap(),
+    );
+    let (output2, input2) = (
+        transport.open_output().unwrap(),
+        transport.open_input().unwrap(),
+    );
+    transport.connect(output1, input1).unwrap();
+    transport.connect(output2, input2).unwrap();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(10));
+            transport.send(output2, "hello".into()).unwrap();
+        });
+        assert_eq!(
+            transport.recv_any(&[input1, input2]),
+            Ok(Some((1, Some(Frame::Data("hello".into())))))
+        );
+    });
+    transport.close_output(output1).unwrap();
+    assert_eq!(
+        transport.recv_any(&[input1, input2]),
+        Ok(Some((0, Some(Frame::Eos))))
+    );
+    assert_eq!(transport.state(input1.into()), Ok(PortState::Open));
+    Ok(())
+}
+
+#[test]
+fn fan_out() -> Result<(), ()> {
+    for fanout in [FanOut::Broadcast, FanOut::RoundRobin] {
+        let transport = FlumeTransport::new();
+        let output = transport.open_output().unwrap();
+        let inputs = [
+            transport.open_input().unwrap(),
+            transport.open_input().unwrap(),
+        ];
+        let options = ConnectionOptions::new()
+            .with_capacity(2)
+            .with_fanout(fanout);
+        for input in inputs {
+            assert_eq!(transport.connect_with(output, input, options), Ok(true));
+        }
+        assert_eq!(
+            transport.state(output.into()),
+            Ok(PortState::FannedOut(fanout, 2))
+        );
+        transport.send(output, "1".into()).unwrap();
+        transport.send(output, "2".into()).unwrap();
+        transport.close_output(output).unwrap();
+        let received: Vec<_> = inputs
+            .iter()
+            .map(|&input| {
+                let mut frames = Vec::new();
+                while let Some(frame) = transport.recv(input).unwrap() {
+                    if frame == Frame::Eos {
+                        break;
+                    }
+                    frames.push(frame);
+                }
+                frames
+            })
+            .collect();
+        let (one, two) = (Frame::Data("1".into()), Frame::Data("2".into()));
+        match fanout {
+            FanOut::Broadcast => assert_eq!(received, [[one.clone(), two.clone()], [one, two]]),
+            FanOut::RoundRobin => assert_eq!(received, [[one], [two]]),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn fan_in() -> Result<(), ()> {
+    let transport = FlumeTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(2);
+    for output in outputs {
+        assert_eq!(transport.connect_with(output, input, options), Ok(true));
+    }
+    assert_eq!(transport.state(input.into()), Ok(PortState::FannedIn(2)));
+    transport.send(outputs[0], "1".into()).unwrap();
+    transport.close_output(outputs[0]).unwrap();
+    assert_eq!(
+        transport.state(input.into()),
+        Ok(PortState::Connected(outputs[1].into()))
+    );
+    transport.send(outputs[1], "2".into()).unwrap();
+    transport.close_output(outputs[1]).unwrap();
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data("1".into()))));
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data("2".into()))));
+    // The input only receives the EOS once all of its outputs are closed:
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Eos)));
+    assert_eq!(transport.state(input.into()), Ok(PortState::Open));
+    assert_eq!(
+        transport.recv_timeout(input, Duration::from_secs(10)),
+        Ok(None)
+    );
+    Ok(())
+}
+
+#[test]
+fn fan_in_with_conflicting_options() -> Result<(), ()> {
+    let transport = FlumeTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(4);
+    assert_eq!(transport.connect_with(outputs[0], input, options), Ok(true));
+    assert!(transport
+        .connect_with(outputs[1], input, options.with_capacity(8))
+        .is_err());
+    assert_eq!(transport.state(outputs[1].into()), Ok(PortState::Open));
+    Ok(())
+}
@@ -18,6 +18,8 @@ pub enum AnalysisError {
     InvalidImport(QualifiedName),
     /// Unknown name: `{0}`.
     UnknownName(QualifiedName),
+    /// Invalid route: {0}.
+    InvalidRoute(String),
     /// Other error: `{0}`.
     Other(String),
 }
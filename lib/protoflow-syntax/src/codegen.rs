@@ -3,7 +3,8 @@
 extern crate std;
 
 use crate::{
-    prelude::{fmt, String, Vec},
+    parse_routes,
+    prelude::{fmt, String, ToString, Vec},
     AnalysisError,
 };
 use error_stack::Report;
@@ -67,7 +68,7 @@ impl TryFrom<&ParsedMember> for Code {
                 }))
             }
             BlockUsage(usage) => Code::try_from(usage),
-            AttributeUsage(_usage) => Ok(Code::default()), // TODO
+            AttributeUsage(_usage) => Ok(Code::default()), // TODO: needs attribute values
             PortUsage(_usage) => Ok(Code::default()),      // TODO
         }
     }
@@ -110,8 +111,24 @@ impl TryFrom<&ParsedBlock> for Code {
                 .map(|s| s.as_str())
                 .unwrap_or_else(|| "block")
         );
-        Ok(Self(quote! {
-            let #name = s.block();
-        }))
+        Ok(Self(
+            match usage.definition.as_ref().and_then(|name| name.last()) {
+                Some("Route") => {
+                    let routes = parse_routes(usage).map_err(Report::new)?;
+                    let routes = routes.iter().map(|(route, predicate)| {
+                        let predicate = predicate.to_string();
+                        quote! {
+                            (#route.into(), #predicate.parse().unwrap())
+                        }
+                    });
+                    quote! {
+                        let #name = s.route::<String>(vec![#(#routes),*]);
+                    }
+                }
+                _ => quote! {
+                    let #name = s.block();
+                },
+            },
+        ))
     }
 }
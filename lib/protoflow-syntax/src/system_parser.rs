@@ -4,14 +4,14 @@
 extern crate std;
 
 use crate::{
-    prelude::{vec, BTreeSet, Vec},
+    prelude::{format, vec, BTreeSet, String, ToString, Vec},
     AnalysisError, AnalysisResult,
 };
 use error_stack::ResultExt;
-use protoflow_blocks::BLOCKS;
+use protoflow_blocks::{RoutePredicate, BLOCKS};
 use sysml_model::QualifiedName;
 
-pub use sysml_parser::{ParseError, ParsedBlock, ParsedMember, ParsedModel};
+pub use sysml_parser::{ParseError, ParsedAttribute, ParsedBlock, ParsedMember, ParsedModel};
 
 #[derive(Debug, Default)]
 pub struct SystemParser {
@@ -111,8 +111,46 @@ impl SystemParser {
 
     pub fn check_block_usage(
         &mut self,
-        _member: &ParsedBlock,
+        member: &ParsedBlock,
     ) -> core::result::Result<(), AnalysisError> {
-        Ok(()) // TODO
+        match member.definition.as_ref().and_then(|name| name.last()) {
+            Some("Route") => parse_routes(member).map(|_| ()),
+            _ => Ok(()), // TODO
+        }
     }
 }
+
+/// Parses the routes of a `Route` block usage from its attributes, each
+/// named after a route and typed by the route's predicate, as in
+/// `attribute errors : 'value matches "^ERROR"';`.
+pub fn parse_routes(
+    block: &ParsedBlock,
+) -> core::result::Result<Vec<(String, RoutePredicate)>, AnalysisError> {
+    block
+        .members
+        .iter()
+        .map(|member| match member {
+            ParsedMember::AttributeUsage(ParsedAttribute {
+                name: Some(name),
+                definition: Some(predicate),
+                ..
+            }) => match predicate.to_vec().as_slice() {
+                [predicate] => predicate
+                    .parse()
+                    .map(|predicate| (name.clone(), predicate))
+                    .map_err(|error| AnalysisError::InvalidRoute(format!("{}: {}", name, error))),
+                _ => Err(AnalysisError::InvalidRoute(format!(
+                    "{}: the predicate must be quoted",
+                    name
+                ))),
+            },
+            ParsedMember::AttributeUsage(attribute) => Err(AnalysisError::InvalidRoute(format!(
+                "{}: a route needs a name and a predicate",
+                attribute.name.as_deref().unwrap_or_default()
+            ))),
+            _ => Err(AnalysisError::InvalidRoute(
+                "only attributes configure routes".to_string(),
+            )),
+        })
+        .collect()
+}
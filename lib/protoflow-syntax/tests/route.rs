@@ -0,0 +1,47 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow_syntax::{AnalysisError, Code, SystemParser};
+
+#[test]
+fn route_from_sysml() {
+    let mut parser = SystemParser::default()
+        .from_string(
+            r#"
+            package Logs {
+                import Protoflow::*;
+                block router : Protoflow::Route {
+                    attribute errors : 'value matches "^ERROR"';
+                    attribute warnings : 'value matches "^WARN"';
+                }
+            }
+            "#,
+        )
+        .unwrap();
+    let model = parser.check().unwrap();
+    let code = Code::try_from(model).unwrap().unparse();
+    let code: String = code.split_whitespace().collect();
+    assert!(code.contains("letrouter=s.route::<String"));
+    assert!(code.contains(r#"("errors".into(),"valuematches\"^ERROR\"".parse().unwrap())"#));
+    assert!(code.contains(r#"("warnings".into(),"valuematches\"^WARN\"".parse().unwrap())"#));
+}
+
+#[test]
+fn route_with_invalid_predicate() {
+    let mut parser = SystemParser::default()
+        .from_string(
+            r#"
+            package Logs {
+                import Protoflow::*;
+                block router : Protoflow::Route {
+                    attribute errors : 'value matches';
+                }
+            }
+            "#,
+        )
+        .unwrap();
+    let error = parser.check().unwrap_err();
+    assert!(matches!(
+        error.current_context(),
+        AnalysisError::InvalidRoute(_)
+    ));
+}
@@ -5,3 +5,8 @@
 
 #[doc(hidden)]
 pub use protoflow_core::prelude;
+
+#[cfg(feature = "std")]
+mod transport;
+#[cfg(feature = "std")]
+pub use transport::*;
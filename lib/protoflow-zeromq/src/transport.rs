@@ -0,0 +1,1098 @@
+// This is free and unencumbered software released into the public domain.
+
+extern crate std;
+
+use core::future::Future;
+use parking_lot::{Condvar, Mutex};
+use protoflow_core::{
+    prelude::{format, vec, Arc, Box, Bytes, Duration, String, ToString, Vec},
+    runtimes::block_in_place,
+    ConnectionOptions, FanOut, Frame, InputPort, InputPortID, Message, OutputPort, OutputPortID,
+    OverflowPolicy, Port, PortError, PortID, PortResult, PortState, Transport,
+    DEFAULT_CONNECTION_CAPACITY,
+};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock, Weak,
+    },
+    time::Instant,
+};
+use tokio::{
+    runtime::{Handle, Runtime},
+    sync::{
+        mpsc::{self, error::TryRecvError},
+        watch, Mutex as AsyncMutex,
+    },
+    time::MissedTickBehavior,
+};
+use zeromq::{
+    PubSocket, PullSocket, PushSocket, Socket, SocketRecv, SocketSend, SubSocket, ZmqMessage,
+};
+
+const DEFAULT_INPUT_PORT_COUNT: usize = 16;
+const DEFAULT_OUTPUT_PORT_COUNT: usize = 16;
+
+/// How often a connected output port sends a heartbeat, by default.
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of heartbeats in a row an output port may miss before the
+/// input port it's connected to considers it crashed.
+const HEARTBEAT_LIVENESS: u32 = 3;
+
+/// How long connecting a PUB socket waits for its subscriber to answer a
+/// probe before sending another one.
+const PROBE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Makes the default endpoints of each transport in this process unique.
+static TRANSPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The socket types used to connect ports.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ZmqPattern {
+    /// Input ports bind PULL sockets, and output ports connect PUSH
+    /// sockets. A sender blocks while the receiver is behind.
+    #[default]
+    PushPull,
+
+    /// Input ports bind SUB sockets, and output ports connect PUB sockets.
+    /// A sender never blocks: messages are dropped, the EOS included,
+    /// while the receiver is behind, or before the receiver's subscription
+    /// reaches a sender connected from another process.
+    PubSub,
+}
+
+/// A transport that connects ports using ZeroMQ sockets.
+///
+/// Each input port binds a PULL or SUB socket, depending on the transport's
+/// [`ZmqPattern`], and connecting an output port to it connects a PUSH or
+/// PUB socket to its endpoint. Input ports bind `ipc://` endpoints by
+/// default, or `tcp://` endpoints with an ephemeral port if the transport
+/// was created with a `tcp://host` endpoint.
+///
+/// To distribute a system across processes, one process exposes the
+/// endpoint of an input port with [`ZmqTransport::endpoint`] and marks it
+/// as connected with [`ZmqTransport::connect_from`], while the other
+/// process connects an output port to that endpoint with
+/// [`ZmqTransport::connect_to`]. Both processes must use the same pattern
+/// and heartbeat interval.
+///
+/// Each ZeroMQ message carries one frame, encoded with [`Frame::encode`],
+/// or is empty for a heartbeat. Connected output ports send heartbeats
+/// until they are closed, and once an input port has received a message,
+/// it reports a `PortError::Disconnected` error if its output ports miss
+/// several heartbeats in a row, as when their process crashed.
+///
+/// An output port fans out to several input ports through a socket
+/// connected to each of them, and several output ports fan in to an input
+/// port by connecting to its endpoint. The input port receives the EOS once
+/// all of them have sent theirs, and only notices crashed output ports once
+/// none of them sends heartbeats anymore.
+///
+/// The capacity of the first connection to an input port sets how many
+/// frames it buffers ahead of their being received, on top of the buffers
+/// of ZeroMQ and of the operating system, and the connections fanning into
+/// it must agree with it. Only the `Block` overflow policy is supported.
+///
+/// All transports share one Tokio runtime, which drives the sockets. The
+/// blocking operations of the transport may also be called from within
+/// another Tokio runtime, such as by the blocks of a `TokioRuntime`.
+pub struct ZmqTransport {
+    /// The prefix of the endpoints bound by input ports.
+    endpoint: String,
+    pattern: ZmqPattern,
+    heartbeat_interval: Duration,
+    state: Mutex<ZmqTransportState>,
+    /// Notified whenever a port is connected or closed.
+    state_changed: Condvar,
+}
+
+#[derive(Default)]
+struct ZmqTransportState {
+    outputs: Vec<ZmqOutput>,
+    inputs: Vec<ZmqInput>,
+    /// Whether all ports were closed at once, on a shutdown.
+    is_terminated: bool,
+}
+
+struct ZmqInput {
+    state: PortState,
+    /// Whether this is an input port of another process.
+    is_remote: bool,
+    endpoint: String,
+    /// The bound socket, until the port gets connected for the first time.
+    socket: Option<BoundSocket>,
+    /// The receiving end of the bound socket, once the port got connected.
+    receiver: Option<ZmqReceiver>,
+    /// The output ports connected to this port, including closed ones whose
+    /// EOS it has yet to receive.
+    sources: Vec<OutputPortID>,
+    /// Whether the port has received the EOS of all of its output ports,
+    /// without getting connected again since.
+    drained: bool,
+    /// Set when the port is closed, waking up any blocked operations.
+    closed: watch::Sender<bool>,
+}
+
+struct ZmqOutput {
+    state: PortState,
+    /// Whether this is an output port of another process.
+    is_remote: bool,
+    /// The input ports connected to this port, along with the socket
+    /// connected to each of them, unless this is a remote port.
+    targets: Vec<(InputPortID, Option<OutputSocket>)>,
+    fanout: FanOut,
+    /// The position of the next input port, for round-robin fan-out.
+    next: usize,
+}
+
+/// A socket connected by an output port, shared with the background task
+/// sending its heartbeats, and taken out once the EOS is sent.
+type OutputSocket = Arc<AsyncMutex<Option<Box<dyn SocketSend + Send>>>>;
+
+/// The socket bound by an input port.
+enum BoundSocket {
+    Pull(PullSocket),
+    Sub(SubSocket),
+}
+
+/// The receiving end of the socket bound by an input port.
+///
+/// A background task receives the messages from the socket, so that no
+/// message is lost when a receive operation gives up before completing.
+#[derive(Clone)]
+struct ZmqReceiver {
+    /// The frames received by the background task, or the errors it ran
+    /// into.
+    frames: Arc<AsyncMutex<mpsc::Receiver<PortResult<Frame>>>>,
+    /// The number of frames buffered by `frames`.
+    capacity: usize,
+    /// The number of connected output ports whose EOS the background task
+    /// has yet to receive, which only passes on the last one.
+    pending_eos: Arc<AtomicUsize>,
+    /// The number of heartbeats received.
+    heartbeats: watch::Receiver<u64>,
+}
+
+impl ZmqTransport {
+    /// Creates a transport whose input ports bind `ipc://` endpoints in the
+    /// temporary directory.
+    pub fn new() -> Self {
+        let endpoint = format!(
+            "ipc://{}/protoflow-{}-{}",
+            std::env::temp_dir().display(),
+            std::process::id(),
+            TRANSPORT_COUNT.fetch_add(1, Ordering::SeqCst),
+        );
+        Self::with_endpoint(&endpoint)
+    }
+
+    /// Creates a transport whose input ports bind endpoints derived from
+    /// the given one, such as `ipc:///tmp/myflow` or `tcp://127.0.0.1`.
+    pub fn with_endpoint(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            pattern: ZmqPattern::default(),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            state: Mutex::new(ZmqTransportState::new()),
+            state_changed: Condvar::new(),
+        }
+    }
+
+    /// Sets the socket types used to connect the ports opened from now on.
+    pub fn with_pattern(mut self, pattern: ZmqPattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Sets how often connected output ports send a heartbeat, and so how
+    /// soon input ports opened from now on notice a crashed output port.
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    /// Returns the endpoint bound by an input port, for output ports in
+    /// other processes to connect to.
+    pub fn endpoint<M: Message>(&self, input: &InputPort<M>) -> PortResult<String> {
+        let input = input_id(input)?;
+        let state = self.state.lock();
+        match state.inputs.get(input.index()) {
+            None => Err(PortError::Invalid(input.into())),
+            Some(input) => Ok(input.endpoint.clone()),
+        }
+    }
+
+    /// Connects an output port to an input port of another process, bound
+    /// at the given endpoint.
+    pub fn connect_to<M: Message>(
+        &self,
+        source: &OutputPort<M>,
+        endpoint: &str,
+    ) -> PortResult<bool> {
+        let source = output_id(source)?;
+        let target = {
+            let mut state = self.state.lock();
+            state.inputs.push(ZmqInput {
+                state: PortState::Open,
+                is_remote: true,
+                endpoint: endpoint.to_string(),
+                socket: None,
+                receiver: None,
+                sources: Vec::new(),
+                drained: false,
+                closed: watch::Sender::new(false),
+            });
+            InputPortID::try_from(-(state.inputs.len() as isize))
+                .map_err(|s| PortError::Other(s.to_string()))?
+        };
+        self.connect(source, target)
+    }
+
+    /// Marks an input port as connected to an output port of another
+    /// process, which connects to the endpoint of the input port.
+    ///
+    /// This may be called once for each of several output ports fanning in
+    /// to the input port. If the input port wasn't connected yet, it gets
+    /// the default connection capacity.
+    pub fn connect_from<M: Message>(&self, target: &InputPort<M>) -> PortResult<bool> {
+        let target = input_id(target)?;
+        let result = {
+            let mut state = self.state.lock();
+            match state.inputs.get(target.index()) {
+                Some(input) if !input.is_remote && !input.state.is_closed() => {
+                    state.outputs.push(ZmqOutput {
+                        state: PortState::Connected(target.into()),
+                        is_remote: true,
+                        targets: vec![(target, None)],
+                        fanout: FanOut::default(),
+                        next: 0,
+                    });
+                    let source = OutputPortID::try_from(state.outputs.len() as isize)
+                        .map_err(|s| PortError::Other(s.to_string()))?;
+                    let liveness = self.heartbeat_interval * HEARTBEAT_LIVENESS;
+                    let input = &mut state.inputs[target.index()];
+                    input.connected(source, DEFAULT_CONNECTION_CAPACITY, liveness);
+                    Ok(true)
+                }
+                _ => Err(PortError::Invalid(target.into())),
+            }
+        };
+        self.state_changed.notify_all();
+        result
+    }
+}
+
+impl Default for ZmqTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ZmqTransport {
+    fn drop(&mut self) {
+        let state = core::mem::take(&mut *self.state.lock());
+        let _guard = tokio().enter(); // the sockets shut down asynchronously
+        for input in state.inputs {
+            input.closed.send_replace(true); // stop the background task
+            if let (false, Some(path)) = (input.is_remote, input.endpoint.strip_prefix("ipc://")) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+impl ZmqTransportState {
+    pub fn new() -> Self {
+        // Avoid reallocations by pre-allocating an ample default capacity.
+        Self {
+            outputs: Vec::with_capacity(DEFAULT_OUTPUT_PORT_COUNT),
+            inputs: Vec::with_capacity(DEFAULT_INPUT_PORT_COUNT),
+            is_terminated: false,
+        }
+    }
+
+    /// Updates the state of a connected output port after its input ports
+    /// have changed.
+    fn update_output(&mut self, output: OutputPortID) {
+        let output = &mut self.outputs[output.index()];
+        output.state = match output.targets.as_slice() {
+            [] if output.is_remote => PortState::Closed,
+            [] => PortState::Open,
+            [(input, _)] => PortState::Connected(PortID::Input(*input)),
+            targets => PortState::FannedOut(output.fanout, targets.len()),
+        };
+    }
+}
+
+impl ZmqInput {
+    /// Connects an output port to this local input port, starting to
+    /// receive the messages of its socket, with the given capacity, if it
+    /// wasn't connected yet.
+    fn connected(&mut self, source: OutputPortID, capacity: usize, liveness: Duration) {
+        if let Some(socket) = self.socket.take() {
+            self.receiver = Some(spawn_receiver(socket, &self.closed, capacity, liveness));
+        }
+        if let Some(receiver) = &self.receiver {
+            receiver.pending_eos.fetch_add(1, Ordering::SeqCst);
+        }
+        self.sources.push(source);
+        self.drained = false;
+        self.update();
+    }
+
+    /// Undoes the connection of an output port to this local input port,
+    /// after its socket failed to connect.
+    fn disconnected(&mut self, source: OutputPortID) {
+        self.sources.retain(|&output| output != source);
+        if let Some(receiver) = &self.receiver {
+            receiver.pending_eos.fetch_sub(1, Ordering::SeqCst);
+        }
+        self.update();
+    }
+
+    /// Updates the state of this port after its output ports have changed.
+    fn update(&mut self) {
+        self.state = match self.sources.as_slice() {
+            [] => PortState::Open,
+            [output] => PortState::Connected(PortID::Output(*output)),
+            outputs => PortState::FannedIn(outputs.len()),
+        };
+    }
+}
+
+impl Transport for ZmqTransport {
+    fn state(&self, port: PortID) -> PortResult<PortState> {
+        let state = self.state.lock();
+        match port {
+            PortID::Input(input) => match state.inputs.get(input.index()) {
+                None => Err(PortError::Invalid(port)),
+                Some(input) => Ok(input.state),
+            },
+            PortID::Output(output) => match state.outputs.get(output.index()) {
+                None => Err(PortError::Invalid(port)),
+                Some(output) => Ok(output.state),
+            },
+        }
+    }
+
+    fn open_input(&self) -> PortResult<InputPortID> {
+        let mut state = self.state.lock();
+        let endpoint = match self.endpoint.starts_with("tcp://") {
+            true => format!("{}:0", self.endpoint), // an ephemeral port
+            false => format!("{}-{}", self.endpoint, state.inputs.len() + 1),
+        };
+        let bound = match self.pattern {
+            ZmqPattern::PushPull => block_on(async move {
+                let mut socket = PullSocket::new();
+                let endpoint = socket.bind(&endpoint).await?;
+                Ok((BoundSocket::Pull(socket), endpoint))
+            })?,
+            ZmqPattern::PubSub => block_on(async move {
+                let mut socket = SubSocket::new();
+                socket.subscribe("").await?;
+                let endpoint = socket.bind(&endpoint).await?;
+                Ok((BoundSocket::Sub(socket), endpoint))
+            })?,
+        };
+        let (socket, endpoint) =
+            bound.map_err(|error: zeromq::ZmqError| PortError::Other(error.to_string()))?;
+        state.inputs.push(ZmqInput {
+            state: PortState::Open,
+            is_remote: false,
+            endpoint: endpoint.to_string(),
+            socket: Some(socket),
+            receiver: None,
+            sources: Vec::new(),
+            drained: false,
+            closed: watch::Sender::new(false),
+        });
+
+        InputPortID::try_from(-(state.inputs.len() as isize))
+            .map_err(|s| PortError::Other(s.to_string()))
+    }
+
+    fn open_output(&self) -> PortResult<OutputPortID> {
+        let mut state = self.state.lock();
+        state.outputs.push(ZmqOutput {
+            state: PortState::Open,
+            is_remote: false,
+            targets: Vec::new(),
+            fanout: FanOut::default(),
+            next: 0,
+        });
+
+        OutputPortID::try_from(state.outputs.len() as isize)
+            .map_err(|s| PortError::Other(s.to_string()))
+    }
+
+    fn close_input(&self, input: InputPortID) -> PortResult<bool> {
+        let result = self.close_input_port(input);
+        self.state_changed.notify_all();
+        result
+    }
+
+    fn close_output(&self, output: OutputPortID) -> PortResult<bool> {
+        let result = self.close_output_port(output);
+        self.state_changed.notify_all();
+        result
+    }
+
+    fn connect_with(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        let result = self.connect_ports(source, target, options);
+        self.state_changed.notify_all();
+        result
+    }
+
+    fn wait_for(&self, port: PortID, timeout: Option<Duration>) -> PortResult<bool> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut state = self.state.lock();
+        loop {
+            let port_state = match port {
+                PortID::Input(input) => state.inputs.get(input.index()).map(|input| input.state),
+                PortID::Output(output) => {
+                    state.outputs.get(output.index()).map(|output| output.state)
+                }
+            };
+            match port_state {
+                None => return Err(PortError::Invalid(port)),
+                Some(PortState::Closed) => return Err(PortError::Closed),
+                Some(
+                    PortState::Connected(_) | PortState::FannedOut(..) | PortState::FannedIn(_),
+                ) => return Ok(true),
+                Some(PortState::Open) => {}
+            }
+            match deadline {
+                None => block_in_place(|| self.state_changed.wait(&mut state)),
+                Some(deadline) => {
+                    if Instant::now() >= deadline {
+                        return Ok(false); // timed out
+                    }
+                    block_in_place(|| self.state_changed.wait_until(&mut state, deadline));
+                }
+            }
+        }
+    }
+
+    fn send(&self, output: OutputPortID, message: Bytes) -> PortResult<()> {
+        self.send_frame(output, Frame::Data(message), None) // blocking
+    }
+
+    fn send_timeout(
+        &self,
+        output: OutputPortID,
+        message: Bytes,
+        timeout: Duration,
+    ) -> PortResult<()> {
+        let deadline = Instant::now().checked_add(timeout);
+        self.send_frame(output, Frame::Data(message), deadline)
+    }
+
+    fn send_control(&self, output: OutputPortID, frame: Frame) -> PortResult<()> {
+        if !frame.is_control() {
+            return Err(PortError::Invalid(PortID::Output(output))); // TODO: better errors
+        }
+        self.send_frame(output, frame, None) // blocking
+    }
+
+    fn recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        self.recv_frame(input, None) // blocking
+    }
+
+    fn recv_timeout(&self, input: InputPortID, timeout: Duration) -> PortResult<Option<Frame>> {
+        self.recv_frame(input, Instant::now().checked_add(timeout))
+    }
+
+    fn try_recv(&self, input: InputPortID) -> PortResult<Option<Frame>> {
+        let frames = {
+            let state = self.state.lock();
+            match state.inputs.get(input.index()) {
+                None => return Err(PortError::Invalid(input.into())),
+                Some(port) => match (port.state, &port.receiver) {
+                    (PortState::Closed, _) => return Err(PortError::Closed),
+                    (PortState::Open, _) => return Err(PortError::Disconnected),
+                    (PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..), _) => {
+                        unreachable!()
+                    }
+                    (_, None) => return Err(PortError::Invalid(input.into())),
+                    (_, Some(receiver)) => receiver.frames.clone(),
+                },
+            }
+        };
+        let frame = match frames.try_lock() {
+            Err(_) => return Ok(None), // another thread is receiving
+            Ok(mut frames) => match frames.try_recv() {
+                Ok(frame) => Some(frame),
+                Err(TryRecvError::Empty) => return Ok(None),
+                Err(TryRecvError::Disconnected) => None,
+            },
+        };
+        match self.received(input, frame)? {
+            None => Err(PortError::Closed),
+            frame => Ok(frame),
+        }
+    }
+
+    fn close_all(&self) -> PortResult<()> {
+        let (inputs, outputs) = {
+            let mut state = self.state.lock();
+            state.is_terminated = true;
+            for input in state.inputs.iter() {
+                input.closed.send_replace(true);
+            }
+            for output in state.outputs.iter_mut() {
+                output.state = PortState::Closed;
+            }
+            for input in state.inputs.iter_mut() {
+                input.state = PortState::Closed;
+                input.sources.clear();
+            }
+            let inputs: Vec<_> = (state.inputs.iter_mut())
+                .map(|i| (i.socket.take(), i.receiver.take()))
+                .collect();
+            let outputs: Vec<_> = (state.outputs.iter_mut())
+                .map(|o| core::mem::take(&mut o.targets))
+                .collect();
+            (inputs, outputs)
+        };
+        self.state_changed.notify_all();
+        let _guard = tokio().enter(); // the sockets shut down asynchronously
+        drop((inputs, outputs));
+        Ok(())
+    }
+}
+
+impl ZmqTransport {
+    fn close_input_port(&self, input: InputPortID) -> PortResult<bool> {
+        let input_index = input.index();
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let input_state = match state.inputs.get(input_index) {
+            None => return Err(PortError::Invalid(input.into())),
+            Some(input) => input.state,
+        };
+        let mut sockets = Vec::new();
+        match input_state {
+            PortState::Closed => return Ok(false), // already closed
+            PortState::Open => {}
+            PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_) => {
+                for output in core::mem::take(&mut state.inputs[input_index].sources) {
+                    // The output may already have been closed, with its EOS
+                    // not yet received:
+                    let targets = &mut state.outputs[output.index()].targets;
+                    if let Some(position) = targets.iter().position(|(i, _)| *i == input) {
+                        sockets.push(targets.remove(position).1);
+                        state.update_output(output);
+                    }
+                }
+            }
+            PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..) => unreachable!(),
+        }
+        let input = &mut state.inputs[input_index];
+        input.state = PortState::Closed;
+        input.closed.send_replace(true); // wake up any blocked operations
+        let bound = (input.socket.take(), input.receiver.take());
+        let _guard = tokio().enter(); // the sockets shut down asynchronously
+        drop((sockets, bound));
+        Ok(true)
+    }
+
+    fn close_output_port(&self, output: OutputPortID) -> PortResult<bool> {
+        let output_index = output.index();
+        let mut guard = self.state.lock();
+        let state = &mut *guard;
+        let output_state = match state.outputs.get(output_index) {
+            None => return Err(PortError::Invalid(output.into())),
+            Some(output) => output.state,
+        };
+        let mut sockets = Vec::new();
+        match output_state {
+            PortState::Closed => return Ok(false), // already closed
+            PortState::Open => {}
+            PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..) => {
+                for (input, socket) in core::mem::take(&mut state.outputs[output_index].targets) {
+                    let input = &mut state.inputs[input.index()];
+                    if input.is_remote {
+                        input.state = PortState::Closed;
+                    } else if input.sources.len() > 1 {
+                        // A local input only gets the EOS from its last output:
+                        input.sources.retain(|&source| source != output);
+                        input.update();
+                    }
+                    // A local input stays connected until it receives the EOS:
+                    sockets.extend(socket);
+                }
+            }
+            PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_) => unreachable!(),
+        }
+        state.outputs[output_index].state = PortState::Closed;
+        drop(guard);
+        if !sockets.is_empty() {
+            block_on(async move {
+                for socket in sockets {
+                    // Stop the heartbeats, which mustn't follow the EOS:
+                    let Some(mut socket) = socket.lock().await.take() else {
+                        continue;
+                    };
+                    let _ = socket.send(ZmqMessage::from(Frame::Eos.encode())).await;
+                }
+            })?;
+        }
+        Ok(true)
+    }
+
+    fn connect_ports(
+        &self,
+        source: OutputPortID,
+        target: InputPortID,
+        options: ConnectionOptions,
+    ) -> PortResult<bool> {
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let (endpoint, is_remote) = match (
+            state.outputs.get(source.index()),
+            state.inputs.get(target.index()),
+        ) {
+            (None, _)
+            | (
+                Some(ZmqOutput {
+                    is_remote: true, ..
+                }),
+                _,
+            ) => {
+                return Err(PortError::Invalid(PortID::Output(source))); // TODO: better errors
+            }
+            (Some(output), _) if output.state.is_closed() => {
+                return Err(PortError::Invalid(PortID::Output(source))); // TODO: better errors
+            }
+            (_, None) => return Err(PortError::Invalid(PortID::Input(target))),
+            (_, Some(input)) if input.state.is_closed() => {
+                return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+            }
+            (_, Some(input)) => (input.endpoint.clone(), input.is_remote),
+        };
+        // The other overflow policies would need the receiving process to
+        // tell the sending process that its buffer is full:
+        if options.overflow != OverflowPolicy::Block {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        // The input's outputs must be distinct, and none of them closed with
+        // its EOS not yet received:
+        let input = &state.inputs[target.index()];
+        if input.sources.iter().any(|&output| {
+            output == source
+                || !(state.outputs[output.index()].targets.iter()).any(|(i, _)| *i == target)
+        }) {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        // The input's outputs share its buffer, so must agree on its capacity:
+        let capacity = options.capacity.max(1);
+        if input
+            .receiver
+            .as_ref()
+            .is_some_and(|r| r.capacity != capacity)
+        {
+            return Err(PortError::Invalid(PortID::Input(target))); // TODO: better errors
+        }
+        let liveness = self.heartbeat_interval * HEARTBEAT_LIVENESS;
+        let input = &mut state.inputs[target.index()];
+        if !is_remote {
+            input.connected(source, capacity, liveness);
+        }
+        let heartbeats = input.receiver.as_ref().map(|r| r.heartbeats.clone());
+        let connected = match self.pattern {
+            ZmqPattern::PushPull => block_on(async move {
+                let mut socket = PushSocket::new();
+                socket.connect(&endpoint).await?;
+                Ok(Box::new(socket) as Box<dyn SocketSend + Send>)
+            }),
+            ZmqPattern::PubSub => block_on(async move {
+                let mut socket = PubSocket::new();
+                socket.connect(&endpoint).await?;
+                if let Some(heartbeats) = heartbeats {
+                    subscribed(&mut socket, heartbeats).await;
+                }
+                Ok(Box::new(socket) as Box<dyn SocketSend + Send>)
+            }),
+        };
+        let connected = connected.and_then(|result| {
+            result.map_err(|error: zeromq::ZmqError| PortError::Other(error.to_string()))
+        });
+        let socket = match connected {
+            Ok(socket) => socket,
+            Err(error) => {
+                if !is_remote {
+                    state.inputs[target.index()].disconnected(source);
+                }
+                return Err(error);
+            }
+        };
+        let socket = Arc::new(AsyncMutex::new(Some(socket)));
+        tokio().spawn(send_heartbeats(
+            Arc::downgrade(&socket),
+            self.heartbeat_interval,
+        ));
+        let output = &mut state.outputs[source.index()];
+        output.targets.push((target, Some(socket)));
+        output.fanout = options.fanout;
+        state.update_output(source);
+        let input = &mut state.inputs[target.index()];
+        if is_remote {
+            input.state = PortState::Connected(PortID::Output(source));
+        }
+        Ok(true)
+    }
+
+    fn send_frame(
+        &self,
+        output: OutputPortID,
+        frame: Frame,
+        deadline: Option<Instant>,
+    ) -> PortResult<()> {
+        let sockets: Vec<OutputSocket> = {
+            let mut state = self.state.lock();
+            let output = match state.outputs.get_mut(output.index()) {
+                None => return Err(PortError::Invalid(PortID::Output(output))),
+                Some(output) => output,
+            };
+            let targets = match output.state {
+                PortState::Closed => return Err(PortError::Closed),
+                PortState::Open => return Err(PortError::Disconnected),
+                PortState::Connected(PortID::Output(_)) | PortState::FannedIn(_) => {
+                    unreachable!()
+                }
+                PortState::Connected(PortID::Input(_)) | PortState::FannedOut(..) => {
+                    // Control frames go to all the inputs, regardless of the
+                    // fan-out:
+                    match output.fanout {
+                        FanOut::RoundRobin if !frame.is_control() => {
+                            let next = output.next % output.targets.len();
+                            output.next = next + 1;
+                            &output.targets[next..=next]
+                        }
+                        _ => output.targets.as_slice(),
+                    }
+                }
+            };
+            (targets.iter())
+                .map(|(_, socket)| socket.clone().ok_or(PortError::Closed))
+                .collect::<PortResult<_>>()?
+        };
+        let send = async move {
+            // Send to all the inputs, even if some fail, returning the first
+            // error:
+            let mut result = Ok(());
+            for socket in sockets {
+                let mut socket = socket.lock().await;
+                let sent = match socket.as_mut() {
+                    None => Err(PortError::Closed),
+                    Some(socket) => (socket.send(ZmqMessage::from(frame.encode())).await)
+                        .map_err(|_| PortError::SendFailed),
+                };
+                result = result.and(sent);
+            }
+            result
+        };
+        block_on(async move {
+            match deadline {
+                None => send.await,
+                Some(deadline) => {
+                    let deadline = tokio::time::Instant::from_std(deadline);
+                    match tokio::time::timeout_at(deadline, send).await {
+                        Ok(result) => result,
+                        Err(_) => Err(PortError::Timeout),
+                    }
+                }
+            }
+        })?
+    }
+
+    /// Receives a frame, waiting for one until the deadline, if any.
+    fn recv_frame(
+        &self,
+        input: InputPortID,
+        deadline: Option<Instant>,
+    ) -> PortResult<Option<Frame>> {
+        let (frames, mut closed) = {
+            let mut state = self.state.lock();
+            loop {
+                match state.inputs.get(input.index()) {
+                    None => return Err(PortError::Invalid(PortID::Input(input))),
+                    Some(input) if input.state.is_closed() => {
+                        return match state.is_terminated {
+                            true => Err(PortError::Closed),
+                            false => Ok(None), // EOS (port closed)
+                        };
+                    }
+                    Some(ZmqInput { drained: true, .. }) => return Ok(None), // EOS
+                    Some(ZmqInput {
+                        is_remote: true, ..
+                    }) => return Err(PortError::Invalid(input.into())),
+                    Some(ZmqInput {
+                        receiver: Some(receiver),
+                        closed,
+                        ..
+                    }) => break (receiver.frames.clone(), closed.subscribe()),
+                    Some(_) => {} // wait for the port to get connected
+                }
+                match deadline {
+                    None => block_in_place(|| self.state_changed.wait(&mut state)),
+                    Some(deadline) => {
+                        if Instant::now() >= deadline {
+                            return Err(PortError::Timeout);
+                        }
+                        block_in_place(|| self.state_changed.wait_until(&mut state, deadline));
+                    }
+                }
+            }
+        };
+        let frame = block_on(async move {
+            let recv = async { frames.lock().await.recv().await };
+            let timeout = async {
+                match deadline {
+                    None => core::future::pending().await,
+                    Some(deadline) => {
+                        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await
+                    }
+                }
+            };
+            tokio::select! {
+                biased;
+                _ = closed.wait_for(|is_closed| *is_closed) => Ok(None),
+                frame = recv => Ok(frame),
+                _ = timeout => Err(PortError::Timeout),
+            }
+        })??;
+        self.received(input, frame)
+    }
+
+    /// Handles a frame received by an input port, or an error reported by
+    /// its background task, or the port having been closed if `None`.
+    fn received(
+        &self,
+        input: InputPortID,
+        frame: Option<PortResult<Frame>>,
+    ) -> PortResult<Option<Frame>> {
+        match frame {
+            None if self.state.lock().is_terminated => Err(PortError::Closed),
+            None => Ok(None), // EOS (port closed)
+            Some(Ok(frame)) => {
+                if frame.is_eos() {
+                    self.disconnect_input(input, true);
+                }
+                Ok(Some(frame))
+            }
+            Some(Err(PortError::Disconnected)) => {
+                self.disconnect_input(input, false); // the output ports crashed
+                Err(PortError::Disconnected)
+            }
+            Some(Err(error)) => Err(error),
+        }
+    }
+
+    /// Disconnects an input port from those of its output ports that are
+    /// closed, or in another process, once it has received the EOS of the
+    /// last of them, if `eos`, or once they crashed.
+    fn disconnect_input(&self, input: InputPortID, eos: bool) {
+        let input_index = input.index();
+        let mut state = self.state.lock();
+        let state = &mut *state;
+        let port = &mut state.inputs[input_index];
+        if !port.state.is_closed() {
+            let mut sources = core::mem::take(&mut port.sources);
+            sources.retain(|&source| {
+                let output = &mut state.outputs[source.index()];
+                if output.is_remote {
+                    output.targets.clear();
+                    output.state = PortState::Closed;
+                }
+                output.targets.iter().any(|(i, _)| *i == input)
+            });
+            if let Some(receiver) = &port.receiver {
+                receiver.pending_eos.store(sources.len(), Ordering::SeqCst);
+            }
+            port.drained = eos && sources.is_empty();
+            port.sources = sources;
+            port.update();
+        }
+        self.state_changed.notify_all();
+    }
+}
+
+/// Runs a future on the Tokio runtime shared by all transports, blocking
+/// the current thread until it completes.
+///
+/// Unlike `Runtime::block_on`, this doesn't panic when called from within
+/// another Tokio runtime, as by the blocks of a `TokioRuntime`: the future
+/// is then spawned on the shared runtime, and waited for.
+fn block_on<T: Send + 'static>(future: impl Future<Output = T> + Send + 'static) -> PortResult<T> {
+    if Handle::try_current().is_err() {
+        return Ok(block_in_place(|| tokio().block_on(future)));
+    }
+    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+    tokio().spawn(async move {
+        let _ = sender.send(future.await);
+    });
+    // The task only fails to send its output if the shared runtime shut down:
+    block_in_place(|| receiver.recv()).map_err(|_| PortError::Closed)
+}
+
+/// Returns the Tokio runtime shared by all transports.
+fn tokio() -> &'static Runtime {
+    static TOKIO: OnceLock<Runtime> = OnceLock::new();
+    TOKIO.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .thread_name("protoflow-zeromq")
+            .enable_all()
+            .build()
+            .expect("failed to start the Tokio runtime")
+    })
+}
+
+/// Spawns the background task receiving the messages of a bound socket,
+/// buffering up to `capacity` frames.
+fn spawn_receiver(
+    socket: BoundSocket,
+    closed: &watch::Sender<bool>,
+    capacity: usize,
+    liveness: Duration,
+) -> ZmqReceiver {
+    let (frames, receiver) = mpsc::channel(capacity);
+    let (heartbeats, heartbeats_receiver) = watch::channel(0);
+    let pending_eos = Arc::new(AtomicUsize::new(0));
+    let closed = closed.subscribe();
+    let eos = pending_eos.clone();
+    match socket {
+        BoundSocket::Pull(socket) => tokio().spawn(recv_frames(
+            socket, frames, heartbeats, eos, closed, liveness,
+        )),
+        BoundSocket::Sub(socket) => tokio().spawn(recv_frames(
+            socket, frames, heartbeats, eos, closed, liveness,
+        )),
+    };
+    ZmqReceiver {
+        frames: Arc::new(AsyncMutex::new(receiver)),
+        capacity,
+        pending_eos,
+        heartbeats: heartbeats_receiver,
+    }
+}
+
+/// Receives the messages of a bound socket until its input port is closed.
+///
+/// Once a message was received, the output port is considered crashed, and
+/// a `PortError::Disconnected` error reported, if no other message, be it a
+/// heartbeat, arrives for `liveness`, unless the message was the EOS.
+///
+/// Only the last of the `pending_eos` EOS frames is passed on, once all the
+/// output ports fanning in to the input port have sent theirs.
+async fn recv_frames<S: SocketRecv>(
+    mut socket: S,
+    frames: mpsc::Sender<PortResult<Frame>>,
+    heartbeats: watch::Sender<u64>,
+    pending_eos: Arc<AtomicUsize>,
+    mut closed: watch::Receiver<bool>,
+    liveness: Duration,
+) {
+    let mut deadline: Option<tokio::time::Instant> = None;
+    loop {
+        let watchdog = async {
+            match deadline {
+                None => core::future::pending().await,
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+            }
+        };
+        let frame = tokio::select! {
+            _ = closed.wait_for(|is_closed| *is_closed) => break,
+            _ = watchdog => {
+                deadline = None;
+                Err(PortError::Disconnected)
+            }
+            message = socket.recv() => match message {
+                Err(_) => Err(PortError::RecvFailed),
+                Ok(message) => {
+                    let message = message.into_vec().into_iter().next().unwrap_or_default();
+                    deadline = Some(tokio::time::Instant::now() + liveness);
+                    if message.is_empty() {
+                        heartbeats.send_modify(|count| *count += 1);
+                        continue;
+                    }
+                    let frame = Frame::decode(message);
+                    if frame.as_ref().is_ok_and(Frame::is_eos) {
+                        if pending_eos.fetch_sub(1, Ordering::SeqCst) > 1 {
+                            continue; // other output ports are still connected
+                        }
+                        deadline = None;
+                    }
+                    frame
+                }
+            },
+        };
+        if frames.send(frame).await.is_err() {
+            break; // the input port was closed
+        }
+        // The output port isn't to blame for the time the frame was queued:
+        if let Some(deadline) = deadline.as_mut() {
+            *deadline = tokio::time::Instant::now() + liveness;
+        }
+    }
+}
+
+/// Sends heartbeats over a connected socket until it's closed or dropped.
+async fn send_heartbeats(
+    socket: Weak<AsyncMutex<Option<Box<dyn SocketSend + Send>>>>,
+    interval: Duration,
+) {
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let Some(socket) = socket.upgrade() else {
+            break; // the output port was closed
+        };
+        let mut socket = socket.lock().await;
+        let Some(socket) = socket.as_mut() else {
+            break; // the EOS was sent
+        };
+        let _ = socket.send(heartbeat()).await;
+    }
+}
+
+/// Waits until a PUB socket's subscriber is subscribed, probing it with
+/// heartbeats, as a PUB socket drops the messages sent before then.
+async fn subscribed(socket: &mut PubSocket, mut heartbeats: watch::Receiver<u64>) {
+    heartbeats.borrow_and_update();
+    loop {
+        let _ = socket.send(heartbeat()).await;
+        tokio::select! {
+            result = heartbeats.changed() => {
+                let _ = result; // the input port may have been closed
+                break;
+            }
+            _ = tokio::time::sleep(PROBE_INTERVAL) => {}
+        }
+    }
+}
+
+fn heartbeat() -> ZmqMessage {
+    ZmqMessage::from(Bytes::new())
+}
+
+fn input_id(port: &dyn Port) -> PortResult<InputPortID> {
+    match port.id() {
+        Some(PortID::Input(input)) => Ok(input),
+        _ => Err(PortError::Other("not an input port".into())),
+    }
+}
+
+fn output_id(port: &dyn Port) -> PortResult<OutputPortID> {
+    match port.id() {
+        Some(PortID::Output(output)) => Ok(output),
+        _ => Err(PortError::Other("not an output port".into())),
+    }
+}
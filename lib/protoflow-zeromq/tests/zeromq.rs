@@ -0,0 +1,310 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow_core::{
+    runtimes::StdRuntime, ConnectionOptions, FanOut, Frame, InputPortID, OverflowPolicy, Port,
+    PortError, PortState, System, Transport,
+};
+use protoflow_zeromq::{ZmqPattern, ZmqTransport};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+#[test]
+fn send_and_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect(&output, &input));
+    let sender = thread::spawn(move || {
+        for message in 0..10 {
+            output.send(&message).unwrap();
+        }
+        output.close().unwrap();
+    });
+    for message in 0..10 {
+        assert_eq!(input.recv(), Ok(Some(message)));
+    }
+    assert_eq!(input.recv(), Ok(None));
+    sender.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn tcp_endpoint() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::with_endpoint("tcp://127.0.0.1")).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    let endpoint = runtime.transport().endpoint(&input).unwrap();
+    assert!(endpoint.starts_with("tcp://127.0.0.1:"));
+    system.connect(&output, &input);
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    Ok(())
+}
+
+#[test]
+fn timeouts_and_try_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    system.connect(&output, &input);
+    assert_eq!(input.try_recv(), Ok(None));
+    let timeout = Duration::from_millis(10);
+    assert_eq!(input.recv_timeout(timeout), Err(PortError::Timeout));
+    output.send(&42).unwrap();
+    assert_eq!(input.recv_timeout(Duration::from_secs(5)), Ok(Some(42)));
+    Ok(())
+}
+
+#[test]
+fn try_recv_keeps_frames() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let sender = thread::spawn(move || {
+        for message in 0..100 {
+            output.send(&message).unwrap();
+        }
+        output.close().unwrap();
+    });
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut messages = Vec::new();
+    while messages.len() < 100 && Instant::now() < deadline {
+        if let Some(message) = input.try_recv().unwrap() {
+            messages.push(message);
+        }
+    }
+    assert_eq!(messages, (0..100).collect::<Vec<_>>());
+    assert_eq!(input.recv(), Ok(None)); // EOS
+    sender.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn shutdown_wakes_up_recv() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let receiver = thread::spawn(move || input.recv());
+    thread::sleep(Duration::from_millis(10));
+    runtime.shutdown().unwrap();
+    assert_eq!(receiver.join().unwrap(), Err(PortError::Closed));
+    Ok(())
+}
+
+#[test]
+fn connect_across_transports() -> Result<(), ()> {
+    // Each runtime stands in for a separate process:
+    let consumer = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let consumer_system = System::new(&consumer);
+    let input = consumer_system.input::<i32>();
+    let endpoint = consumer.transport().endpoint(&input).unwrap();
+    assert_eq!(consumer.transport().connect_from(&input), Ok(true));
+    assert!(input.is_connected());
+
+    let producer = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let producer_system = System::new(&producer);
+    let mut output = producer_system.output::<i32>();
+    assert_eq!(
+        producer.transport().connect_to(&output, &endpoint),
+        Ok(true)
+    );
+    assert!(output.is_connected());
+
+    output.send(&42).unwrap();
+    output.close().unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert_eq!(input.recv(), Ok(None)); // EOS
+    assert!(!input.is_connected());
+    Ok(())
+}
+
+#[test]
+fn control_frames() -> Result<(), ()> {
+    let runtime = StdRuntime::new(ZmqTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    output.flush().unwrap();
+    output.send_error("overheated").unwrap();
+    output.send(&42).unwrap();
+    output.close().unwrap();
+    let error = PortError::Upstream("overheated".into());
+    assert_eq!(input.recv(), Err(error));
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert_eq!(input.recv(), Ok(None)); // EOS
+    Ok(())
+}
+
+#[test]
+fn pub_sub() -> Result<(), ()> {
+    let transport = ZmqTransport::new().with_pattern(ZmqPattern::PubSub);
+    let runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect(&output, &input));
+    output.send(&42).unwrap();
+    output.send(&43).unwrap();
+    output.close().unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert_eq!(input.recv(), Ok(Some(43)));
+    assert_eq!(input.recv(), Ok(None)); // EOS
+    Ok(())
+}
+
+#[test]
+fn crashed_producer() -> Result<(), ()> {
+    let heartbeat = Duration::from_millis(20);
+    let consumer = StdRuntime::new(ZmqTransport::new().with_heartbeat(heartbeat)).unwrap();
+    let consumer_system = System::new(&consumer);
+    let input = consumer_system.input::<i32>();
+    let endpoint = consumer.transport().endpoint(&input).unwrap();
+    consumer.transport().connect_from(&input).unwrap();
+
+    let producer = StdRuntime::new(ZmqTransport::new().with_heartbeat(heartbeat)).unwrap();
+    let producer_system = System::new(&producer);
+    let output = producer_system.output::<i32>();
+    producer.transport().connect_to(&output, &endpoint).unwrap();
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+
+    // Shutting down drops the sockets without sending the EOS:
+    producer.shutdown().unwrap();
+    assert_eq!(input.recv(), Err(PortError::Disconnected));
+    assert!(!input.is_connected());
+    Ok(())
+}
+
+#[test]
+fn fan_out() -> Result<(), ()> {
+    for fanout in [FanOut::Broadcast, FanOut::RoundRobin] {
+        let transport = ZmqTransport::new();
+        let output = transport.open_output().unwrap();
+        let inputs = [
+            transport.open_input().unwrap(),
+            transport.open_input().unwrap(),
+        ];
+        let options = ConnectionOptions::new().with_fanout(fanout);
+        for input in inputs {
+            assert_eq!(transport.connect_with(output, input, options), Ok(true));
+        }
+        assert_eq!(
+            transport.state(output.into()),
+            Ok(PortState::FannedOut(fanout, 2))
+        );
+        transport.send(output, "1".into()).unwrap();
+        transport.send(output, "2".into()).unwrap();
+        transport.close_output(output).unwrap();
+        let received: Vec<_> = inputs
+            .iter()
+            .map(|&input| recv_until_eos(&transport, input))
+            .collect();
+        let (one, two) = (Frame::Data("1".into()), Frame::Data("2".into()));
+        match fanout {
+            FanOut::Broadcast => assert_eq!(received, [[one.clone(), two.clone()], [one, two]]),
+            FanOut::RoundRobin => assert_eq!(received, [[one], [two]]),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn fan_in() -> Result<(), ()> {
+    let transport = ZmqTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    for output in outputs {
+        assert_eq!(transport.connect(output, input), Ok(true));
+    }
+    assert_eq!(transport.state(input.into()), Ok(PortState::FannedIn(2)));
+    transport.send(outputs[0], "1".into()).unwrap();
+    transport.close_output(outputs[0]).unwrap();
+    assert_eq!(
+        transport.state(input.into()),
+        Ok(PortState::Connected(outputs[1].into()))
+    );
+    transport.send(outputs[1], "2".into()).unwrap();
+    transport.close_output(outputs[1]).unwrap();
+    // The input only receives the EOS once all of its outputs are closed, in
+    // whichever order their sockets delivered their frames:
+    let mut received = recv_until_eos(&transport, input);
+    received.sort_by_key(|frame| format!("{:?}", frame));
+    assert_eq!(received, [Frame::Data("1".into()), Frame::Data("2".into())]);
+    assert_eq!(transport.state(input.into()), Ok(PortState::Open));
+    assert_eq!(
+        transport.recv_timeout(input, Duration::from_secs(10)),
+        Ok(None)
+    );
+    Ok(())
+}
+
+#[test]
+fn unsupported_options() -> Result<(), ()> {
+    let transport = ZmqTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(4);
+    assert!(transport
+        .connect_with(
+            outputs[0],
+            input,
+            options.with_overflow(OverflowPolicy::DropOldest)
+        )
+        .is_err());
+    assert_eq!(transport.state(input.into()), Ok(PortState::Open));
+    assert_eq!(transport.connect_with(outputs[0], input, options), Ok(true));
+    // The outputs fanning in share the buffer of the input:
+    assert!(transport
+        .connect_with(outputs[1], input, options.with_capacity(8))
+        .is_err());
+    assert_eq!(transport.state(outputs[1].into()), Ok(PortState::Open));
+    Ok(())
+}
+
+#[test]
+fn within_tokio_runtime() -> Result<(), ()> {
+    let tokio = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    tokio.block_on(async {
+        let transport = ZmqTransport::new();
+        let output = transport.open_output().unwrap();
+        let input = transport.open_input().unwrap();
+        assert_eq!(transport.connect(output, input), Ok(true));
+        transport.send(output, "42".into()).unwrap();
+        assert_eq!(transport.recv(input), Ok(Some(Frame::Data("42".into()))));
+        transport.close_output(output).unwrap();
+        assert_eq!(transport.recv(input), Ok(Some(Frame::Eos)));
+    });
+    Ok(())
+}
+
+/// Receives the frames of an input port until the EOS.
+fn recv_until_eos(transport: &ZmqTransport, input: InputPortID) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    while let Some(frame) = transport.recv(input).unwrap() {
+        if frame == Frame::Eos {
+            break;
+        }
+        frames.push(frame);
+    }
+    frames
+}
@@ -2,7 +2,7 @@
 
 use crate::sysexits::Sysexits;
 use protoflow_blocks::{build_stdio_system, Encoding, StdioConfig, StdioError};
-use protoflow_core::SystemExecution;
+use protoflow_core::{BlockError, SystemExecution};
 use std::path::PathBuf;
 
 #[derive(Clone, Debug)]
@@ -11,6 +11,7 @@ pub enum ExecuteError {
     MissingParameter(&'static str),
     InvalidParameter(&'static str),
     InvalidEncoding(String),
+    ExecutionFailed(String),
 }
 
 impl std::error::Error for ExecuteError {}
@@ -31,10 +32,19 @@ impl std::fmt::Display for ExecuteError {
             InvalidEncoding(encoding) => {
                 write!(f, "invalid encoding: {}", encoding)
             }
+            ExecutionFailed(error) => {
+                write!(f, "execution failed: {}", error)
+            }
         }
     }
 }
 
+impl From<BlockError> for ExecuteError {
+    fn from(error: BlockError) -> Self {
+        Self::ExecutionFailed(error.to_string())
+    }
+}
+
 impl From<StdioError> for ExecuteError {
     fn from(error: StdioError) -> Self {
         use StdioError::*;
@@ -57,6 +67,9 @@ pub fn execute(
         params: system_params.iter().cloned().collect(),
     };
     let system = build_stdio_system(system_uri, system_config)?;
-    system.execute().unwrap().join().unwrap(); // TODO: improve error handling
+    system
+        .execute()
+        .and_then(|process| process.join())
+        .map_err(ExecuteError::from)?;
     Ok(())
 }
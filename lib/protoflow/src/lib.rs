@@ -16,6 +16,12 @@ pub use protoflow_core::*;
 //#[cfg_attr(docsrs, doc(cfg(feature = "blocks")))]
 pub use protoflow_blocks as blocks;
 
+/// The Crossbeam transport is available if the crate was built with a
+/// `features = ["crossbeam"]` configuration.
+#[cfg(feature = "crossbeam")]
+//#[cfg_attr(docsrs, doc(cfg(feature = "crossbeam")))]
+pub use protoflow_crossbeam as crossbeam;
+
 /// Derive macros are available if the crate was built with a
 /// `features = ["derive"]` configuration.
 #[cfg(feature = "derive")]
@@ -25,12 +31,24 @@ pub use protoflow_derive as derive;
 mod feature;
 pub use feature::*;
 
+/// The Flume transport is available if the crate was built with a
+/// `features = ["flume"]` configuration.
+#[cfg(feature = "flume")]
+//#[cfg_attr(docsrs, doc(cfg(feature = "flume")))]
+pub use protoflow_flume as flume;
+
 /// The parser is available if the crate was built with a
 /// `features = ["syntax"]` configuration.
 #[cfg(feature = "syntax")]
 //#[cfg_attr(docsrs, doc(cfg(feature = "syntax")))]
 pub use protoflow_syntax as syntax;
 
+/// The ZeroMQ transport is available if the crate was built with a
+/// `features = ["zeromq"]` configuration.
+#[cfg(feature = "zeromq")]
+//#[cfg_attr(docsrs, doc(cfg(feature = "zeromq")))]
+pub use protoflow_zeromq as zeromq;
+
 #[doc = include_str!("../../../README.md")]
 #[cfg(doctest)]
 pub struct ReadmeDoctests;
@@ -85,6 +85,7 @@ impl From<error_stack::Report<protoflow_syntax::AnalysisError>> for Sysexits {
             ParseFailure => Self::EX_NOINPUT,
             InvalidImport(_) => Self::EX_DATAERR,
             UnknownName(_) => Self::EX_DATAERR,
+            InvalidRoute(_) => Self::EX_DATAERR,
             Other(_) => Self::EX_SOFTWARE,
         }
     }
@@ -109,6 +110,7 @@ impl From<crate::commands::execute::ExecuteError> for Sysexits {
             MissingParameter(_) => Self::EX_USAGE,
             InvalidParameter(_) => Self::EX_USAGE,
             InvalidEncoding(_) => Self::EX_USAGE,
+            ExecutionFailed(_) => Self::EX_SOFTWARE,
         }
     }
 }
@@ -0,0 +1,61 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{Delay, DelayType},
+    clocks::VirtualClock,
+    prelude::Arc,
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    BlockError, BlockRuntime, Clock, Runtime, System,
+};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+#[test]
+fn sleep_with_virtual_clock() -> Result<(), ()> {
+    let clock = Arc::new(VirtualClock::new());
+    let runtime = StdRuntime::with_clock(MpscTransport::new(), clock.clone()).unwrap();
+    assert_eq!(runtime.now(), Duration::ZERO);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(runtime.now(), Duration::from_secs(1));
+    assert!(runtime.sleep_until(Duration::from_millis(500)).is_ok()); // in the past
+    Ok(())
+}
+
+#[test]
+fn delay_with_virtual_clock() -> Result<(), ()> {
+    let clock = Arc::new(VirtualClock::new());
+    let mut runtime = StdRuntime::with_clock(MpscTransport::new(), clock.clone()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let delay = system.block(Delay::with_params(
+        system.input(),
+        system.output(),
+        DelayType::Fixed(Duration::from_secs(3600)),
+    ));
+    let input = system.input::<i32>();
+    system.connect(&output, &delay.input);
+    system.connect(&delay.output, &input);
+    let process = runtime.execute(system).unwrap();
+    output.send(&42).unwrap();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let ticker = {
+        let (clock, done) = (clock.clone(), done.clone());
+        std::thread::spawn(move || {
+            while !done.load(Ordering::SeqCst) {
+                clock.advance(Duration::from_secs(60));
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        })
+    };
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert!(clock.now() >= Duration::from_secs(3600));
+    done.store(true, Ordering::SeqCst);
+    ticker.join().unwrap();
+
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
@@ -0,0 +1,48 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    prelude::{Arc, String},
+    Block, BlockDescriptor, BlockError, BlockResult, BlockRuntime,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A block that fails its first `failures` executions, and that can only
+/// be executed once per clone.
+#[derive(Clone)]
+pub struct Flaky {
+    failures: usize,
+    executions: Arc<AtomicUsize>,
+    is_executed: bool,
+}
+
+impl Flaky {
+    pub fn new(failures: usize) -> Self {
+        Self {
+            failures,
+            executions: Arc::new(AtomicUsize::new(0)),
+            is_executed: false,
+        }
+    }
+
+    /// Returns the number of times the block was executed, by any clone.
+    pub fn executions(&self) -> usize {
+        self.executions.load(Ordering::SeqCst)
+    }
+}
+
+impl BlockDescriptor for Flaky {
+    fn name(&self) -> Option<String> {
+        Some("flaky".into())
+    }
+}
+
+impl Block for Flaky {
+    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
+        assert!(!self.is_executed, "not a fresh clone");
+        self.is_executed = true;
+        if self.executions.fetch_add(1, Ordering::SeqCst) < self.failures {
+            return Err(BlockError::Other("flaked".into()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime, transports::MpscTransport, ConnectionOptions, InputPort, OutputPort,
+    OverflowPolicy, PortError, System,
+};
+use std::time::Duration;
+
+fn connect(options: ConnectionOptions) -> (OutputPort<i32>, InputPort<i32>) {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect_with(&output, &input, options));
+    (output, input)
+}
+
+#[test]
+fn capacity() -> Result<(), ()> {
+    let (mut output, input) = connect(ConnectionOptions::new().with_capacity(3));
+    let timeout = Duration::from_millis(10);
+    for message in 1..=3 {
+        assert_eq!(output.send_timeout(&message, timeout), Ok(()));
+    }
+    assert_eq!(output.send_timeout(&4, timeout), Err(PortError::Timeout));
+    assert_eq!(input.recv(), Ok(Some(1)));
+    assert_eq!(output.send_timeout(&4, timeout), Ok(()));
+    output.close().unwrap(); // the EOS bypasses the capacity
+    for message in 2..=4 {
+        assert_eq!(input.recv(), Ok(Some(message)));
+    }
+    assert_eq!(input.recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn drop_newest() -> Result<(), ()> {
+    let options = ConnectionOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropNewest);
+    let (output, input) = connect(options);
+    for message in 1..=4 {
+        assert_eq!(output.send(&message), Ok(()));
+    }
+    assert_eq!(input.try_recv(), Ok(Some(1)));
+    assert_eq!(input.try_recv(), Ok(Some(2)));
+    assert_eq!(input.try_recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn drop_oldest() -> Result<(), ()> {
+    let options = ConnectionOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropOldest);
+    let (output, input) = connect(options);
+    for message in 1..=4 {
+        assert_eq!(output.send(&message), Ok(()));
+    }
+    assert_eq!(input.try_recv(), Ok(Some(3)));
+    assert_eq!(input.try_recv(), Ok(Some(4)));
+    assert_eq!(input.try_recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn overflow_error() -> Result<(), ()> {
+    let options = ConnectionOptions::new().with_overflow(OverflowPolicy::Error);
+    let (output, input) = connect(options);
+    assert_eq!(output.send(&1), Ok(()));
+    assert_eq!(output.send(&2), Err(PortError::Overflow));
+    assert_eq!(input.recv(), Ok(Some(1)));
+    assert_eq!(output.send(&2), Ok(()));
+    Ok(())
+}
@@ -0,0 +1,79 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    prelude::Bytes, runtimes::StdRuntime, transports::MpscTransport, ConnectionOptions, Frame,
+    OverflowPolicy, Port, PortError, PortState, System, Transport,
+};
+
+#[test]
+fn empty_message_is_not_eos() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    transport.connect(output, input).unwrap();
+    transport.send(output, Bytes::new()).unwrap();
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Data(Bytes::new()))));
+    assert_eq!(
+        transport.send_control(output, Frame::Eos), // not a control frame
+        Err(PortError::Invalid(output.into()))
+    );
+    assert!(transport.state(input.into()).unwrap().is_connected());
+    transport.close_output(output).unwrap();
+    assert_eq!(transport.recv(input), Ok(Some(Frame::Eos)));
+    assert_eq!(transport.state(input.into()), Ok(PortState::Open));
+    Ok(())
+}
+
+#[test]
+fn send_error() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect_with(&output, &input, ConnectionOptions::new().with_capacity(4));
+    output.send(&1).unwrap();
+    output.send_error("overheated").unwrap();
+    output.send(&2).unwrap();
+    assert_eq!(input.recv(), Ok(Some(1)));
+    let error = PortError::Upstream("overheated".into());
+    assert_eq!(input.try_recv(), Err(error));
+    assert_eq!(input.recv(), Ok(Some(2)));
+    assert!(input.is_connected());
+    Ok(())
+}
+
+#[test]
+fn flush() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    output.send(&1).unwrap(); // fills the channel's capacity
+    output.flush().unwrap(); // doesn't block
+    output.flush().unwrap();
+    assert_eq!(input.recv(), Ok(Some(1)));
+    assert_eq!(input.try_recv(), Ok(None)); // flushes are skipped
+    Ok(())
+}
+
+#[test]
+fn overflow_keeps_errors() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    let options = ConnectionOptions::new()
+        .with_capacity(2)
+        .with_overflow(OverflowPolicy::DropOldest);
+    system.connect_with(&output, &input, options);
+    output.send_error("overheated").unwrap();
+    for message in 1..=3 {
+        output.send(&message).unwrap();
+    }
+    let error = PortError::Upstream("overheated".into());
+    assert_eq!(input.try_recv(), Err(error));
+    assert_eq!(input.try_recv(), Ok(Some(3)));
+    assert_eq!(input.try_recv(), Ok(None));
+    Ok(())
+}
@@ -0,0 +1,140 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{build_stdio_system, Decode, Encoding, StdioConfig, StdioError},
+    prelude::{prost, prost::Message as _, Bytes, FromStr},
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    BlockError, BlockResult, ConnectionOptions, Message, PortError, System,
+};
+
+/// Decodes messages from a byte stream split into the given chunks.
+fn decode<T: Message + FromStr + 'static>(
+    encoding: Encoding,
+    chunks: &[&[u8]],
+) -> (Vec<T>, BlockResult) {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<Bytes>();
+    let decoder = system.block(Decode::<T>::with_params(
+        system.input(),
+        system.output(),
+        encoding,
+    ));
+    let input = system.input::<T>();
+    let options = ConnectionOptions::new().with_capacity(64);
+    system.connect_with(&output, &decoder.input, options);
+    system.connect_with(&decoder.output, &input, options);
+    let process = system.execute().unwrap();
+    for chunk in chunks {
+        // Sending fails once the decoder has failed:
+        let _ = output.send(&Bytes::copy_from_slice(chunk));
+    }
+    output.close().unwrap();
+    let result = process.join();
+    let mut messages = Vec::new();
+    while let Ok(Some(message)) = input.try_recv() {
+        messages.push(message);
+    }
+    (messages, result)
+}
+
+fn is_decode_error(result: BlockResult) -> bool {
+    match result {
+        Err(BlockError::Failed(failures)) => matches!(
+            failures[0].error,
+            BlockError::PortError(PortError::DecodeFailed(_))
+        ),
+        _ => false,
+    }
+}
+
+#[test]
+fn decode_with_length_prefix() -> Result<(), ()> {
+    let expected = vec![String::from("a"), "b".repeat(200), String::new()];
+    let mut stream = Vec::new();
+    for message in &expected {
+        stream.extend(message.encode_length_delimited_to_vec());
+    }
+    // Split every message and length prefix across chunks:
+    let chunks: Vec<_> = stream.chunks(1).collect();
+    let (messages, result) = decode::<String>(Encoding::ProtobufWithLengthPrefix, &chunks);
+    assert_eq!(messages, expected);
+    assert!(result.is_ok());
+
+    let (messages, result) = decode::<String>(Encoding::ProtobufWithLengthPrefix, &[&stream[..8]]);
+    assert_eq!(messages, vec![String::from("a")]);
+    assert!(is_decode_error(result)); // truncated message
+
+    let (_, result) = decode::<String>(Encoding::ProtobufWithLengthPrefix, &[&[0xFF; 10]]);
+    assert!(is_decode_error(result)); // invalid length prefix
+
+    let huge_prefix = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+    let (_, result) = decode::<String>(Encoding::ProtobufWithLengthPrefix, &[&huge_prefix]);
+    assert!(is_decode_error(result)); // message too large, without overflowing
+
+    let mut prefix = Vec::new();
+    prost::encode_length_delimiter(1 << 31, &mut prefix).unwrap();
+    let (_, result) = decode::<String>(Encoding::ProtobufWithLengthPrefix, &[&prefix]);
+    assert!(is_decode_error(result)); // over the 2 GiB limit
+    Ok(())
+}
+
+#[test]
+fn decode_without_length_prefix() -> Result<(), ()> {
+    let stream = String::from("hello, world").encode_to_vec();
+    let (messages, result) = decode::<String>(
+        Encoding::ProtobufWithoutLengthPrefix,
+        &[&stream[..3], &stream[3..]],
+    );
+    assert_eq!(messages, vec![String::from("hello, world")]);
+    assert!(result.is_ok());
+
+    let (messages, result) = decode::<String>(Encoding::ProtobufWithoutLengthPrefix, &[]);
+    assert!(messages.is_empty());
+    assert!(result.is_ok());
+    Ok(())
+}
+
+#[test]
+fn decode_lines() -> Result<(), ()> {
+    let (messages, result) = decode::<i32>(
+        Encoding::TextWithNewlineSuffix,
+        &[b"1\n2", b"3\r\n", b"\n", b"4"],
+    );
+    assert_eq!(messages, vec![1, 23]);
+    assert!(is_decode_error(result)); // the empty line isn't a number
+
+    let (messages, result) = decode::<i32>(Encoding::TextWithNewlineSuffix, &[b"1\n", b"2"]);
+    assert_eq!(messages, vec![1, 2]); // the last line has no newline
+    assert!(result.is_ok());
+
+    let (messages, result) = decode::<String>(
+        Encoding::TextWithNewlineSuffix,
+        &[
+            "héllo\nwo".as_bytes(),
+            &"ŕld\n".as_bytes()[..1],
+            &"ŕld\n".as_bytes()[1..],
+        ],
+    );
+    assert_eq!(messages, vec![String::from("héllo"), String::from("woŕld")]);
+    assert!(result.is_ok());
+
+    let (_, result) = decode::<String>(Encoding::TextWithNewlineSuffix, &[b"\xFF\n"]);
+    assert!(is_decode_error(result));
+    Ok(())
+}
+
+#[test]
+fn decode_stdio_system() -> Result<(), ()> {
+    let config = |encoding: &str| StdioConfig {
+        encoding: Encoding::TextWithNewlineSuffix,
+        params: [("encoding".into(), encoding.into())].into(),
+    };
+    assert!(build_stdio_system("Decode".into(), config("protobuf")).is_ok());
+    assert!(matches!(
+        build_stdio_system("Decode".into(), config("morse")),
+        Err(StdioError::InvalidParameter("encoding"))
+    ));
+    Ok(())
+}
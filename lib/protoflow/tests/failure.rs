@@ -0,0 +1,67 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use common::Flaky;
+use protoflow::{
+    blocks::Drop, runtimes::StdRuntime, transports::MpscTransport, Block, BlockDescriptor,
+    BlockError, BlockResult, BlockRuntime, FailurePolicy, System,
+};
+
+#[derive(Clone)]
+struct Panicky;
+
+impl BlockDescriptor for Panicky {}
+
+impl Block for Panicky {
+    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
+        panic!("panicked")
+    }
+}
+
+#[test]
+fn report_all_failures() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    system.block(Flaky::new(1));
+    system.block(Flaky::new(0));
+    system.block(Panicky);
+    let Err(BlockError::Failed(failures)) = system.execute().unwrap().join() else {
+        panic!("expected a failure report");
+    };
+    assert_eq!(failures.len(), 2);
+    assert_eq!(failures[0].name.as_deref(), Some("flaky"));
+    assert!(matches!(failures[0].error, BlockError::Other(_)));
+    assert_eq!(failures[1].name, None);
+    assert!(matches!(failures[1].error, BlockError::Panic(_)));
+    assert_eq!(failures[1].error.to_string(), "Panic: panicked");
+    Ok(())
+}
+
+#[test]
+fn fail_fast() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let mut system = System::new(&runtime);
+    system.set_failure_policy(FailurePolicy::FailFast);
+    let output = system.output::<i32>();
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&output, &blackhole.input);
+    system.block(Flaky::new(1));
+    let Err(BlockError::Failed(failures)) = system.execute().unwrap().join() else {
+        panic!("expected a failure report");
+    };
+    assert_eq!(failures.len(), 1);
+    assert!(!runtime.is_alive());
+    Ok(())
+}
+
+#[test]
+fn restart_on_failure() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let mut system = System::new(&runtime);
+    system.set_failure_policy(FailurePolicy::Restart);
+    let flaky = system.block(Flaky::new(2));
+    assert!(system.execute().unwrap().join().is_ok());
+    assert_eq!(flaky.executions(), 3);
+    Ok(())
+}
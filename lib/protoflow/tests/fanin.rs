@@ -0,0 +1,120 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime, transports::MpscTransport, ConnectionOptions, Port, PortID, PortState,
+    System, Transport,
+};
+use std::time::Duration;
+
+#[test]
+fn merge() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut outputs = [system.output::<i32>(), system.output::<i32>()];
+    let input = system.input::<i32>();
+    for output in &outputs {
+        assert!(system.connect(output, &input));
+    }
+    assert_eq!(input.state(), PortState::FannedIn(2));
+    assert!(input.is_connected());
+
+    outputs[0].send(&1).unwrap();
+    outputs[0].close().unwrap();
+    assert_eq!(
+        input.state(),
+        PortState::Connected(outputs[1].id().unwrap())
+    );
+    assert_eq!(input.recv(), Ok(Some(1)));
+    outputs[1].send(&2).unwrap();
+    assert_eq!(input.recv(), Ok(Some(2)));
+    outputs[1].close().unwrap();
+    assert_eq!(input.recv(), Ok(None));
+    assert_eq!(input.state(), PortState::Open);
+    Ok(())
+}
+
+#[test]
+fn merge_concurrently() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let input = system.input::<i32>();
+    let producers: Vec<_> = (0..4)
+        .map(|_| {
+            let mut output = system.output::<i32>();
+            assert!(system.connect(&output, &input));
+            std::thread::spawn(move || {
+                for message in 1..=100 {
+                    output.send(&message).unwrap();
+                }
+                output.close().unwrap();
+            })
+        })
+        .collect();
+    let mut sum = 0;
+    while let Some(message) = input.recv().unwrap() {
+        sum += message;
+    }
+    assert_eq!(sum, 4 * 5050);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    assert_eq!(input.state(), PortState::Open);
+    Ok(())
+}
+
+#[test]
+fn recv_after_all_outputs_closed() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut outputs = [system.output::<i32>(), system.output::<i32>()];
+    let input = system.input::<i32>();
+    for output in &outputs {
+        assert!(system.connect(output, &input));
+    }
+    for output in &mut outputs {
+        output.close().unwrap();
+    }
+    assert_eq!(input.recv(), Ok(None));
+    // The input stays at the end of the stream, instead of blocking:
+    assert_eq!(input.recv_timeout(Duration::from_secs(10)), Ok(None));
+    assert_eq!(input.state(), PortState::Open);
+    // Until it gets connected again:
+    let output = system.output::<i32>();
+    assert!(system.connect(&output, &input));
+    output.send(&1).unwrap();
+    assert_eq!(input.recv(), Ok(Some(1)));
+    Ok(())
+}
+
+#[test]
+fn reject_duplicate_connections() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let output = transport.open_output().unwrap();
+    let input = transport.open_input().unwrap();
+    assert_eq!(transport.connect(output, input), Ok(true));
+    assert!(transport.connect(output, input).is_err());
+    assert_eq!(
+        transport.state(input.into()),
+        Ok(PortState::Connected(PortID::Output(output)))
+    );
+    Ok(())
+}
+
+#[test]
+fn reject_conflicting_options() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let outputs = [
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+        transport.open_output().unwrap(),
+    ];
+    let input = transport.open_input().unwrap();
+    let options = ConnectionOptions::new().with_capacity(4);
+    assert_eq!(transport.connect_with(outputs[0], input, options), Ok(true));
+    assert_eq!(transport.connect_with(outputs[1], input, options), Ok(true));
+    assert!(transport
+        .connect_with(outputs[2], input, options.with_capacity(8))
+        .is_err());
+    assert_eq!(transport.state(input.into()), Ok(PortState::FannedIn(2)));
+    Ok(())
+}
@@ -0,0 +1,93 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime, transports::MpscTransport, ConnectionOptions, FanOut, InputPort,
+    OutputPort, Port, PortState, System,
+};
+use std::sync::Arc;
+
+fn fan_out(fanout: FanOut, count: usize) -> (OutputPort<i32>, Vec<InputPort<i32>>) {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let options = ConnectionOptions::new()
+        .with_capacity(4)
+        .with_fanout(fanout);
+    let inputs: Vec<_> = (0..count).map(|_| system.input::<i32>()).collect();
+    for input in &inputs {
+        assert!(system.connect_with(&output, input, options));
+    }
+    (output, inputs)
+}
+
+#[test]
+fn broadcast() -> Result<(), ()> {
+    let (mut output, inputs) = fan_out(FanOut::Broadcast, 3);
+    assert_eq!(output.state(), PortState::FannedOut(FanOut::Broadcast, 3));
+    assert!(output.is_connected());
+    for message in 1..=2 {
+        output.send(&message).unwrap();
+    }
+    output.close().unwrap();
+    for input in &inputs {
+        assert_eq!(input.recv(), Ok(Some(1)));
+        assert_eq!(input.recv(), Ok(Some(2)));
+        assert_eq!(input.recv(), Ok(None));
+        assert_eq!(input.state(), PortState::Open);
+    }
+    Ok(())
+}
+
+#[test]
+fn broadcast_by_reference() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<String>();
+    let inputs = [system.input::<String>(), system.input::<String>()];
+    for input in &inputs {
+        assert!(system.connect(&output, input));
+    }
+    let message = Arc::new(String::from("Hello, world!"));
+    output.send_shared(message.clone()).unwrap();
+    for input in &inputs {
+        assert!(Arc::ptr_eq(
+            &input.recv_shared().unwrap().unwrap(),
+            &message
+        ));
+    }
+    Ok(())
+}
+
+#[test]
+fn round_robin() -> Result<(), ()> {
+    let (mut output, inputs) = fan_out(FanOut::RoundRobin, 2);
+    assert_eq!(output.state(), PortState::FannedOut(FanOut::RoundRobin, 2));
+    for message in 1..=4 {
+        output.send(&message).unwrap();
+    }
+    output.close().unwrap();
+    assert_eq!(inputs[0].recv(), Ok(Some(1)));
+    assert_eq!(inputs[0].recv(), Ok(Some(3)));
+    assert_eq!(inputs[0].recv(), Ok(None));
+    assert_eq!(inputs[1].recv(), Ok(Some(2)));
+    assert_eq!(inputs[1].recv(), Ok(Some(4)));
+    assert_eq!(inputs[1].recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn close_fanned_out_inputs() -> Result<(), ()> {
+    let (output, mut inputs) = fan_out(FanOut::Broadcast, 3);
+    inputs[0].close().unwrap();
+    assert_eq!(output.state(), PortState::FannedOut(FanOut::Broadcast, 2));
+    inputs[1].close().unwrap();
+    assert_eq!(
+        output.state(),
+        PortState::Connected(inputs[2].id().unwrap())
+    );
+    output.send(&42).unwrap();
+    assert_eq!(inputs[2].recv(), Ok(Some(42)));
+    inputs[2].close().unwrap();
+    assert_eq!(output.state(), PortState::Open);
+    Ok(())
+}
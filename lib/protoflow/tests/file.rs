@@ -0,0 +1,123 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{ReadFile, WriteFile, WriteFlags},
+    prelude::Bytes,
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    ConnectionOptions, System,
+};
+use std::path::{Path, PathBuf};
+
+/// Returns a path for a test file, removing any such file left over.
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("protoflow-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Reads a file in chunks of at most the given size.
+fn read_file(path: &Path, buffer_size: usize) -> Vec<Bytes> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut path_output = system.output::<String>();
+    let reader = system.block(ReadFile::with_params(
+        system.input(),
+        system.output(),
+        Some(buffer_size),
+    ));
+    let input = system.input::<Bytes>();
+    let options = ConnectionOptions::new().with_capacity(64);
+    system.connect_with(&path_output, &reader.path, options);
+    system.connect_with(&reader.output, &input, options);
+    let process = system.execute().unwrap();
+    path_output
+        .send(&path.to_string_lossy().into_owned())
+        .unwrap();
+    path_output.close().unwrap();
+    process.join().unwrap();
+    let mut chunks = Vec::new();
+    while let Ok(Some(chunk)) = input.try_recv() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Writes the given chunks to a file.
+fn write_file(path: &Path, flags: WriteFlags, chunks: &[&[u8]]) {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut path_output = system.output::<String>();
+    let mut output = system.output::<Bytes>();
+    let writer = system.block(WriteFile::with_params(
+        system.input(),
+        system.input(),
+        flags,
+    ));
+    let options = ConnectionOptions::new().with_capacity(64);
+    system.connect_with(&path_output, &writer.path, options);
+    system.connect_with(&output, &writer.input, options);
+    let process = system.execute().unwrap();
+    path_output
+        .send(&path.to_string_lossy().into_owned())
+        .unwrap();
+    path_output.close().unwrap();
+    for chunk in chunks {
+        output.send(&Bytes::copy_from_slice(chunk)).unwrap();
+    }
+    output.close().unwrap();
+    process.join().unwrap();
+}
+
+#[test]
+fn read_file_in_chunks() -> Result<(), ()> {
+    let path = temp_path("read");
+    std::fs::write(&path, b"Hello, world!").unwrap();
+    let chunks = read_file(&path, 5);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        chunks,
+        vec![
+            Bytes::from("Hello"),
+            Bytes::from(", wor"),
+            Bytes::from("ld!")
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_empty_file() -> Result<(), ()> {
+    let path = temp_path("empty");
+    std::fs::write(&path, b"").unwrap();
+    let chunks = read_file(&path, 5);
+    std::fs::remove_file(&path).unwrap();
+    assert!(chunks.is_empty());
+    Ok(())
+}
+
+#[test]
+fn write_file_truncating() -> Result<(), ()> {
+    let path = temp_path("truncate");
+    std::fs::write(&path, b"Goodbye, world!").unwrap();
+    write_file(&path, WriteFlags::default(), &[b"Hello", b", world!"]);
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contents, b"Hello, world!");
+    Ok(())
+}
+
+#[test]
+fn write_file_appending() -> Result<(), ()> {
+    let path = temp_path("append");
+    write_file(&path, WriteFlags::append(), &[b"Hello"]);
+    let flags = WriteFlags {
+        sync: true,
+        ..WriteFlags::append()
+    };
+    write_file(&path, flags, &[b", world!"]);
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contents, b"Hello, world!");
+    Ok(())
+}
@@ -0,0 +1,188 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{Broadcast, Merge, Pair, RoundRobin, Select, Switch, Zip},
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    BlockError, ConnectionOptions, InputPort, Message, OutputPort, System,
+};
+
+fn options() -> ConnectionOptions {
+    ConnectionOptions::new().with_capacity(16)
+}
+
+fn send<T: Message>(mut output: OutputPort<T>, messages: &[T]) {
+    for message in messages {
+        output.send(message).unwrap();
+    }
+    output.close().unwrap();
+}
+
+fn drain<T: Message>(input: &InputPort<T>) -> Vec<T> {
+    let mut messages = Vec::new();
+    while let Ok(Some(message)) = input.try_recv() {
+        messages.push(message);
+    }
+    messages
+}
+
+#[test]
+fn merge() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output_a, output_b) = (system.output::<i32>(), system.output::<i32>());
+    let merger = system.block(Merge::<i32, 3>::new(system.inputs(), system.output()));
+    let input = system.input::<i32>();
+    system.connect_with(&output_a, &merger.inputs[0], options());
+    system.connect_with(&output_b, &merger.inputs[1], options());
+    system.connect_with(&merger.output, &input, options());
+    let process = system.execute().unwrap();
+    send(output_a, &[1, 2, 3]);
+    send(output_b, &[4, 5]);
+    process.join().unwrap();
+    let messages = drain(&input);
+    // The messages from each input stay in order:
+    let from_a: Vec<_> = messages.iter().copied().filter(|m| *m <= 3).collect();
+    let from_b: Vec<_> = messages.iter().copied().filter(|m| *m > 3).collect();
+    assert_eq!(from_a, vec![1, 2, 3]);
+    assert_eq!(from_b, vec![4, 5]);
+    Ok(())
+}
+
+#[test]
+fn broadcast() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let broadcaster = system.block(Broadcast::<i32, 3>::new(system.input(), system.outputs()));
+    let (input_a, input_b) = (system.input::<i32>(), system.input::<i32>());
+    system.connect_with(&output, &broadcaster.input, options());
+    system.connect_with(&broadcaster.outputs[0], &input_a, options());
+    system.connect_with(&broadcaster.outputs[2], &input_b, options());
+    let process = system.execute().unwrap();
+    send(output, &[1, 2, 3]);
+    process.join().unwrap();
+    assert_eq!(drain(&input_a), vec![1, 2, 3]);
+    assert_eq!(drain(&input_b), vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn round_robin() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let distributor = system.block(RoundRobin::<i32, 3>::new(system.input(), system.outputs()));
+    let (input_a, input_b) = (system.input::<i32>(), system.input::<i32>());
+    system.connect_with(&output, &distributor.input, options());
+    system.connect_with(&distributor.outputs[0], &input_a, options());
+    system.connect_with(&distributor.outputs[2], &input_b, options());
+    let process = system.execute().unwrap();
+    send(output, &[1, 2, 3, 4, 5]);
+    process.join().unwrap();
+    // The unconnected output is skipped:
+    assert_eq!(drain(&input_a), vec![1, 3, 5]);
+    assert_eq!(drain(&input_b), vec![2, 4]);
+    Ok(())
+}
+
+#[test]
+fn zip() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output_a, output_b) = (system.output::<i32>(), system.output::<String>());
+    let zipper = system.block(Zip::<i32, String>::new(
+        system.input(),
+        system.input(),
+        system.output(),
+    ));
+    let input = system.input::<Pair<i32, String>>();
+    system.connect_with(&output_a, &zipper.first, options());
+    system.connect_with(&output_b, &zipper.second, options());
+    system.connect_with(&zipper.output, &input, options());
+    let process = system.execute().unwrap();
+    send(output_a, &[1, 2, 3]);
+    send(output_b, &["a".into(), "b".into()]);
+    process.join().unwrap();
+    // The unpaired message is dropped:
+    assert_eq!(
+        drain(&input),
+        vec![Pair::new(1, "a".into()), Pair::new(2, "b".into())]
+    );
+    Ok(())
+}
+
+#[test]
+fn switch() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output, index) = (system.output::<i32>(), system.output::<u64>());
+    let switch = system.block(Switch::<i32, 2>::new(
+        system.input(),
+        system.input(),
+        system.outputs(),
+    ));
+    let (input_a, input_b) = (system.input::<i32>(), system.input::<i32>());
+    system.connect_with(&output, &switch.input, options());
+    system.connect_with(&index, &switch.index, options());
+    system.connect_with(&switch.outputs[0], &input_a, options());
+    system.connect_with(&switch.outputs[1], &input_b, options());
+    let process = system.execute().unwrap();
+    send(index, &[1, 0, 1]);
+    send(output, &[1, 2, 3, 4, 5]);
+    process.join().unwrap();
+    // The last index applies once the index stream has ended:
+    assert_eq!(drain(&input_a), vec![2]);
+    assert_eq!(drain(&input_b), vec![1, 3, 4, 5]);
+    Ok(())
+}
+
+#[test]
+fn select() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output_a, output_b) = (system.output::<i32>(), system.output::<i32>());
+    let index = system.output::<u64>();
+    let selector = system.block(Select::<i32, 2>::new(
+        system.input(),
+        system.inputs(),
+        system.output(),
+    ));
+    let input = system.input::<i32>();
+    system.connect_with(&index, &selector.index, options());
+    system.connect_with(&output_a, &selector.inputs[0], options());
+    system.connect_with(&output_b, &selector.inputs[1], options());
+    system.connect_with(&selector.output, &input, options());
+    let process = system.execute().unwrap();
+    send(output_a, &[1, 2, 3]);
+    send(output_b, &[4, 5, 6]);
+    send(index, &[1, 0, 0, 1]);
+    process.join().unwrap();
+    // The last index applies once the index stream has ended:
+    assert_eq!(drain(&input), vec![4, 1, 2, 5, 6]);
+    Ok(())
+}
+
+#[test]
+fn invalid_index() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output, index) = (system.output::<i32>(), system.output::<u64>());
+    let switch = system.block(Switch::<i32, 2>::new(
+        system.input(),
+        system.input(),
+        system.outputs(),
+    ));
+    system.connect_with(&output, &switch.input, options());
+    system.connect_with(&index, &switch.index, options());
+    let process = system.execute().unwrap();
+    send(index, &[2]);
+    send(output, &[1]);
+    match process.join() {
+        Err(BlockError::Failed(failures)) => {
+            assert!(matches!(failures[0].error, BlockError::Other(_)))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{Add, Div, Max, Mean, Min, Percentiles, Sub, Sum, Variance},
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    BlockError, ConnectionOptions, InputPort, Message, OutputPort, System,
+};
+
+fn options() -> ConnectionOptions {
+    ConnectionOptions::new().with_capacity(16)
+}
+
+fn send<T: Message>(mut output: OutputPort<T>, messages: &[T]) {
+    for message in messages {
+        output.send(message).unwrap();
+    }
+    output.close().unwrap();
+}
+
+fn drain<T: Message>(input: &InputPort<T>) -> Vec<T> {
+    let mut messages = Vec::new();
+    while let Ok(Some(message)) = input.try_recv() {
+        messages.push(message);
+    }
+    messages
+}
+
+#[test]
+fn arithmetic() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output, operands) = (system.output::<i64>(), system.output::<i64>());
+    let adder = system.block(Add::<i64>::with_params(
+        system.input(),
+        system.input(),
+        system.output(),
+        Some(10),
+    ));
+    let subtractor = system.block(Sub::<i64>::new(
+        system.input(),
+        system.input(),
+        system.output(),
+    ));
+    let input = system.input::<i64>();
+    system.connect_with(&output, &adder.input, options());
+    system.connect_with(&adder.output, &subtractor.input, options());
+    system.connect_with(&operands, &subtractor.operand, options());
+    system.connect_with(&subtractor.output, &input, options());
+    let process = system.execute().unwrap();
+    send(output, &[1, 2, 3]);
+    send(operands, &[1, 2]); // the shorter stream ends the output
+    process.join().unwrap();
+    assert_eq!(drain(&input), vec![10, 10]);
+    Ok(())
+}
+
+#[test]
+fn divide_by_zero() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<u32>();
+    let divider = system.block(Div::<u32>::with_params(
+        system.input(),
+        system.input(),
+        system.output(),
+        Some(0),
+    ));
+    system.connect_with(&output, &divider.input, options());
+    let process = system.execute().unwrap();
+    send(output, &[1]);
+    match process.join() {
+        Err(BlockError::Failed(failures)) => {
+            assert!(matches!(failures[0].error, BlockError::Other(_)))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    Ok(())
+}
+
+#[test]
+fn divide_floats_by_zero() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output, operands) = (system.output::<f64>(), system.output::<f64>());
+    let divider = system.block(Div::<f64>::new(
+        system.input(),
+        system.input(),
+        system.output(),
+    ));
+    let input = system.input::<f64>();
+    system.connect_with(&output, &divider.input, options());
+    system.connect_with(&operands, &divider.operand, options());
+    system.connect_with(&divider.output, &input, options());
+    let process = system.execute().unwrap();
+    send(output, &[1.0, 1.0]);
+    send(operands, &[2.0, 0.0]); // fails like an integer division by zero
+    match process.join() {
+        Err(BlockError::Failed(failures)) => {
+            assert!(matches!(failures[0].error, BlockError::Other(_)))
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    assert_eq!(drain(&input), vec![0.5]);
+    Ok(())
+}
+
+#[test]
+fn aggregates() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let outputs: Vec<_> = (0..5).map(|_| system.output::<i32>()).collect();
+    let summer = system.block(Sum::<i32>::new(system.input(), system.output()));
+    let minimizer = system.block(Min::<i32>::new(system.input(), system.output()));
+    let maximizer = system.block(Max::<i32>::new(system.input(), system.output()));
+    let averager = system.block(Mean::<i32>::new(system.input(), system.output()));
+    let variance = system.block(Variance::<i32>::new(system.input(), system.output()));
+    let (sum, min, max) = (system.input(), system.input(), system.input());
+    let (mean, var) = (system.input(), system.input());
+    system.connect_with(&outputs[0], &summer.input, options());
+    system.connect_with(&outputs[1], &minimizer.input, options());
+    system.connect_with(&outputs[2], &maximizer.input, options());
+    system.connect_with(&outputs[3], &averager.input, options());
+    system.connect_with(&outputs[4], &variance.input, options());
+    system.connect_with(&summer.output, &sum, options());
+    system.connect_with(&minimizer.output, &min, options());
+    system.connect_with(&maximizer.output, &max, options());
+    system.connect_with(&averager.output, &mean, options());
+    system.connect_with(&variance.output, &var, options());
+    let process = system.execute().unwrap();
+    for output in outputs {
+        send(output, &[2, 4, 4, 4, 5, 5, 7, 9]);
+    }
+    process.join().unwrap();
+    assert_eq!(drain(&sum), vec![40]);
+    assert_eq!(drain(&min), vec![2]);
+    assert_eq!(drain(&max), vec![9]);
+    assert_eq!(drain(&mean), vec![5.0]);
+    assert_eq!(drain(&var), vec![4.0]);
+    Ok(())
+}
+
+#[test]
+fn aggregate_nothing() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output_a, output_b) = (system.output::<f64>(), system.output::<f64>());
+    let summer = system.block(Sum::<f64>::new(system.input(), system.output()));
+    let averager = system.block(Mean::<f64>::new(system.input(), system.output()));
+    let (sum, mean) = (system.input(), system.input());
+    system.connect_with(&output_a, &summer.input, options());
+    system.connect_with(&output_b, &averager.input, options());
+    system.connect_with(&summer.output, &sum, options());
+    system.connect_with(&averager.output, &mean, options());
+    let process = system.execute().unwrap();
+    send(output_a, &[]);
+    send(output_b, &[]);
+    process.join().unwrap();
+    // An empty sum is zero, but an empty mean is undefined:
+    assert_eq!(drain(&sum), vec![0.0]);
+    assert_eq!(drain(&mean), Vec::<f64>::new());
+    Ok(())
+}
+
+#[test]
+fn percentiles() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output_a, output_b) = (system.output::<u64>(), system.output::<u64>());
+    let small = system.block(Percentiles::<u64>::new(
+        system.input(),
+        system.output(),
+        system.output(),
+        system.output(),
+    ));
+    let large = system.block(Percentiles::<u64>::new(
+        system.input(),
+        system.output(),
+        system.output(),
+        system.output(),
+    ));
+    let (small_p50, small_p99) = (system.input(), system.input());
+    let (p50, p95, p99) = (system.input(), system.input(), system.input());
+    let options = ConnectionOptions::new().with_capacity(1024);
+    system.connect_with(&output_a, &small.input, options);
+    system.connect_with(&output_b, &large.input, options);
+    system.connect_with(&small.p50, &small_p50, options);
+    system.connect_with(&small.p99, &small_p99, options);
+    system.connect_with(&large.p50, &p50, options);
+    system.connect_with(&large.p95, &p95, options);
+    system.connect_with(&large.p99, &p99, options);
+    let process = system.execute().unwrap();
+    send(output_a, &[30, 10, 20]);
+    // A permutation of 1..=1000:
+    let numbers: Vec<u64> = (0..1000).map(|i| i * 7919 % 1000 + 1).collect();
+    send(output_b, &numbers);
+    process.join().unwrap();
+    // Small inputs have exact percentiles:
+    assert_eq!(drain(&small_p50), vec![20.0]);
+    assert_eq!(drain(&small_p99), vec![29.8]);
+    // Larger inputs have estimated percentiles:
+    for (input, expected) in [(&p50, 500.0), (&p95, 950.0), (&p99, 990.0)] {
+        let estimate = drain(input)[0];
+        assert!((estimate - expected).abs() < 10.0, "{}", estimate);
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime,
+    transports::{MockTransport, MpscTransport},
+    types::Timestamp,
+    ConnectionOptions, InputPort, MessageMetadata, OutputPort, System, Transport,
+};
+
+fn connect<X: Transport + Default + 'static>(transport: X) -> (OutputPort<i32>, InputPort<i32>) {
+    let runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    let options = ConnectionOptions::new().with_capacity(4);
+    assert!(system.connect_with(&output, &input, options));
+    (output, input)
+}
+
+fn metadata() -> MessageMetadata {
+    MessageMetadata::new()
+        .with_timestamp(Timestamp::date(2024, 1, 1).unwrap())
+        .with_sequence(7)
+        .with_correlation_id("request-42")
+        .with_trace_context("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01")
+        .with_header("source", "sensor")
+}
+
+#[test]
+fn send_with_metadata() -> Result<(), ()> {
+    let (mut output, input) = connect(MpscTransport::new());
+    output.send_with_metadata(&42, &metadata()).unwrap();
+    output
+        .send_with_metadata(&0, &MessageMetadata::new())
+        .unwrap();
+    output.send(&43).unwrap();
+    output.send_with_metadata(&44, &metadata()).unwrap();
+    output.close().unwrap();
+    assert_eq!(input.recv_with_metadata(), Ok(Some((42, metadata()))));
+    assert_eq!(
+        input.recv_with_metadata(),
+        Ok(Some((0, MessageMetadata::new())))
+    );
+    assert_eq!(
+        input.recv_with_metadata(),
+        Ok(Some((43, MessageMetadata::new())))
+    );
+    assert_eq!(input.recv(), Ok(Some(44))); // the metadata is skipped
+    assert_eq!(input.recv_with_metadata(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn carry_metadata_over_any_transport() -> Result<(), ()> {
+    let (output, input) = connect(MockTransport::new());
+    output.send_with_metadata(&42, &metadata()).unwrap();
+    assert_eq!(input.recv_with_metadata(), Ok(Some((42, metadata()))));
+    Ok(())
+}
+
+#[cfg(feature = "crossbeam")]
+#[test]
+fn carry_metadata_over_crossbeam() -> Result<(), ()> {
+    let (output, input) = connect(protoflow::crossbeam::CrossbeamTransport::new());
+    output.send_with_metadata(&42, &metadata()).unwrap();
+    assert_eq!(input.recv_with_metadata(), Ok(Some((42, metadata()))));
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime, transports::MockTransport, InputPort, OutputPort, Port, PortError,
+    PortID, PortState, System,
+};
+use std::{sync::Arc, time::Duration};
+
+fn connect() -> (
+    Arc<StdRuntime<MockTransport>>,
+    OutputPort<i32>,
+    InputPort<i32>,
+) {
+    let runtime = StdRuntime::new(MockTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    assert!(system.connect(&output, &input));
+    (runtime, output, input)
+}
+
+#[test]
+fn record_sent_messages() -> Result<(), ()> {
+    let (runtime, mut output, input) = connect();
+    let transport = runtime.transport();
+    for message in 1..=3 {
+        output.send(&message).unwrap();
+    }
+    output.close().unwrap();
+    assert_eq!(transport.sent(&output), Ok(vec![1, 2, 3]));
+    for message in 1..=3 {
+        assert_eq!(input.recv(), Ok(Some(message)));
+    }
+    assert_eq!(input.recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn inject_messages() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MockTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let input = system.input::<i32>();
+    let transport = runtime.transport();
+    transport.inject(&input, &42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert_eq!(input.recv(), Err(PortError::Disconnected));
+    transport.inject(&input, &43).unwrap();
+    assert_eq!(input.try_recv(), Ok(Some(43)));
+    Ok(())
+}
+
+#[test]
+fn record_state_transitions() -> Result<(), ()> {
+    let (runtime, mut output, mut input) = connect();
+    let (source, target) = (output.id().unwrap(), input.id().unwrap());
+    output.close().unwrap();
+    assert_eq!(input.recv(), Ok(None));
+    input.close().unwrap();
+    assert_eq!(
+        runtime.transport().transitions(),
+        vec![
+            (source, PortState::Connected(target)),
+            (target, PortState::Connected(source)),
+            (source, PortState::Closed),
+            (target, PortState::Open),
+            (target, PortState::Closed),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn simulate_failures() -> Result<(), ()> {
+    let (runtime, output, input) = connect();
+    let transport = runtime.transport();
+    transport.fail_next(output.id().unwrap(), PortError::SendFailed);
+    assert_eq!(output.send(&1), Err(PortError::SendFailed));
+    assert_eq!(output.send(&2), Ok(()));
+    transport.fail_next(input.id().unwrap(), PortError::RecvFailed);
+    assert_eq!(input.recv(), Err(PortError::RecvFailed));
+    assert_eq!(input.recv(), Ok(Some(2)));
+    assert_eq!(transport.sent(&output), Ok(vec![2]));
+    Ok(())
+}
+
+#[test]
+fn simulate_disconnect() -> Result<(), ()> {
+    let (runtime, output, input) = connect();
+    let transport = runtime.transport().clone();
+    let Some(PortID::Output(source)) = output.id() else {
+        unreachable!()
+    };
+    let receiver = std::thread::spawn(move || input.recv());
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(transport.break_connection(source), Ok(true));
+    assert_eq!(receiver.join().unwrap(), Err(PortError::Disconnected));
+    assert!(!output.is_connected());
+    assert_eq!(output.send(&1), Err(PortError::Disconnected));
+    assert_eq!(transport.break_connection(source), Ok(false));
+    Ok(())
+}
+
+#[cfg(feature = "blocks")]
+#[test]
+fn unit_test_block() -> Result<(), ()> {
+    use protoflow::{blocks::Const, Block};
+
+    let (runtime, output, input) = connect();
+    let mut block = Const::with_params(output, 42);
+    block.execute(&runtime).unwrap();
+    let transport = runtime.transport();
+    assert_eq!(transport.sent(&block.output), Ok(vec![42]));
+    assert!(block.output.is_closed());
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert_eq!(input.recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn recv_on_closed_port() -> Result<(), ()> {
+    let (runtime, _output, mut input) = connect();
+    assert_eq!(input.close(), Ok(true));
+    assert_eq!(input.recv(), Ok(None));
+    assert_eq!(input.recv_timeout(Duration::from_millis(1)), Ok(None));
+    assert_eq!(input.try_recv(), Err(PortError::Closed));
+    runtime.shutdown().unwrap();
+    assert_eq!(input.recv(), Err(PortError::Closed));
+    assert_eq!(input.try_recv(), Err(PortError::Closed));
+    Ok(())
+}
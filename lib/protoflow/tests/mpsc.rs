@@ -4,8 +4,9 @@ use protoflow::{
     blocks::{Const, Drop},
     runtimes::StdRuntime,
     transports::MpscTransport,
-    Runtime, System,
+    InputPorts, Runtime, System,
 };
+use std::{thread, time::Duration};
 
 #[test]
 fn execute_mpsc_transport() -> Result<(), ()> {
@@ -22,3 +23,25 @@ fn execute_mpsc_transport() -> Result<(), ()> {
     process.join().unwrap();
     Ok(())
 }
+
+#[test]
+fn recv_from_ports() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let [mut output_a, mut output_b] = [system.output::<i32>(), system.output::<i32>()];
+    let inputs = InputPorts::<i32, 2>::new(&system);
+    system.connect(&output_a, &inputs[0]);
+    system.connect(&output_b, &inputs[1]);
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10)); // while the receiver waits
+        output_b.send(&42).unwrap();
+        output_b.close().unwrap();
+    });
+    assert_eq!(inputs.recv_from(0), Ok(Some((1, 42))));
+    sender.join().unwrap();
+    output_a.send(&7).unwrap();
+    output_a.close().unwrap();
+    assert_eq!(inputs.recv_from(1), Ok(Some((0, 7))));
+    assert_eq!(inputs.recv_from(0), Ok(None));
+    Ok(())
+}
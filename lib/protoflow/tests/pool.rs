@@ -0,0 +1,83 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "pool")]
+
+use protoflow::{
+    blocks::{Const, Delay, DelayType, Drop},
+    runtimes::{PoolRuntime, StdRuntime},
+    transports::MpscTransport,
+    BlockError, Runtime, System,
+};
+use std::time::Duration;
+
+#[test]
+fn execute_on_pool() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    // SAFETY: None of the blocks hold values that aren't `Send`.
+    let mut pool = unsafe { PoolRuntime::new(&runtime, 2) }.unwrap();
+    let system = System::new(&runtime);
+    let constant = system.block(Const {
+        output: system.output(),
+        value: 42,
+    });
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&constant.output, &blackhole.input);
+    let process = pool.execute(system).unwrap();
+    process.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn execute_more_blocks_than_workers() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    // SAFETY: None of the blocks hold values that aren't `Send`.
+    let mut pool = unsafe { PoolRuntime::new(&runtime, 1) }.unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let mut source = output.clone();
+    for _ in 0..8 {
+        let delay = system.block(Delay::with_params(
+            system.input(),
+            system.output(),
+            DelayType::Fixed(Duration::from_millis(1)),
+        ));
+        system.connect(&source, &delay.input);
+        source = delay.output;
+    }
+    let input = system.input::<i32>();
+    system.connect(&source, &input);
+    let process = pool.execute(system).unwrap();
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn execute_blocked_blocks_on_workers() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    // SAFETY: None of the blocks hold values that aren't `Send`.
+    let mut pool = unsafe { PoolRuntime::new(&runtime, 4) }.unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let mut source = output.clone();
+    for _ in 0..500 {
+        let delay = system.block(Delay::with_params(
+            system.input(),
+            system.output(),
+            DelayType::Fixed(Duration::from_millis(1)),
+        ));
+        system.connect(&source, &delay.input);
+        source = delay.output;
+    }
+    let input = system.input::<i32>();
+    system.connect(&source, &input);
+    let process = pool.execute(system).unwrap();
+    std::thread::sleep(Duration::from_millis(50)); // while all blocks wait
+    assert!(pool.thread_count() <= pool.worker_count());
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert!(pool.thread_count() <= pool.worker_count());
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    prelude::{vec, Arc, Vec},
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    Block, BlockDescriptor, BlockResult, BlockRuntime, FailurePolicy, InputPort, Port,
+    PortDescriptor, PortState, Process, RestartPolicy, System,
+};
+use std::sync::Mutex;
+
+/// A block that collects all the messages it receives.
+#[derive(Clone)]
+struct Collect {
+    input: InputPort<i32>,
+    messages: Arc<Mutex<Vec<i32>>>,
+}
+
+impl Collect {
+    fn new(input: InputPort<i32>) -> Self {
+        Self {
+            input,
+            messages: Arc::default(),
+        }
+    }
+
+    fn messages(&self) -> Vec<i32> {
+        self.messages.lock().unwrap().clone()
+    }
+}
+
+impl BlockDescriptor for Collect {
+    fn inputs(&self) -> Vec<PortDescriptor> {
+        vec![PortDescriptor::from(&self.input)]
+    }
+}
+
+impl Block for Collect {
+    fn execute(&mut self, _runtime: &dyn BlockRuntime) -> BlockResult {
+        while let Some(message) = self.input.recv()? {
+            self.messages.lock().unwrap().push(message);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn hot_plug_sinks() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let handle = system.spawn().unwrap();
+
+    let first = Collect::new(handle.input());
+    let first_id = handle.block(first.clone()).unwrap();
+    assert_eq!(handle.connect(&output, &first.input), Ok(true));
+    output.send(&1).unwrap();
+
+    let second = Collect::new(handle.input());
+    handle.block(second.clone()).unwrap();
+    assert_eq!(handle.connect(&output, &second.input), Ok(true));
+    output.send(&2).unwrap();
+
+    // The detached sink receives the end of its stream:
+    assert_eq!(handle.disconnect(&output, &first.input), Ok(true));
+    assert_eq!(handle.disconnect(&output, &first.input), Ok(false));
+    let process = handle.blocks().into_iter().find(|p| p.id() == first_id);
+    while process.as_ref().unwrap().is_alive() {
+        std::thread::yield_now();
+    }
+    assert!(handle.remove_block(first_id).is_ok());
+    assert_eq!(first.messages(), vec![1, 2]);
+    assert_eq!(handle.blocks().len(), 1);
+
+    output.send(&3).unwrap();
+    output.close().unwrap();
+    assert!(handle.join().is_ok());
+    assert_eq!(second.messages(), vec![2, 3]);
+    Ok(())
+}
+
+#[test]
+fn remove_running_block() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let sink = system.block(Collect::new(system.input()));
+    system.connect(&output, &sink.input);
+    let handle = system.spawn().unwrap();
+    let id = handle.blocks()[0].id();
+
+    output.send(&42).unwrap();
+    assert!(handle.remove_block(id).is_ok());
+    assert!(handle.blocks().is_empty());
+    assert!(sink.input.is_closed());
+    assert_eq!(output.state(), PortState::Open);
+    assert!(handle.remove_block(id).is_err());
+    Ok(())
+}
+
+#[test]
+fn remove_supervised_block() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let mut system = System::new(&runtime);
+    system.set_failure_policy(FailurePolicy::Restart);
+    let output = system.output::<i32>();
+    let sink = system.block_with_restart(Collect::new(system.input()), RestartPolicy::always());
+    system.connect(&output, &sink.input);
+    let handle = system.spawn().unwrap();
+    let id = handle.blocks()[0].id();
+
+    output.send(&42).unwrap();
+    // The block isn't restarted once its ports are closed:
+    assert!(handle.remove_block(id).is_ok());
+    assert!(handle.blocks().is_empty());
+    assert!(sink.input.is_closed());
+    Ok(())
+}
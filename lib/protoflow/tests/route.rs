@@ -0,0 +1,165 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{Route, RoutePredicate},
+    prelude::prost::encoding,
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    types::Timestamp,
+    ConnectionOptions, InputPort, MessageMetadata, System,
+};
+
+fn predicate(input: &str) -> RoutePredicate {
+    input.parse().unwrap()
+}
+
+#[test]
+fn match_values() -> Result<(), ()> {
+    let hello = String::from("hello");
+    assert!(predicate("value == \"hello\"").matches(&hello));
+    assert!(predicate("value != \"world\"").matches(&hello));
+    assert!(predicate("value < \"world\"").matches(&hello));
+    assert!(predicate("value matches \"^h.*o$\"").matches(&hello));
+    assert!(!predicate("value matches \"^world\"").matches(&hello));
+    assert!(predicate("value == \"\"").matches(&String::new()));
+
+    assert!(predicate("value == -42").matches(&-42i32));
+    assert!(predicate("value < 0 and value >= -100").matches(&-42i64));
+    assert!(predicate("value == 0").matches(&0u64)); // encoded as no field
+    assert!(predicate("value in 0.5..1.5").matches(&1.0f64));
+    assert!(!predicate("value in 0.5..1").matches(&1.0f32));
+    assert!(predicate("value in 0.5..=1").matches(&1.0f32));
+
+    // Mismatched types never match:
+    assert!(!predicate("value == 42").matches(&hello));
+    assert!(!predicate("value != 42").matches(&hello));
+    assert!(!predicate("value == \"42\"").matches(&42u64));
+    Ok(())
+}
+
+#[test]
+fn match_fields() -> Result<(), ()> {
+    let metadata = MessageMetadata::new()
+        .with_timestamp(Timestamp::date(2024, 1, 1).unwrap())
+        .with_sequence(7)
+        .with_correlation_id("request-42");
+    assert!(predicate("$2 in 5..10 and $3 matches \"-42$\"").matches(&metadata));
+    assert!(predicate("$1.1 > 1700000000").matches(&metadata));
+    assert!(predicate("$4 == \"\" && $1.2 == 0").matches(&metadata));
+    assert!(predicate("not ($2 == 1 || $3 == \"\")").matches(&metadata));
+    assert!(predicate("!false && (true)").matches(&metadata));
+    assert!(!predicate("$3.1 == 0").matches(&metadata)); // not a message
+    Ok(())
+}
+
+#[test]
+fn match_typed_fields() -> Result<(), ()> {
+    let mut message = vec![];
+    encoding::uint64::encode(1, &u64::MAX, &mut message);
+    encoding::sint32::encode(2, &-5, &mut message);
+    encoding::sint64::encode(3, &i64::MIN, &mut message);
+    encoding::fixed32::encode(4, &7, &mut message);
+    encoding::fixed64::encode(5, &u64::MAX, &mut message);
+    encoding::sfixed32::encode(6, &-7, &mut message);
+    encoding::sfixed64::encode(7, &-9, &mut message);
+    encoding::int32::encode(8, &-1, &mut message);
+    encoding::bool::encode(9, &true, &mut message);
+    encoding::float::encode(10, &0.5, &mut message);
+    let matches = |input: &str| predicate(input).matches_encoded(&message);
+
+    assert!(matches("value:uint64 == 18446744073709551615"));
+    assert!(matches("value:uint64 > 9223372036854775807"));
+    assert!(matches("value == -1")); // read as an int64 by default
+    assert!(matches("$2:sint32 == -5 and $2:sint64 in -10..0"));
+    assert!(matches("$3:sint64 == -9223372036854775808"));
+    assert!(matches(
+        "$4:fixed32 == 7 and $5:fixed64 == 18446744073709551615"
+    ));
+    assert!(matches("$6:sfixed32 == -7 and $7:sfixed64 == -9"));
+    assert!(matches("$8:int32 == -1 and $8:uint32 == 4294967295"));
+    assert!(matches("$9:bool == 1 and $10:float == 0.5 and $10 == 0.5"));
+    assert!(matches("$11:uint64 == 0 and $11:sint32 == 0")); // missing
+
+    // Fields whose wire type doesn't match their stated type never match:
+    assert!(!matches("$4:fixed64 == 7"));
+    assert!(!matches("$4:sint32 != 7"));
+    assert!(!matches("$1:double != 0"));
+    assert!(!matches("$1:string != \"\""));
+
+    let hello = String::from("hello");
+    assert!(predicate("value:string matches \"^h\"").matches(&hello));
+    assert!(!predicate("value:int64 != 0").matches(&hello));
+    Ok(())
+}
+
+#[test]
+fn reject_invalid_predicates() -> Result<(), ()> {
+    for input in [
+        "",
+        "value",
+        "value ==",
+        "value == 1 2",
+        "$0 == 1",
+        "$1 in 1",
+        "(value == 1",
+        "value == \"hello",
+        "value matches 1",
+        "value matches \"(\"",
+        "value = 1",
+        "value:uint128 == 1",
+        "value: == 1",
+        "value:uint64 == \"1\"",
+        "value:string == 1",
+        "value:string in 1..2",
+        "value:int32 matches \"1\"",
+        "value == 18446744073709551616",
+    ] {
+        assert!(input.parse::<RoutePredicate>().is_err(), "{}", input);
+    }
+    Ok(())
+}
+
+#[test]
+fn route_messages() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<String>();
+    let router = system.block(Route::<String>::with_params(
+        system.input(),
+        vec![system.output(), system.output(), system.output()],
+        system.output(),
+        vec![
+            ("errors".into(), predicate("value matches \"^E\"")),
+            ("warnings".into(), predicate("value matches \"^(E|W)\"")),
+            ("unused".into(), predicate("value matches \"^X\"")),
+        ],
+    ));
+    let options = ConnectionOptions::new().with_capacity(16);
+    let (errors, warnings, others) = (
+        system.input::<String>(),
+        system.input::<String>(),
+        system.input::<String>(),
+    );
+    system.connect_with(&output, &router.input, options);
+    system.connect_with(router.output("errors").unwrap(), &errors, options);
+    system.connect_with(router.output("warnings").unwrap(), &warnings, options);
+    system.connect_with(&router.default, &others, options);
+    let process = system.execute().unwrap();
+    for message in ["E: a", "W: b", "I: c", "X: d", "E: e"] {
+        output.send(&message.to_string()).unwrap();
+    }
+    output.close().unwrap();
+    process.join().unwrap();
+    let drain = |input: &InputPort<String>| {
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) = input.try_recv() {
+            messages.push(message);
+        }
+        messages
+    };
+    // The first matching route wins, and the unconnected route drops:
+    assert_eq!(drain(&errors), vec!["E: a", "E: e"]);
+    assert_eq!(drain(&warnings), vec!["W: b"]);
+    assert_eq!(drain(&others), vec!["I: c"]);
+    Ok(())
+}
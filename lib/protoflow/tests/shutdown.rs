@@ -0,0 +1,168 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::{Count, Delay, DelayType, Drop},
+    prelude::Vec,
+    runtimes::StdRuntime,
+    transports::MpscTransport,
+    Block, BlockDescriptor, BlockError, BlockResult, BlockRuntime, ConnectionOptions, OutputPort,
+    PortDescriptor, Process, ProcessID, Runtime, System,
+};
+use std::time::{Duration, Instant};
+
+/// A block that sends an endless stream of numbers.
+#[derive(Clone)]
+struct Counter {
+    output: OutputPort<u64>,
+}
+
+impl BlockDescriptor for Counter {
+    fn outputs(&self) -> Vec<PortDescriptor> {
+        vec![PortDescriptor::from(&self.output)]
+    }
+}
+
+impl Block for Counter {
+    fn execute(&mut self, runtime: &dyn BlockRuntime) -> BlockResult {
+        runtime.wait_for(&self.output)?;
+        for number in 0.. {
+            self.output.send(&number)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn shutdown_blocked_recv() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let mut runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&output, &blackhole.input);
+    let process = runtime.execute(system).unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(process.is_alive());
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn shutdown_blocked_sleep() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let mut runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let delay = system.block(Delay::with_params(
+        system.input(),
+        system.output(),
+        DelayType::Fixed(Duration::from_secs(3600)),
+    ));
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&output, &delay.input);
+    system.connect(&delay.output, &blackhole.input);
+    let process = runtime.execute(system).unwrap();
+    output.send(&42).unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    process.shutdown().unwrap();
+    assert!(matches!(process.join(), Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn shutdown_drains_buffered_messages() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let mut runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let delay = system.block(Delay::with_params(
+        system.input(),
+        system.output(),
+        DelayType::Fixed(Duration::from_millis(20)),
+    ));
+    let input = system.input::<i32>();
+    let options = ConnectionOptions::new().with_capacity(16);
+    system.connect_with(&output, &delay.input, options);
+    system.connect_with(&delay.output, &input, options);
+    let process = runtime.execute(system).unwrap();
+    for number in 1..=3 {
+        output.send(&number).unwrap();
+    }
+    output.close().unwrap();
+    assert!(process.terminate_timeout(Duration::from_secs(10)).is_ok());
+    let mut received = Vec::new();
+    while let Ok(Some(number)) = input.try_recv() {
+        received.push(number);
+    }
+    assert_eq!(received, vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn shutdown_stops_sources() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let mut runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let counter = system.block(Counter {
+        output: system.output(),
+    });
+    let count = system.block(Count::new(system.input(), system.output(), system.output()));
+    let input = system.input::<u64>();
+    system.connect(&counter.output, &count.input);
+    system.connect(&count.count, &input);
+    let process = runtime.execute(system).unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    let start = Instant::now();
+    let result = process.terminate_timeout(Duration::from_secs(10));
+    assert!(matches!(result, Err(BlockError::Terminated))); // only the source
+    assert!(start.elapsed() < Duration::from_secs(10));
+    // The downstream block drained the stream, and then reported its count:
+    assert!(input.try_recv().unwrap().is_some_and(|count| count > 0));
+    Ok(())
+}
+
+#[test]
+fn shutdown_after_timeout() -> Result<(), ()> {
+    let transport = MpscTransport::new();
+    let mut runtime = StdRuntime::new(transport).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let delay = system.block(Delay::with_params(
+        system.input(),
+        system.output(),
+        DelayType::Fixed(Duration::from_secs(3600)),
+    ));
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&output, &delay.input);
+    system.connect(&delay.output, &blackhole.input);
+    let process = runtime.execute(system).unwrap();
+    output.send(&42).unwrap();
+    let result = process.terminate_timeout(Duration::from_millis(50));
+    assert!(matches!(result, Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn shutdown_by_default() -> Result<(), ()> {
+    /// A process implementing only the required methods.
+    struct Finished;
+
+    impl Process for Finished {
+        fn id(&self) -> ProcessID {
+            0
+        }
+
+        fn is_alive(&self) -> bool {
+            false
+        }
+
+        fn join(&self) -> BlockResult {
+            Ok(())
+        }
+    }
+
+    assert!(Finished.shutdown().is_ok());
+    assert!(Finished.terminate().is_ok());
+    assert!(Finished.terminate_timeout(Duration::from_secs(1)).is_ok());
+    Ok(())
+}
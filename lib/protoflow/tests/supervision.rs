@@ -0,0 +1,68 @@
+// This is free and unencumbered software released into the public domain.
+
+mod common;
+
+use common::Flaky;
+use protoflow::{
+    runtimes::StdRuntime, transports::MpscTransport, BlockError, RestartPolicy, System,
+};
+use std::time::Duration;
+
+#[test]
+fn restart_on_failure() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let policy = RestartPolicy::on_failure()
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+    let flaky = system.block_with_restart(Flaky::new(3), policy);
+    assert!(system.execute().unwrap().join().is_ok());
+    assert_eq!(flaky.executions(), 4);
+    Ok(())
+}
+
+#[test]
+fn restart_at_most() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let policy = RestartPolicy::on_failure().with_max_restarts(2);
+    let flaky = system.block_with_restart(Flaky::new(5), policy);
+    let Err(BlockError::Failed(failures)) = system.execute().unwrap().join() else {
+        panic!("expected a failure report");
+    };
+    assert_eq!(failures.len(), 1);
+    assert_eq!(flaky.executions(), 3);
+    Ok(())
+}
+
+#[test]
+fn restart_always() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let policy = RestartPolicy::always().with_max_restarts(2);
+    let flaky = system.block_with_restart(Flaky::new(0), policy);
+    assert!(system.execute().unwrap().join().is_ok());
+    assert_eq!(flaky.executions(), 3);
+    Ok(())
+}
+
+#[test]
+fn restart_never() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let flaky = system.block_with_restart(Flaky::new(1), RestartPolicy::never());
+    assert!(system.execute().unwrap().join().is_err());
+    assert_eq!(flaky.executions(), 1);
+    Ok(())
+}
+
+#[test]
+fn exponential_backoff() -> Result<(), ()> {
+    let policy =
+        RestartPolicy::on_failure().with_backoff(Duration::from_secs(1), Duration::from_secs(5));
+    assert_eq!(policy.backoff_for(0), Duration::from_secs(1));
+    assert_eq!(policy.backoff_for(1), Duration::from_secs(2));
+    assert_eq!(policy.backoff_for(2), Duration::from_secs(4));
+    assert_eq!(policy.backoff_for(3), Duration::from_secs(5));
+    assert_eq!(policy.backoff_for(100), Duration::from_secs(5));
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{runtimes::StdRuntime, transports::MpscTransport, PortError, System};
+use std::time::{Duration, Instant};
+
+#[test]
+fn recv_timeout() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let timeout = Duration::from_millis(10);
+    assert_eq!(input.recv_timeout(timeout), Err(PortError::Timeout));
+    output.send(&42).unwrap();
+    assert_eq!(input.recv_timeout(timeout), Ok(Some(42)));
+    Ok(())
+}
+
+#[test]
+fn recv_deadline() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let deadline = Instant::now() + Duration::from_millis(10);
+    assert_eq!(input.recv_deadline(deadline), Err(PortError::Timeout));
+    assert!(Instant::now() >= deadline);
+    Ok(())
+}
+
+#[test]
+fn send_timeout() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    let timeout = Duration::from_millis(10);
+    assert_eq!(output.send_timeout(&1, timeout), Ok(())); // fills the channel
+    assert_eq!(output.send_timeout(&2, timeout), Err(PortError::Timeout));
+    let receiver = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        input.recv()
+    });
+    assert_eq!(output.send_timeout(&3, Duration::from_secs(10)), Ok(()));
+    assert_eq!(receiver.join().unwrap(), Ok(Some(1)));
+    Ok(())
+}
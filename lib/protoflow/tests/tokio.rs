@@ -0,0 +1,105 @@
+// This is free and unencumbered software released into the public domain.
+
+#![cfg(feature = "tokio")]
+
+use protoflow::{
+    blocks::{Const, Drop},
+    prelude::futures::{future::BoxFuture, FutureExt},
+    runtimes::{StdRuntime, TokioRuntime},
+    transports::MpscTransport,
+    AsyncBlock, BlockDescriptor, BlockError, BlockResult, BlockRuntime, InputPort, OutputPort,
+    RestartPolicy, Runtime, System,
+};
+
+#[derive(Clone)]
+struct Forward {
+    input: InputPort<i32>,
+    output: OutputPort<i32>,
+}
+
+impl BlockDescriptor for Forward {}
+
+impl AsyncBlock for Forward {
+    fn execute<'a>(&'a mut self, _runtime: &'a dyn BlockRuntime) -> BoxFuture<'a, BlockResult> {
+        Box::pin(async move {
+            while let Some(message) = self.input.recv_async().await? {
+                self.output.send_async(&message).await?;
+            }
+            self.output.close()?;
+            Ok(())
+        })
+    }
+}
+
+#[test]
+fn execute_on_tokio() -> Result<(), ()> {
+    let tokio = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let mut executor = TokioRuntime::new(&runtime, tokio.handle().clone()).unwrap();
+    let system = System::new(&runtime);
+    let constant = system.block(Const {
+        output: system.output(),
+        value: 42,
+    });
+    let blackhole = system.block(Drop::new(system.input()));
+    system.connect(&constant.output, &blackhole.input);
+    let process = executor.execute(system).unwrap();
+    process.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn execute_async_block() -> Result<(), ()> {
+    let tokio = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let executor = TokioRuntime::new(&runtime, tokio.handle().clone()).unwrap();
+    let system = System::new(&runtime);
+    let (output, input) = (system.output::<i32>(), system.input::<i32>());
+    let forward = Forward {
+        input: system.input(),
+        output: system.output(),
+    };
+    system.connect(&output, &forward.input);
+    system.connect(&forward.output, &input);
+    let process = executor.execute_async_block(Box::new(forward)).unwrap();
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn execute_async_block_in_system() -> Result<(), ()> {
+    let tokio = tokio::runtime::Builder::new_multi_thread().build().unwrap();
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let mut executor = TokioRuntime::new(&runtime, tokio.handle().clone()).unwrap();
+    let system = System::new(&runtime);
+    let (output, input) = (system.output::<i32>(), system.input::<i32>());
+    let forward = system.async_block_with_restart(
+        Forward {
+            input: system.input(),
+            output: system.output(),
+        },
+        RestartPolicy::on_failure(),
+    );
+    system.connect(&output, &forward.input);
+    system.connect(&forward.output, &input);
+    let process = executor.execute(system).unwrap();
+    output.send(&42).unwrap();
+    assert_eq!(input.recv(), Ok(Some(42)));
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
+
+#[test]
+fn recv_async_cancelled() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let (output, input) = (system.output::<i32>(), system.input::<i32>());
+    system.connect(&output, &input);
+    // Nothing to receive yet, so the receive is pending, and then dropped:
+    assert!(input.recv_async().now_or_never().is_none());
+    output.send(&42).unwrap();
+    assert_eq!(input.recv_async().now_or_never(), Some(Ok(Some(42))));
+    Ok(())
+}
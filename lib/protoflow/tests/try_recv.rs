@@ -0,0 +1,44 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    runtimes::StdRuntime, transports::MpscTransport, InputPorts, MessageReceiver, PortError, System,
+};
+
+#[test]
+fn try_recv_from_port() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    let mut input = system.input::<i32>();
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    system.connect(&output, &input);
+    assert_eq!(input.try_recv(), Ok(None));
+    output.send(&42).unwrap();
+    assert_eq!(input.try_recv(), Ok(Some(42)));
+    assert_eq!(input.try_recv(), Ok(None));
+    let closer = std::thread::spawn(move || output.close());
+    while input.try_recv() == Ok(None) {
+        std::thread::yield_now();
+    }
+    assert_eq!(closer.join().unwrap(), Ok(true));
+    assert_eq!(input.try_recv(), Err(PortError::Disconnected));
+    input.close().unwrap();
+    assert_eq!(input.try_recv(), Err(PortError::Closed));
+    Ok(())
+}
+
+#[test]
+fn try_recv_from_ports() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let outputs = [system.output::<i32>(), system.output::<i32>()];
+    let inputs = InputPorts::<i32, 2>::new(&system);
+    assert_eq!(inputs.try_recv(), Err(PortError::Disconnected));
+    system.connect(&outputs[0], &inputs[0]);
+    system.connect(&outputs[1], &inputs[1]);
+    assert_eq!(inputs.try_recv(), Ok(None));
+    outputs[1].send(&42).unwrap();
+    assert_eq!(inputs.try_recv(), Ok(Some(42)));
+    assert_eq!(inputs.try_recv(), Ok(None));
+    Ok(())
+}
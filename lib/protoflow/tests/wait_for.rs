@@ -0,0 +1,83 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    blocks::Const, runtimes::StdRuntime, transports::MpscTransport, BlockError, BlockRuntime, Port,
+    PortID, System, Transport,
+};
+use std::time::Duration;
+
+#[test]
+fn wait_for_connected() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let input = system.input::<i32>();
+    system.connect(&output, &input);
+    assert!(runtime.wait_for(&output).is_ok());
+    assert!(runtime.wait_for(&input).is_ok());
+    Ok(())
+}
+
+#[test]
+fn wait_for_timeout() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<i32>();
+    let timeout = Duration::from_millis(10);
+    assert!(matches!(
+        runtime.wait_for_timeout(&output, timeout),
+        Ok(false)
+    ));
+    Ok(())
+}
+
+#[test]
+fn wait_for_closed() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<i32>();
+    output.close().unwrap();
+    assert!(runtime.wait_for(&output).is_err());
+    Ok(())
+}
+
+#[test]
+fn wait_for_later_connection() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let constant = system.block(Const {
+        output: system.output(),
+        value: 42,
+    });
+    let input = system.input::<i32>();
+    let (Some(PortID::Output(source)), Some(PortID::Input(target))) =
+        (constant.output.id(), input.id())
+    else {
+        unreachable!()
+    };
+    let process = system.execute().unwrap();
+    let transport = runtime.transport().clone();
+    let connector = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10)); // while the block waits
+        transport.connect(source, target).unwrap();
+    });
+    assert_eq!(input.recv(), Ok(Some(42)));
+    connector.join().unwrap();
+    process.join().unwrap();
+    Ok(())
+}
+
+#[test]
+fn wait_for_shutdown() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let _ = system.block(Const {
+        output: system.output(),
+        value: 42,
+    });
+    let process = system.execute().unwrap();
+    std::thread::sleep(Duration::from_millis(10)); // while the block waits
+    assert!(process.is_alive());
+    assert!(matches!(process.terminate(), Err(BlockError::Terminated)));
+    Ok(())
+}
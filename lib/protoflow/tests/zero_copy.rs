@@ -0,0 +1,62 @@
+// This is free and unencumbered software released into the public domain.
+
+use protoflow::{
+    prelude::Bytes,
+    runtimes::StdRuntime,
+    transports::{MockTransport, MpscTransport},
+    ConnectionOptions, System, Transport,
+};
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn send_by_reference() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<Bytes>();
+    let input = system.input::<Bytes>();
+    assert!(system.connect(&output, &input));
+    assert!(runtime.transport().is_zero_copy());
+    let message = Arc::new(Bytes::from(vec![0xAB; 1 << 20]));
+    output.send_shared(message.clone()).unwrap();
+    let received = input.recv_shared().unwrap().unwrap();
+    assert!(Arc::ptr_eq(&message, &received));
+    output.close().unwrap();
+    assert_eq!(input.recv_shared(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn send_without_encoding() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MpscTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let mut output = system.output::<Bytes>();
+    let input = system.input::<Bytes>();
+    let options = ConnectionOptions::new().with_capacity(2);
+    assert!(system.connect_with(&output, &input, options));
+    let message = Bytes::from(vec![0xCD; 1 << 20]);
+    output.send(&message).unwrap();
+    output
+        .send_timeout(&Bytes::from_static(b"tile"), Duration::from_millis(10))
+        .unwrap();
+    output.close().unwrap();
+    let received = input.recv().unwrap().unwrap();
+    assert_eq!(received.as_ptr(), message.as_ptr()); // not copied
+    assert_eq!(input.try_recv(), Ok(Some(Bytes::from_static(b"tile"))));
+    assert_eq!(input.recv(), Ok(None));
+    Ok(())
+}
+
+#[test]
+fn fall_back_to_encoding() -> Result<(), ()> {
+    let runtime = StdRuntime::new(MockTransport::new()).unwrap();
+    let system = System::new(&runtime);
+    let output = system.output::<String>();
+    let input = system.input::<String>();
+    assert!(system.connect(&output, &input));
+    let transport = runtime.transport();
+    assert!(!transport.is_zero_copy());
+    output.send_shared(Arc::new("Hello".into())).unwrap();
+    assert_eq!(transport.sent(&output), Ok(vec!["Hello".to_string()]));
+    assert_eq!(input.recv_shared(), Ok(Some(Arc::new("Hello".into()))));
+    Ok(())
+}